@@ -3,14 +3,20 @@ use std::sync::Arc;
 use crate::session::handle::SessionHandle;
 use crate::{
     common::{
-        action::{ActionSource, KeyAction, PointerAction, PointerActionType},
+        action::{ActionSource, KeyAction, PointerAction, PointerActionType, PointerOrigin},
         command::{Actions, Command},
         keys::TypingData,
     },
-    error::WebDriverResult,
-    WebElement,
+    error::{WebDriverError, WebDriverErrorInfo, WebDriverResult},
+    ElementId, WebElement,
 };
 
+/// The ids of the default keyboard and mouse input sources.
+const RESERVED_IDS: [&str; 2] = ["key", "pointer"];
+
+/// The largest coordinate accepted by the WebDriver spec, `Number.MAX_SAFE_INTEGER`.
+const MAX_COORDINATE: u64 = (1 << 53) - 1;
+
 /// The ActionChain struct allows you to perform multiple input actions in
 /// a sequence, including drag-and-drop, send keystrokes to an element, and
 /// hover the mouse over an element.
@@ -27,6 +33,111 @@ pub struct ActionChain {
     handle: Arc<SessionHandle>,
     key_actions: ActionSource<KeyAction>,
     pointer_actions: ActionSource<PointerAction>,
+    extra_pointers: Vec<ActionSource<PointerAction>>,
+    descriptions: Vec<ActionDescription>,
+    /// Why an additional pointer was rejected, if one was. This is reported by `perform()`.
+    invalid_pointer: Option<String>,
+}
+
+/// A human-readable description of one action added to an [`ActionChain`], used to
+/// report which action failed.
+#[derive(Debug)]
+struct ActionDescription {
+    description: String,
+    kind: ActionKind,
+    element: Option<ElementId>,
+    /// Why the WebDriver server is certain to reject this action, if it is.
+    invalid: Option<InvalidAction>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ActionKind {
+    Key,
+    Move,
+    Button,
+}
+
+/// An action that is known to be invalid before it is sent.
+#[derive(Debug)]
+enum InvalidAction {
+    /// The server would return `InvalidArgument`.
+    Argument(String),
+    /// The server would return `MoveTargetOutOfBounds`.
+    OutOfBounds(String),
+}
+
+impl InvalidAction {
+    /// Check that a move to the specified coordinates can succeed.
+    fn check_move(origin: &PointerOrigin, x: i64, y: i64) -> Option<Self> {
+        if x.unsigned_abs() > MAX_COORDINATE || y.unsigned_abs() > MAX_COORDINATE {
+            let message =
+                format!("coordinates must be between -{MAX_COORDINATE} and {MAX_COORDINATE}");
+            return Some(InvalidAction::Argument(message));
+        }
+        match origin {
+            PointerOrigin::Viewport if x < 0 || y < 0 => Some(InvalidAction::OutOfBounds(
+                "viewport coordinates cannot be negative".to_string(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The error to return for this action, at the specified index.
+    fn error(&self, index: usize, description: &str) -> WebDriverError {
+        let prefix = format!("action {index} ({description}) failed");
+        match self {
+            InvalidAction::Argument(message) => WebDriverError::InvalidArgument(
+                WebDriverErrorInfo::new(format!("{prefix}: {message}")),
+            ),
+            InvalidAction::OutOfBounds(message) => WebDriverError::MoveTargetOutOfBounds(
+                WebDriverErrorInfo::new(format!("{prefix}: {message}")),
+            ),
+        }
+    }
+}
+
+/// The actions for a single tick of an [`ActionChain`], for use with
+/// [`ActionChain::tick`].
+///
+/// Actions added to different pointers within the same tick are performed
+/// simultaneously.
+#[derive(Debug)]
+pub struct PointerTick<'a> {
+    pointers: &'a mut Vec<ActionSource<PointerAction>>,
+    ticks: usize,
+    invalid_pointer: &'a mut Option<String>,
+    /// Receives the actions for a pointer with a reserved id, which are discarded.
+    rejected: ActionSource<PointerAction>,
+}
+
+impl PointerTick<'_> {
+    /// Get the pointer with the specified id, so that actions can be added to it.
+    ///
+    /// If no pointer with this id has been added via [`ActionChain::add_pointer`],
+    /// a new touch pointer is added. The ids `"key"` and `"pointer"` are reserved for the
+    /// default input sources, so using either of them makes [`ActionChain::perform`]
+    /// return a `WebDriverError::InvalidArgument` error.
+    pub fn pointer(&mut self, id: &str) -> &mut ActionSource<PointerAction> {
+        if RESERVED_IDS.contains(&id) {
+            self.invalid_pointer.get_or_insert_with(|| reserved_id_message(id));
+            return &mut self.rejected;
+        }
+        let index = match self.pointers.iter().position(|p| p.id() == id) {
+            Some(index) => index,
+            None => {
+                let mut pointer = ActionSource::<PointerAction>::new(id, PointerActionType::Touch);
+                pointer.pad_to(self.ticks);
+                self.pointers.push(pointer);
+                self.pointers.len() - 1
+            }
+        };
+        &mut self.pointers[index]
+    }
+}
+
+/// The error message for an additional pointer with a reserved id.
+fn reserved_id_message(id: &str) -> String {
+    format!("pointer id {id:?} is reserved for the default input sources")
 }
 
 impl ActionChain {
@@ -42,7 +153,252 @@ impl ActionChain {
                 "pointer",
                 PointerActionType::Mouse,
             ),
+            extra_pointers: Vec::new(),
+            descriptions: Vec::new(),
+            invalid_pointer: None,
+        }
+    }
+
+    /// Record the description of an action, for error reporting.
+    fn describe(&mut self, kind: ActionKind, description: String, element: Option<&ElementId>) {
+        self.descriptions.push(ActionDescription {
+            description,
+            kind,
+            element: element.cloned(),
+            invalid: None,
+        });
+    }
+
+    /// Record the description of a move action, checking its coordinates.
+    fn describe_move(&mut self, origin: PointerOrigin, x: i64, y: i64, description: String) {
+        let invalid = InvalidAction::check_move(&origin, x, y);
+        let element = match origin {
+            PointerOrigin::WebElement(id) => Some(id),
+            _ => None,
+        };
+        self.descriptions.push(ActionDescription {
+            description,
+            kind: ActionKind::Move,
+            element,
+            invalid,
+        });
+    }
+
+    /// Record a description of each action added to the additional pointers since the
+    /// specified tick. Pauses are not described, because they cannot fail.
+    fn describe_pointer_actions(&mut self, tick: usize) {
+        for pointer in &self.extra_pointers {
+            let id = pointer.id();
+            for action in pointer.actions_from(tick) {
+                let invalid = match action {
+                    PointerAction::PointerMove {
+                        origin,
+                        x,
+                        y,
+                        ..
+                    } => InvalidAction::check_move(origin, *x, *y),
+                    _ => None,
+                };
+                let (kind, description, element) = match action {
+                    PointerAction::Pause {
+                        ..
+                    } => continue,
+                    PointerAction::PointerMove {
+                        origin,
+                        x,
+                        y,
+                        ..
+                    } => match origin {
+                        PointerOrigin::Viewport => {
+                            (ActionKind::Move, format!("move to ({x}, {y})"), None)
+                        }
+                        PointerOrigin::Pointer => {
+                            (ActionKind::Move, format!("move by ({x}, {y})"), None)
+                        }
+                        PointerOrigin::WebElement(e) => {
+                            (ActionKind::Move, format!("move to element {e}"), Some(e))
+                        }
+                    },
+                    PointerAction::PointerDown {
+                        ..
+                    } => (ActionKind::Button, "press".to_string(), None),
+                    PointerAction::PointerUp {
+                        ..
+                    } => (ActionKind::Button, "release".to_string(), None),
+                    PointerAction::PointerCancel => {
+                        (ActionKind::Button, "cancel".to_string(), None)
+                    }
+                };
+                self.descriptions.push(ActionDescription {
+                    description: format!("{id} {description}"),
+                    kind,
+                    element: element.cloned(),
+                    invalid,
+                });
+            }
+        }
+    }
+
+    /// Find the index of the action that most likely caused the specified error.
+    ///
+    /// The WebDriver server does not report which action failed, so this looks for
+    /// an element id mentioned in the error, or otherwise the only action that could
+    /// have caused this kind of error. Any other error is not attributed to an action.
+    fn failed_action(&self, error: &WebDriverError) -> Option<usize> {
+        let kind = match error {
+            WebDriverError::MoveTargetOutOfBounds(_) => Some(ActionKind::Move),
+            _ => None,
+        };
+        let needs_element = matches!(
+            error,
+            WebDriverError::NoSuchElement(_) | WebDriverError::StaleElementReference(_)
+        );
+        let details = error.to_string();
+
+        let mentioned = self
+            .descriptions
+            .iter()
+            .position(|d| d.element.as_ref().is_some_and(|id| details.contains(&id.to_string())));
+        if mentioned.is_some() {
+            return mentioned;
+        }
+
+        let mut candidates = self.descriptions.iter().enumerate().filter(|(_, d)| {
+            (needs_element && d.element.is_some()) || kind.as_ref() == Some(&d.kind)
+        });
+        match (candidates.next(), candidates.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// The current number of ticks, i.e. the length of the longest action source.
+    fn ticks(&self) -> usize {
+        self.extra_pointers
+            .iter()
+            .map(|p| p.tick_count())
+            .chain([self.key_actions.tick_count(), self.pointer_actions.tick_count()])
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Pad all action sources with pauses so that they have the same number of ticks.
+    fn sync_ticks(&mut self) {
+        let ticks = self.ticks();
+        self.key_actions.pad_to(ticks);
+        self.pointer_actions.pad_to(ticks);
+        for pointer in &mut self.extra_pointers {
+            pointer.pad_to(ticks);
+        }
+    }
+
+    /// Add an additional touch pointer with the specified id, for multi-touch gestures
+    /// such as pinch and zoom.
+    ///
+    /// Actions for additional pointers are added via [`ActionChain::tick`]. The id must
+    /// be unique, and must not be `"key"` or `"pointer"`, which are used by the default
+    /// keyboard and mouse input sources. Otherwise [`ActionChain::perform`] returns a
+    /// `WebDriverError::InvalidArgument` error without performing any actions.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// // Two-finger pinch.
+    /// driver
+    ///     .action_chain()
+    ///     .add_pointer("finger1")
+    ///     .add_pointer("finger2")
+    ///     .tick(|t| {
+    ///         t.pointer("finger1").move_to(100, 200);
+    ///         t.pointer("finger2").move_to(300, 200);
+    ///     })
+    ///     .tick(|t| {
+    ///         t.pointer("finger1").click_and_hold();
+    ///         t.pointer("finger2").click_and_hold();
+    ///     })
+    ///     .tick(|t| {
+    ///         t.pointer("finger1").move_to(180, 200);
+    ///         t.pointer("finger2").move_to(220, 200);
+    ///     })
+    ///     .tick(|t| {
+    ///         t.pointer("finger1").release();
+    ///         t.pointer("finger2").release();
+    ///     })
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn add_pointer(mut self, id: &str) -> Self {
+        if RESERVED_IDS.contains(&id) {
+            self.invalid_pointer.get_or_insert_with(|| reserved_id_message(id));
+        } else if self.extra_pointers.iter().any(|p| p.id() == id) {
+            self.invalid_pointer.get_or_insert_with(|| format!("pointer id {id:?} already added"));
+        } else {
+            let ticks = self.ticks();
+            let mut pointer = ActionSource::<PointerAction>::new(id, PointerActionType::Touch);
+            pointer.pad_to(ticks);
+            self.extra_pointers.push(pointer);
+        }
+        self
+    }
+
+    /// Add actions for one or more additional pointers that will be performed in the
+    /// same tick, i.e. simultaneously.
+    ///
+    /// All input sources are padded with pauses so that the actions added here line
+    /// up with each other, and with any actions added before or after this tick.
+    /// If one pointer is given more than one action, the other input sources pause
+    /// until all of them have completed.
+    ///
+    /// When reporting a failed action (see [`ActionChain::perform`]), each action added
+    /// here, other than a pause, counts as one action, in the order the pointers were
+    /// added.
+    ///
+    /// See [`ActionChain::add_pointer`] for an example.
+    pub fn tick<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut PointerTick<'_>),
+    {
+        self.sync_ticks();
+        let ticks = self.ticks();
+        f(&mut PointerTick {
+            pointers: &mut self.extra_pointers,
+            ticks,
+            invalid_pointer: &mut self.invalid_pointer,
+            rejected: ActionSource::<PointerAction>::new("rejected", PointerActionType::Touch),
+        });
+        self.sync_ticks();
+        self.describe_pointer_actions(ticks);
+        self
+    }
+
+    fn actions_json(&self) -> serde_json::Value {
+        if self.extra_pointers.is_empty() {
+            return serde_json::json!([self.key_actions, self.pointer_actions]);
+        }
+
+        // Pad all sources to the same length so that every tick lines up.
+        let ticks = self.ticks();
+        let mut key_actions = self.key_actions.clone();
+        key_actions.pad_to(ticks);
+        let mut pointer_actions = self.pointer_actions.clone();
+        pointer_actions.pad_to(ticks);
+        let mut sources = vec![serde_json::json!(key_actions), serde_json::json!(pointer_actions)];
+        for pointer in &self.extra_pointers {
+            let mut pointer = pointer.clone();
+            pointer.pad_to(ticks);
+            sources.push(serde_json::json!(pointer));
         }
+        serde_json::Value::Array(sources)
     }
 
     /// Reset all actions, reverting all input devices to default states.
@@ -78,10 +434,49 @@ impl ActionChain {
 
     /// Perform the action sequence. No actions are actually performed until
     /// this method is called.
+    ///
+    /// If the WebDriver server rejects the sequence and the failing action can be
+    /// identified, the error message is prefixed with its zero-based index and a
+    /// description, e.g. `action 3 (move to element X) failed`. Actions are numbered
+    /// in the order the methods of this struct were called, with methods such as
+    /// `click_element()` counting as each of the actions they are made of.
+    ///
+    /// Actions that the server is certain to reject are reported before anything is
+    /// sent: a move with coordinates outside the range allowed by the WebDriver spec
+    /// returns `InvalidArgument`, and a move to negative viewport coordinates returns
+    /// `MoveTargetOutOfBounds`, both with the index of the first such action.
+    ///
+    /// **For errors returned by the server, this is best-effort.** The WebDriver server
+    /// does not say which action failed, so the action is only identified when the error
+    /// mentions one of the elements used, or when only one action could have caused that
+    /// kind of error (such as the only move in a chain that fails with
+    /// `MoveTargetOutOfBounds`). Otherwise the error is returned unchanged.
     pub async fn perform(&self) -> WebDriverResult<()> {
-        let actions = Actions::from(serde_json::json!([self.key_actions, self.pointer_actions]));
-        self.handle.cmd(Command::PerformActions(actions)).await?;
-        Ok(())
+        if let Some(message) = &self.invalid_pointer {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(message.clone())));
+        }
+        let invalid = self.descriptions.iter().enumerate().find_map(|(index, d)| {
+            d.invalid.as_ref().map(|invalid| invalid.error(index, &d.description))
+        });
+        if let Some(e) = invalid {
+            return Err(e);
+        }
+        let actions = Actions::from(self.actions_json());
+        match self.handle.cmd(Command::PerformActions(actions)).await {
+            Ok(_) => Ok(()),
+            Err(mut e) => {
+                if let Some(index) = self.failed_action(&e) {
+                    let description = &self.descriptions[index].description;
+                    if let Some(info) = e.info_mut() {
+                        info.value.message = format!(
+                            "action {index} ({description}) failed: {}",
+                            info.value.message
+                        );
+                    }
+                }
+                Err(e)
+            }
+        }
     }
 
     /// Click and release the left mouse button.
@@ -107,6 +502,7 @@ impl ActionChain {
     /// ```
     pub fn click(mut self) -> Self {
         self.pointer_actions.click();
+        self.describe(ActionKind::Button, "click".to_string(), None);
         // Click = 2 actions (PointerDown + PointerUp).
         self.key_actions.pause();
         self.key_actions.pause();
@@ -165,6 +561,7 @@ impl ActionChain {
     pub fn click_and_hold(mut self) -> Self {
         self.pointer_actions.click_and_hold();
         self.key_actions.pause();
+        self.describe(ActionKind::Button, "click and hold".to_string(), None);
         self
     }
 
@@ -220,6 +617,7 @@ impl ActionChain {
     /// ```
     pub fn context_click(mut self) -> Self {
         self.pointer_actions.context_click();
+        self.describe(ActionKind::Button, "context click".to_string(), None);
         // Click = 2 actions (PointerDown + PointerUp).
         self.key_actions.pause();
         self.key_actions.pause();
@@ -274,6 +672,7 @@ impl ActionChain {
     /// ```
     pub fn double_click(mut self) -> Self {
         self.pointer_actions.double_click();
+        self.describe(ActionKind::Button, "double click".to_string(), None);
         // Each click = 2 actions (PointerDown + PointerUp).
         for _ in 0..4 {
             self.key_actions.pause();
@@ -330,6 +729,33 @@ impl ActionChain {
 
     /// Press the specified key down.
     ///
+    /// The key stays pressed until a matching `key_up()`, including during any pointer
+    /// actions added in between, so a modifier can be held across several clicks:
+    ///
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         let row1 = driver.find(By::Id("row1")).await?;
+    /// #         let row2 = driver.find(By::Id("row2")).await?;
+    /// driver
+    ///     .action_chain()
+    ///     .key_down(Key::Shift)
+    ///     .click_element(&row1)
+    ///     .click_element(&row2)
+    ///     .key_up(Key::Shift)
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -354,8 +780,10 @@ impl ActionChain {
     where
         T: Into<char>,
     {
-        self.key_actions.key_down(value.into());
+        let value = value.into();
+        self.key_actions.key_down(value);
         self.pointer_actions.pause();
+        self.describe(ActionKind::Key, format!("key down {value:?}"), None);
         self
     }
 
@@ -420,8 +848,10 @@ impl ActionChain {
     where
         T: Into<char>,
     {
-        self.key_actions.key_up(value.into());
+        let value = value.into();
+        self.key_actions.key_up(value);
         self.pointer_actions.pause();
+        self.describe(ActionKind::Key, format!("key up {value:?}"), None);
         self
     }
 
@@ -488,6 +918,7 @@ impl ActionChain {
     pub fn move_to(mut self, x: i64, y: i64) -> Self {
         self.pointer_actions.move_to(x, y);
         self.key_actions.pause();
+        self.describe_move(PointerOrigin::Viewport, x, y, format!("move to ({x}, {y})"));
         self
     }
 
@@ -523,6 +954,8 @@ impl ActionChain {
     pub fn move_by_offset(mut self, x_offset: i64, y_offset: i64) -> Self {
         self.pointer_actions.move_by(x_offset, y_offset);
         self.key_actions.pause();
+        let description = format!("move by ({x_offset}, {y_offset})");
+        self.describe_move(PointerOrigin::Pointer, x_offset, y_offset, description);
         self
     }
 
@@ -553,6 +986,8 @@ impl ActionChain {
     pub fn move_to_element_center(mut self, element: &WebElement) -> Self {
         self.pointer_actions.move_to_element_center(element.element_id.clone());
         self.key_actions.pause();
+        let id = &element.element_id;
+        self.describe(ActionKind::Move, format!("move to element {id}"), Some(id));
         self
     }
 
@@ -600,6 +1035,9 @@ impl ActionChain {
     ) -> Self {
         self.pointer_actions.move_to_element(element.element_id.clone(), x_offset, y_offset);
         self.key_actions.pause();
+        let id = element.element_id.clone();
+        let description = format!("move to element {id} with offset ({x_offset}, {y_offset})");
+        self.describe_move(PointerOrigin::WebElement(id), x_offset, y_offset, description);
         self
     }
 
@@ -629,6 +1067,7 @@ impl ActionChain {
     pub fn release(mut self) -> Self {
         self.pointer_actions.release();
         self.key_actions.pause();
+        self.describe(ActionKind::Button, "release".to_string(), None);
         self
     }
 
@@ -733,3 +1172,280 @@ impl ActionChain {
         self.click_element(element).send_keys(text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::{test_handle, MockClient, Reply};
+    use crate::Key;
+    use serde_json::json;
+
+    /// Rejects every action sequence with the specified error and message.
+    fn rejecting_handle(error: &'static str, message: &str) -> Arc<SessionHandle> {
+        Arc::new(MockClient::new().reply("", Reply::error(400, error, message))).handle()
+    }
+
+    #[tokio::test]
+    async fn test_perform_reports_failed_action() {
+        let handle = rejecting_handle("move target out of bounds", "move target out of bounds");
+        let elem = WebElement::from_reference(handle.clone(), "elem-1234");
+        let err = ActionChain::new(handle)
+            .key_down(Key::Shift)
+            .click()
+            .key_up(Key::Shift)
+            .move_to_element_center(&elem)
+            .click()
+            .perform()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebDriverError::MoveTargetOutOfBounds(_)), "{err:?}");
+        let message = err.to_string();
+        assert!(message.contains("action 3 (move to element elem-1234) failed"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_perform_reports_element_in_error() {
+        let handle = rejecting_handle("stale element reference", "stale element: elem-2");
+        let first = WebElement::from_reference(handle.clone(), "elem-1");
+        let second = WebElement::from_reference(handle.clone(), "elem-2");
+        let err = ActionChain::new(handle)
+            .click_element(&first)
+            .drag_and_drop_element(&first, &second)
+            .perform()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebDriverError::StaleElementReference(_)), "{err:?}");
+        let message = err.to_string();
+        assert!(message.contains("action 4 (move to element elem-2) failed"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_perform_reports_invalid_action() {
+        let client = Arc::new(MockClient::new().reply("", Reply::null()));
+        let elem = WebElement::from_reference(client.handle(), "elem-1");
+        let err = ActionChain::new(client.handle())
+            .move_to(10, 10)
+            .click()
+            .move_to_element_with_offset(&elem, i64::MAX, 0)
+            .click()
+            .perform()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebDriverError::InvalidArgument(_)), "{err:?}");
+        let message = err.to_string();
+        assert!(message.contains("action 2 (move to element elem-1 with offset"), "{message}");
+
+        let err = ActionChain::new(client.handle())
+            .move_by_offset(-5, -5)
+            .add_pointer("finger1")
+            .tick(|t| {
+                t.pointer("finger1").move_to(-1, 10);
+            })
+            .perform()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebDriverError::MoveTargetOutOfBounds(_)), "{err:?}");
+        let message = err.to_string();
+        assert!(message.contains("action 1 (finger1 move to (-1, 10)) failed"), "{message}");
+
+        // Nothing is sent for an invalid chain.
+        assert_eq!(client.count(""), 0);
+    }
+
+    #[tokio::test]
+    async fn test_perform_leaves_unattributed_error() {
+        let handle = rejecting_handle("invalid argument", "invalid argument");
+        let err = ActionChain::new(handle).move_to(1, 1).move_to(2, 2).perform().await.unwrap_err();
+        assert!(!err.to_string().contains("failed:"), "{err}");
+    }
+
+    #[test]
+    fn test_single_pointer_json() {
+        let chain = ActionChain::new(test_handle()).move_to(10, 20).click();
+        let actions = chain.actions_json();
+        let sources = actions.as_array().unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0]["id"], "key");
+        assert_eq!(sources[1]["id"], "pointer");
+        assert_eq!(sources[1]["parameters"]["pointerType"], "mouse");
+    }
+
+    #[test]
+    fn test_two_finger_pinch_json() {
+        let chain = ActionChain::new(test_handle())
+            .add_pointer("finger1")
+            .add_pointer("finger2")
+            .tick(|t| {
+                t.pointer("finger1").move_to(100, 200);
+                t.pointer("finger2").move_to(300, 200);
+            })
+            .tick(|t| {
+                t.pointer("finger1").click_and_hold();
+                t.pointer("finger2").click_and_hold();
+            })
+            .tick(|t| {
+                t.pointer("finger1").move_to(180, 200);
+                t.pointer("finger2").move_to(220, 200);
+            })
+            .tick(|t| {
+                t.pointer("finger1").release();
+                t.pointer("finger2").release();
+            });
+
+        let actions = chain.actions_json();
+        let sources = actions.as_array().unwrap();
+        assert_eq!(sources.len(), 4);
+        for source in sources {
+            assert_eq!(source["actions"].as_array().unwrap().len(), 4);
+        }
+
+        // The default sources just pause.
+        assert!(sources[0]["actions"].as_array().unwrap().iter().all(|a| a["type"] == "pause"));
+        assert!(sources[1]["actions"].as_array().unwrap().iter().all(|a| a["type"] == "pause"));
+
+        let finger1 = &sources[2];
+        assert_eq!(finger1["id"], "finger1");
+        assert_eq!(finger1["type"], "pointer");
+        assert_eq!(finger1["parameters"], json!({"pointerType": "touch"}));
+        let finger2 = &sources[3];
+        assert_eq!(finger2["id"], "finger2");
+        assert_eq!(finger2["parameters"], json!({"pointerType": "touch"}));
+
+        let types = |source: &serde_json::Value| -> Vec<String> {
+            source["actions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| a["type"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let expected = vec!["pointerMove", "pointerDown", "pointerMove", "pointerUp"];
+        assert_eq!(types(finger1), expected);
+        assert_eq!(types(finger2), expected);
+        assert_eq!(finger1["actions"][2]["x"], 180);
+        assert_eq!(finger2["actions"][2]["x"], 220);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_pointer_ids() {
+        let client = Arc::new(MockClient::new().reply("", Reply::null()));
+        let chains = [
+            ActionChain::new(client.handle()).add_pointer("key"),
+            ActionChain::new(client.handle()).add_pointer("pointer"),
+            ActionChain::new(client.handle()).add_pointer("finger1").add_pointer("finger1"),
+            ActionChain::new(client.handle()).tick(|t| {
+                t.pointer("pointer").move_to(1, 1);
+            }),
+        ];
+        for chain in chains {
+            let err = chain.perform().await.unwrap_err();
+            assert!(matches!(err, WebDriverError::InvalidArgument(_)), "{err:?}");
+        }
+        assert_eq!(client.count(""), 0);
+
+        // Pointers added by a tick can be used again in later ticks.
+        ActionChain::new(client.handle())
+            .add_pointer("finger1")
+            .tick(|t| {
+                t.pointer("finger1").move_to(1, 1);
+                t.pointer("finger2").move_to(2, 2);
+            })
+            .tick(|t| {
+                t.pointer("finger2").move_to(3, 3);
+            })
+            .perform()
+            .await
+            .unwrap();
+        assert_eq!(client.count(""), 1);
+    }
+
+    #[tokio::test]
+    async fn test_perform_reports_failed_pointer_action() {
+        let handle = rejecting_handle("stale element reference", "stale element: elem-2");
+        let elem = WebElement::from_reference(handle.clone(), "elem-2");
+        let id = elem.element_id();
+        let err = ActionChain::new(handle)
+            .click()
+            .add_pointer("finger1")
+            .add_pointer("finger2")
+            .tick(|t| {
+                t.pointer("finger1").move_to(1, 1);
+                t.pointer("finger1").pause_for(100);
+                t.pointer("finger2").move_to_element_center(id.clone());
+            })
+            .tick(|t| {
+                t.pointer("finger1").click_and_hold();
+            })
+            .perform()
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("action 2 (finger2 move to element elem-2) failed"), "{message}");
+    }
+
+    #[test]
+    fn test_pointer_ticks_align_with_mouse_actions() {
+        let chain = ActionChain::new(test_handle())
+            .move_to(10, 10)
+            .tick(|t| {
+                t.pointer("finger1").move_to(50, 50);
+            })
+            .click();
+
+        let actions = chain.actions_json();
+        let sources = actions.as_array().unwrap();
+        assert_eq!(sources.len(), 3);
+        let finger1 = sources[2]["actions"].as_array().unwrap();
+        assert_eq!(finger1.len(), 4);
+        assert_eq!(finger1[0]["type"], "pause");
+        assert_eq!(finger1[1]["type"], "pointerMove");
+        assert_eq!(finger1[2]["type"], "pause");
+        assert_eq!(finger1[3]["type"], "pause");
+        assert_eq!(sources[1]["actions"][1]["type"], "pause");
+    }
+
+    #[test]
+    fn test_key_held_across_clicks() {
+        let handle = test_handle();
+        let rows: Vec<_> = ["a", "b", "c"]
+            .iter()
+            .map(|id| WebElement::from_reference(handle.clone(), *id))
+            .collect();
+        let chain = ActionChain::new(handle)
+            .key_down(Key::Shift)
+            .click_element(&rows[0])
+            .click_element(&rows[1])
+            .click_element(&rows[2])
+            .key_up(Key::Shift);
+
+        let actions = chain.actions_json();
+        let types = |index: usize| -> Vec<String> {
+            actions[index]["actions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| a["type"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let keys = types(0);
+        let pointer = types(1);
+        assert_eq!(keys.len(), pointer.len());
+
+        // Shift is pressed in the first tick and only released in the last tick.
+        let shift = char::from(Key::Shift).to_string();
+        assert_eq!(keys.first().map(String::as_str), Some("keyDown"));
+        assert_eq!(keys.last().map(String::as_str), Some("keyUp"));
+        assert!(keys[1..keys.len() - 1].iter().all(|t| t == "pause"));
+        assert_eq!(actions[0]["actions"][0]["value"], shift);
+        assert_eq!(actions[0]["actions"][keys.len() - 1]["value"], shift);
+
+        // Every click happens in a tick between the key down and the key up.
+        let downs: Vec<_> = (0..pointer.len()).filter(|&i| pointer[i] == "pointerDown").collect();
+        let ups: Vec<_> = (0..pointer.len()).filter(|&i| pointer[i] == "pointerUp").collect();
+        assert_eq!(downs.len(), 3);
+        assert_eq!(ups.len(), 3);
+        assert!(downs.iter().chain(&ups).all(|&i| i > 0 && i < keys.len() - 1));
+        assert_eq!(pointer[0], "pause");
+        assert_eq!(pointer[pointer.len() - 1], "pause");
+    }
+}
@@ -20,37 +20,29 @@ impl Alert {
     }
 
     /// Get the text of the active alert if there is one.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::get_alert_text()"
-    )]
+    ///
+    /// This is equivalent to `WebDriver::get_alert_text()`.
     pub async fn text(&self) -> WebDriverResult<String> {
         self.handle.get_alert_text().await
     }
 
     /// Dismiss the active alert if there is one.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::dismiss_alert()"
-    )]
+    ///
+    /// This is equivalent to `WebDriver::dismiss_alert()`.
     pub async fn dismiss(&self) -> WebDriverResult<()> {
         self.handle.dismiss_alert().await
     }
 
     /// Accept the active alert if there is one.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::accept_alert()"
-    )]
+    ///
+    /// This is equivalent to `WebDriver::accept_alert()`.
     pub async fn accept(&self) -> WebDriverResult<()> {
         self.handle.accept_alert().await
     }
 
     /// Send the specified text to the active alert if there is one.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::send_alert_text()"
-    )]
+    ///
+    /// This is equivalent to `WebDriver::send_alert_text()`.
     pub async fn send_keys(&self, keys: impl Into<TypingData>) -> WebDriverResult<()> {
         self.handle.send_alert_text(keys.into()).await
     }
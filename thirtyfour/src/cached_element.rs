@@ -0,0 +1,171 @@
+use crate::error::WebDriverResult;
+use crate::WebElement;
+use parking_lot::Mutex;
+use std::fmt;
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Default)]
+struct Cache {
+    tag_name: Option<String>,
+    id: Option<Option<String>>,
+}
+
+/// A `WebElement` wrapper that caches properties that rarely change.
+///
+/// The following properties are fetched from the WebDriver the first time they are
+/// requested, and then served from the cache until `refresh()` is called:
+///
+/// - `tag_name()`
+/// - `id()`
+///
+/// The tag name of an element can never change. The `id` attribute can be changed by
+/// scripts on the page, so call `refresh()` if the page might have done that.
+///
+/// Every other method is available via `Deref` to the underlying `WebElement`, and
+/// always queries the WebDriver. This includes dynamic properties such as `text()`,
+/// `value()` and `rect()`.
+///
+/// Caching is opt-in. Use `WebElement::cached()` to create a `CachedWebElement`.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let elem = driver.find(By::Id("my-element-id")).await?.cached();
+/// for _ in 0..10 {
+///     // Only the first call sends a request to the WebDriver.
+///     assert_eq!(elem.tag_name().await?, "div");
+/// }
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+pub struct CachedWebElement {
+    element: WebElement,
+    cache: Mutex<Cache>,
+}
+
+impl fmt::Debug for CachedWebElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedWebElement")
+            .field("element", &self.element.element_id)
+            .field("cache", &*self.cache.lock())
+            .finish()
+    }
+}
+
+impl Clone for CachedWebElement {
+    fn clone(&self) -> Self {
+        Self {
+            element: self.element.clone(),
+            cache: Mutex::new(self.cache.lock().clone()),
+        }
+    }
+}
+
+impl PartialEq for CachedWebElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.element == other.element
+    }
+}
+
+impl Eq for CachedWebElement {}
+
+impl CachedWebElement {
+    /// Create a new `CachedWebElement` with an empty cache.
+    pub fn new(element: WebElement) -> Self {
+        Self {
+            element,
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+
+    /// Get the underlying `WebElement`.
+    pub fn element(&self) -> &WebElement {
+        &self.element
+    }
+
+    /// Convert back into the underlying `WebElement`, discarding the cache.
+    pub fn into_inner(self) -> WebElement {
+        self.element
+    }
+
+    /// Clear all cached properties, so that they are fetched again when next requested.
+    pub fn refresh(&self) {
+        *self.cache.lock() = Cache::default();
+    }
+
+    /// Get the tag name for this element, fetching it only if it is not already cached.
+    pub async fn tag_name(&self) -> WebDriverResult<String> {
+        if let Some(tag_name) = self.cache.lock().tag_name.clone() {
+            return Ok(tag_name);
+        }
+        let tag_name = self.element.tag_name().await?;
+        self.cache.lock().tag_name = Some(tag_name.clone());
+        Ok(tag_name)
+    }
+
+    /// Get the `id` attribute for this element, fetching it only if it is not already cached.
+    pub async fn id(&self) -> WebDriverResult<Option<String>> {
+        if let Some(id) = self.cache.lock().id.clone() {
+            return Ok(id);
+        }
+        let id = self.element.id().await?;
+        self.cache.lock().id = Some(id.clone());
+        Ok(id)
+    }
+}
+
+impl Deref for CachedWebElement {
+    type Target = WebElement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.element
+    }
+}
+
+impl From<WebElement> for CachedWebElement {
+    fn from(element: WebElement) -> Self {
+        Self::new(element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::MockClient;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_cached_properties() {
+        let client = MockClient::new()
+            .reply("/name", json!("div"))
+            .reply("/id", json!("main"))
+            .reply("", json!("live"));
+        let client = Arc::new(client);
+        let elem = WebElement::from_reference(client.handle(), "abc").cached();
+
+        for _ in 0..3 {
+            assert_eq!(elem.tag_name().await.unwrap(), "div");
+            assert_eq!(elem.id().await.unwrap().as_deref(), Some("main"));
+            assert_eq!(elem.text().await.unwrap(), "live");
+        }
+        let count = |suffix| client.count(suffix);
+        assert_eq!(count("/name"), 1);
+        assert_eq!(count("/attribute/id"), 1);
+        assert_eq!(count("/text"), 3);
+
+        elem.refresh();
+        elem.tag_name().await.unwrap();
+        elem.id().await.unwrap();
+        assert_eq!(count("/name"), 2);
+        assert_eq!(count("/attribute/id"), 2);
+    }
+}
@@ -177,6 +177,23 @@ where
     pub fn id(&self) -> &str {
         &self.id
     }
+
+    /// Get the number of actions (ticks) in this action source.
+    pub(crate) fn tick_count(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Get the actions from the specified tick onwards.
+    pub(crate) fn actions_from(&self, tick: usize) -> &[T] {
+        self.actions.get(tick..).unwrap_or_default()
+    }
+
+    /// Add pause actions until this action source has the specified number of ticks.
+    pub(crate) fn pad_to(&mut self, ticks: usize) {
+        while self.actions.len() < ticks {
+            self.pause();
+        }
+    }
 }
 
 impl ActionSource<KeyAction> {
@@ -253,7 +270,7 @@ impl ActionSource<PointerAction> {
         });
     }
 
-    ///
+    /// Add a move action relative to the current pointer position.
     pub fn move_by(&mut self, x: i64, y: i64) {
         self.add_action(PointerAction::PointerMove {
             duration: self.duration,
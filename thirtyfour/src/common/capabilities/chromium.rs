@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::Path;
 
 use base64::{prelude::BASE64_STANDARD, Engine};
@@ -8,6 +9,9 @@ use serde_json::{json, to_value, Value};
 use crate::error::WebDriverResult;
 use crate::{BrowserCapabilitiesHelper, Capabilities, CapabilitiesHelper};
 
+const HEADLESS_ARG: &str = "--headless=new";
+const LEGACY_HEADLESS_ARG: &str = "--headless";
+
 macro_rules! chromium_arg_wrapper {
     ($($fname:ident => $opt:literal),*) => {
         paste! {
@@ -23,7 +27,7 @@ macro_rules! chromium_arg_wrapper {
                 }
 
                 #[doc = concat!("Return true if the ", $opt, " option is set.")]
-                fn [<is_ $fname>](&mut self) -> bool {
+                fn [<is_ $fname>](&self) -> bool {
                     self.has_arg($opt)
                 }
             )*
@@ -164,6 +168,22 @@ pub trait ChromiumLikeCapabilities: BrowserCapabilitiesHelper {
         self.add_experimental_option("excludeSwitches", to_value(args)?)
     }
 
+    /// Replace the list of exclude switches.
+    ///
+    /// For example, excluding `enable-automation` hides the
+    /// "Chrome is being controlled by automated test software" infobar.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.set_exclude_switches(vec!["enable-automation".to_string()]).unwrap();
+    /// ```
+    fn set_exclude_switches(&mut self, args: Vec<String>) -> WebDriverResult<()> {
+        self.add_experimental_option("excludeSwitches", args)
+    }
+
     /// Remove the specified arg from the list of exclude switches.
     fn remove_exclude_switch(&mut self, arg: &str) -> WebDriverResult<()> {
         let mut args = self.exclude_switches();
@@ -175,8 +195,109 @@ pub trait ChromiumLikeCapabilities: BrowserCapabilitiesHelper {
         }
     }
 
+    /// Get the current browser preferences (if any were previously set).
+    fn prefs(&self) -> HashMap<String, Value> {
+        self.browser_option("prefs").unwrap_or_default()
+    }
+
+    /// Set the browser preferences, replacing any previously set.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use serde_json::json;
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// let mut prefs = HashMap::new();
+    /// prefs.insert("download.default_directory".to_string(), json!("/tmp/downloads"));
+    /// caps.set_prefs(prefs).unwrap();
+    /// ```
+    fn set_prefs(&mut self, prefs: HashMap<String, Value>) -> WebDriverResult<()> {
+        self.add_experimental_option("prefs", prefs)
+    }
+
+    /// Set the directory that downloads are saved to, and disable the download prompt.
+    ///
+    /// This merges the relevant entries into any preferences previously set via
+    /// `set_prefs()`.
+    ///
+    /// **Headless Chrome ignores these preferences.** To save downloads to `dir` there,
+    /// also call `ChromeDevTools::set_download_directory()` once the session has started,
+    /// as shown in the example for `WebDriver::wait_for_download()`.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use std::path::Path;
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.set_download_directory(Path::new("/tmp/downloads")).unwrap();
+    /// ```
+    fn set_download_directory(&mut self, dir: &Path) -> WebDriverResult<()> {
+        let mut prefs = self.prefs();
+        prefs.insert("download.default_directory".to_string(), to_value(dir)?);
+        prefs.insert("download.prompt_for_download".to_string(), json!(false));
+        prefs.insert("download.directory_upgrade".to_string(), json!(true));
+        self.set_prefs(prefs)
+    }
+
+    /// Record the CDP network events in the performance log.
+    ///
+    /// This lets `WebDriver::wait_for_network_idle()` track every request instead of
+    /// falling back to a script in the page. Any other `goog:loggingPrefs` previously set
+    /// are kept.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.enable_performance_logging().unwrap();
+    /// ```
+    fn enable_performance_logging(&mut self) -> WebDriverResult<()> {
+        let mut prefs = match self._get("goog:loggingPrefs") {
+            Some(Value::Object(prefs)) => prefs.clone(),
+            _ => Default::default(),
+        };
+        prefs.insert("performance".to_string(), json!("ALL"));
+        self.set_base_capability("goog:loggingPrefs", prefs)
+    }
+
+    /// Enable or disable headless mode.
+    ///
+    /// Enabling this adds the `--headless=new` argument, which runs the same browser as
+    /// headed mode. The legacy `--headless` argument selects the old headless
+    /// implementation, which behaves differently in many places, so it is removed if
+    /// present.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.set_headless(true).unwrap();
+    /// ```
+    fn set_headless(&mut self, headless: bool) -> WebDriverResult<()> {
+        self.unset_headless()?;
+        if headless {
+            self.add_arg(HEADLESS_ARG)?;
+        }
+        Ok(())
+    }
+
+    /// Remove any headless argument, either `--headless=new` or the legacy `--headless`.
+    fn unset_headless(&mut self) -> WebDriverResult<()> {
+        self.remove_arg(HEADLESS_ARG)?;
+        self.remove_arg(LEGACY_HEADLESS_ARG)
+    }
+
+    /// Return true if either `--headless=new` or the legacy `--headless` argument is set.
+    fn is_headless(&self) -> bool {
+        self.has_arg(HEADLESS_ARG) || self.has_arg(LEGACY_HEADLESS_ARG)
+    }
+
     chromium_arg_wrapper! {
-        headless => "--headless",
         disable_web_security => "--disable-web-security",
         ignore_certificate_errors => "--ignore-certificate-errors",
         no_sandbox => "--no-sandbox",
@@ -235,3 +356,67 @@ impl From<ChromiumCapabilities> for Capabilities {
         caps.capabilities
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DesiredCapabilities;
+
+    #[test]
+    fn test_experimental_options() {
+        let mut caps = DesiredCapabilities::chrome();
+        caps.add_experimental_option("useAutomationExtension", false).unwrap();
+        caps.set_exclude_switches(vec!["enable-automation".to_string()]).unwrap();
+        let mut prefs = HashMap::new();
+        prefs.insert("download.default_directory".to_string(), json!("/tmp/downloads"));
+        caps.set_prefs(prefs.clone()).unwrap();
+
+        assert_eq!(caps.prefs(), prefs);
+        assert_eq!(caps.exclude_switches(), vec!["enable-automation".to_string()]);
+        assert_eq!(
+            to_value(&caps).unwrap()["goog:chromeOptions"],
+            json!({
+                "useAutomationExtension": false,
+                "excludeSwitches": ["enable-automation"],
+                "prefs": {"download.default_directory": "/tmp/downloads"}
+            })
+        );
+
+        caps.set_download_directory(Path::new("/tmp/other")).unwrap();
+        let prefs = caps.prefs();
+        assert_eq!(prefs["download.default_directory"], json!("/tmp/other"));
+        assert_eq!(prefs["download.prompt_for_download"], json!(false));
+
+        caps.remove_experimental_option("useAutomationExtension");
+        assert!(caps.browser_option::<bool>("useAutomationExtension").is_none());
+    }
+
+    #[test]
+    fn test_headless() {
+        let mut caps = DesiredCapabilities::chrome();
+        caps.add_arg("--headless").unwrap();
+        assert!(caps.is_headless());
+
+        caps.set_headless(true).unwrap();
+        assert_eq!(caps.args(), vec!["--headless=new".to_string()]);
+        assert!(caps.is_headless());
+
+        caps.set_headless(false).unwrap();
+        assert!(caps.args().is_empty());
+        assert!(!caps.is_headless());
+
+        let mut caps = DesiredCapabilities::edge();
+        caps.set_headless(true).unwrap();
+        assert_eq!(to_value(&caps).unwrap()["ms:edgeOptions"]["args"], json!(["--headless=new"]));
+    }
+    #[test]
+    fn test_enable_performance_logging() {
+        let mut caps = DesiredCapabilities::chrome();
+        caps.set_base_capability("goog:loggingPrefs", json!({ "browser": "INFO" })).unwrap();
+        caps.enable_performance_logging().unwrap();
+        assert_eq!(
+            to_value(&caps).unwrap()["goog:loggingPrefs"],
+            json!({ "browser": "INFO", "performance": "ALL" })
+        );
+    }
+}
@@ -8,7 +8,7 @@ use crate::common::capabilities::firefox::FirefoxCapabilities;
 use crate::common::capabilities::ie::InternetExplorerCapabilities;
 use crate::common::capabilities::opera::OperaCapabilities;
 use crate::common::capabilities::safari::SafariCapabilities;
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
 use crate::ChromiumCapabilities;
 
 /// Type alias for a generic Capabilities struct.
@@ -33,9 +33,27 @@ const OSS_W3C_CONVERSION: &[(&str, &str)] = &[
     ("platform", "platformName"),
 ];
 
+/// Return true if the specified key is either a standard W3C capability name or
+/// a valid extension capability name (containing a `:`).
+fn is_valid_capability_name(key: &str) -> bool {
+    W3C_CAPABILITY_NAMES.contains(&key) || key.contains(':')
+}
+
+/// The key used to carry an explicit `firstMatch` array in a Capabilities struct.
+///
+/// See [`DesiredCapabilities::with_first_match`].
+const FIRST_MATCH_KEY: &str = "firstMatch";
+
 /// Convert the given serde_json::Value into a W3C-compatible Capabilities struct.
+///
+/// Any `firstMatch` array set via [`DesiredCapabilities::with_first_match`] is used as
+/// the `firstMatch` array. All other valid capabilities are placed in `alwaysMatch`.
 pub fn make_w3c_caps(caps: &Value) -> Value {
     let mut always_match = json!({});
+    let first_match = match caps.get(FIRST_MATCH_KEY) {
+        Some(Value::Array(entries)) if !entries.is_empty() => Value::Array(entries.clone()),
+        _ => json!([{}]),
+    };
 
     if let Some(caps_map) = caps.as_object() {
         for (k, v) in caps_map.iter() {
@@ -47,14 +65,14 @@ pub fn make_w3c_caps(caps: &Value) -> Value {
                 }
             }
 
-            if W3C_CAPABILITY_NAMES.contains(&k.as_str()) || k.contains(':') {
+            if is_valid_capability_name(k) {
                 always_match[k] = v.clone();
             }
         }
     }
 
     json!({
-        "firstMatch": [{}], "alwaysMatch": always_match
+        "firstMatch": first_match, "alwaysMatch": always_match
     })
 }
 
@@ -104,6 +122,37 @@ impl DesiredCapabilities {
     pub fn safari() -> SafariCapabilities {
         SafariCapabilities::new()
     }
+
+    /// Create a generic Capabilities struct that sends the specified `firstMatch` array
+    /// in the new session request, letting the server pick the first entry it can satisfy.
+    ///
+    /// Any capabilities added to the returned struct are sent in `alwaysMatch`, and so
+    /// apply regardless of which `firstMatch` entry is chosen.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use serde_json::json;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// let mut caps = DesiredCapabilities::with_first_match(vec![
+    ///     json!({"browserName": "chrome"}),
+    ///     json!({"browserName": "firefox"}),
+    /// ]);
+    /// caps.add_capability("acceptInsecureCerts", true)?;
+    /// let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn with_first_match(first_match: Vec<Value>) -> Capabilities {
+        let mut caps = Capabilities::new();
+        caps.insert(FIRST_MATCH_KEY.to_string(), Value::Array(first_match));
+        caps
+    }
 }
 
 /// Provides common features for all Capabilities structs.
@@ -117,6 +166,34 @@ pub trait CapabilitiesHelper {
     /// Set the specified capability at the root level.
     fn insert_base_capability(&mut self, key: String, value: Value);
 
+    /// Return a copy of these capabilities, modified by the specified closure.
+    ///
+    /// This leaves the original capabilities unchanged, so that a shared base
+    /// configuration can be used to derive several variants. Any error returned by the
+    /// closure is returned from `with()`.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut base = DesiredCapabilities::chrome();
+    /// base.set_no_sandbox()?;
+    ///
+    /// let headless = base.with(|c| c.set_headless(true))?;
+    /// let large = base.with(|c| c.add_arg("--window-size=1920,1080"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with<F>(&self, f: F) -> WebDriverResult<Self>
+    where
+        Self: Clone,
+        F: FnOnce(&mut Self) -> WebDriverResult<()>,
+    {
+        let mut caps = self.clone();
+        f(&mut caps)?;
+        Ok(caps)
+    }
+
     /// Add any Serialize-able object to the capabilities under the specified key.
     fn set_base_capability<T>(&mut self, key: &str, value: T) -> WebDriverResult<()>
     where
@@ -126,6 +203,24 @@ pub trait CapabilitiesHelper {
         Ok(())
     }
 
+    /// Add a custom capability, validating the key against the W3C spec.
+    ///
+    /// Standard W3C capability names (e.g. `browserName`, `platformName`) are accepted
+    /// as-is. Any other extension capability must contain a `:`, such as
+    /// `"myvendor:option"`. Keys that do not meet this requirement will be rejected by
+    /// the WebDriver server, so an `InvalidArgument` error is returned here instead.
+    fn add_capability<T>(&mut self, key: &str, value: T) -> WebDriverResult<()>
+    where
+        T: Serialize,
+    {
+        if !is_valid_capability_name(key) {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                "invalid capability name '{key}': extension capabilities must contain a ':'"
+            ))));
+        }
+        self.set_base_capability(key, value)
+    }
+
     /// Set the desired browser version.
     fn set_version(&mut self, version: &str) -> WebDriverResult<()> {
         self.set_base_capability("version", version)
@@ -372,15 +467,108 @@ pub enum ScrollBehaviour {
 }
 
 /// The page load strategy for this session.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// This controls when navigation commands such as `WebDriver::goto()` return.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PageLoadStrategy {
     /// Wait for full page loading (the default).
     #[default]
     Normal,
     /// Wait for the DOMContentLoaded event (html content downloaded and parsed only).
+    ///
+    /// This is honoured by chromedriver, msedgedriver and geckodriver.
+    /// Older versions of safaridriver ignore it and fall back to `Normal`.
     Eager,
     /// Return immediately after the initial page content is fully received
     /// (html content downloaded).
     None,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChromiumLikeCapabilities;
+
+    #[test]
+    fn test_page_load_strategy_serialize() {
+        assert_eq!(to_value(PageLoadStrategy::Normal).unwrap(), json!("normal"));
+        assert_eq!(to_value(PageLoadStrategy::Eager).unwrap(), json!("eager"));
+        assert_eq!(to_value(PageLoadStrategy::None).unwrap(), json!("none"));
+    }
+
+    #[test]
+    fn test_page_load_strategy_capability() {
+        let mut caps = DesiredCapabilities::chrome();
+        assert_eq!(caps.page_load_strategy().unwrap(), PageLoadStrategy::Normal);
+        caps.set_page_load_strategy(PageLoadStrategy::Eager).unwrap();
+        assert_eq!(caps.page_load_strategy().unwrap(), PageLoadStrategy::Eager);
+
+        let w3c_caps = make_w3c_caps(&to_value(caps).unwrap());
+        assert_eq!(w3c_caps["alwaysMatch"]["pageLoadStrategy"], json!("eager"));
+    }
+
+    #[test]
+    fn test_add_capability() {
+        let mut caps = DesiredCapabilities::chrome();
+        caps.add_capability("platformName", "linux").unwrap();
+        caps.add_capability("acceptInsecureCerts", true).unwrap();
+        caps.add_capability("myvendor:option", json!({"key": "value"})).unwrap();
+        assert_eq!(caps._get("platformName"), Some(&json!("linux")));
+        assert_eq!(caps._get("myvendor:option"), Some(&json!({"key": "value"})));
+
+        assert!(matches!(
+            caps.add_capability("vendorOption", 1),
+            Err(WebDriverError::InvalidArgument(_))
+        ));
+        assert!(caps._get("vendorOption").is_none());
+    }
+
+    #[test]
+    fn test_with_first_match() {
+        let mut caps = DesiredCapabilities::with_first_match(vec![
+            json!({"browserName": "chrome"}),
+            json!({"browserName": "firefox"}),
+        ]);
+        caps.add_capability("acceptInsecureCerts", true).unwrap();
+
+        let w3c_caps = make_w3c_caps(&to_value(caps).unwrap());
+        assert_eq!(
+            w3c_caps,
+            json!({
+                "firstMatch": [{"browserName": "chrome"}, {"browserName": "firefox"}],
+                "alwaysMatch": {"acceptInsecureCerts": true}
+            })
+        );
+    }
+
+    #[test]
+    fn test_default_first_match() {
+        let caps = DesiredCapabilities::with_first_match(Vec::new());
+        let w3c_caps = make_w3c_caps(&to_value(caps).unwrap());
+        assert_eq!(w3c_caps, json!({"firstMatch": [{}], "alwaysMatch": {}}));
+
+        let w3c_caps = make_w3c_caps(&to_value(DesiredCapabilities::chrome()).unwrap());
+        assert_eq!(w3c_caps["firstMatch"], json!([{}]));
+    }
+
+    #[test]
+    fn test_with_leaves_original_unchanged() {
+        let mut base = DesiredCapabilities::chrome();
+        base.set_no_sandbox().unwrap();
+
+        let headless = base.with(|c| c.set_headless(true)).unwrap();
+        assert!(headless.is_headless());
+        assert!(headless.is_no_sandbox());
+        assert!(!base.is_headless());
+        assert_eq!(base.args(), vec!["--no-sandbox".to_string()]);
+
+        let firefox = DesiredCapabilities::firefox();
+        let variant = firefox.with(|c| c.set_headless(true)).unwrap();
+        assert!(variant.is_headless());
+        assert!(!firefox.is_headless());
+
+        let result = Capabilities::new().with(|c| c.add_capability("invalid", true));
+        assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+    }
+}
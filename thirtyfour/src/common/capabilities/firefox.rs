@@ -1,4 +1,3 @@
-use paste::paste;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json, to_value, Value};
 
@@ -6,6 +5,8 @@ use crate::error::WebDriverResult;
 use crate::CapabilitiesHelper;
 use crate::{BrowserCapabilitiesHelper, Capabilities};
 
+const HEADLESS_ARG: &str = "-headless";
+
 /// Capabilities for Firefox.
 #[derive(Debug, Clone, Serialize)]
 #[serde(transparent)]
@@ -19,30 +20,6 @@ impl Default for FirefoxCapabilities {
     }
 }
 
-macro_rules! firefox_arg_wrapper {
-    ($($fname:ident => $opt:literal),*) => {
-
-        paste! {
-            $(
-                #[doc = concat!("Set the ", $opt, " option.")]
-                pub fn [<set_ $fname>](&mut self) -> WebDriverResult<()> {
-                    self.add_arg($opt)
-                }
-
-                #[doc = concat!("Unset the ", $opt, " option.")]
-                pub fn [<unset_ $fname>](&mut self) -> WebDriverResult<()> {
-                    self.remove_arg($opt)
-                }
-
-                #[doc = concat!("Return true if the ", $opt, " option is set.")]
-                pub fn [<is_ $fname>](&mut self) -> bool {
-                    self.has_arg($opt)
-                }
-            )*
-        }
-    }
-}
-
 impl FirefoxCapabilities {
     /// Create a new `FirefoxCapabilities`.
     pub fn new() -> Self {
@@ -90,6 +67,51 @@ impl FirefoxCapabilities {
         self.insert_browser_option("prefs", preferences)
     }
 
+    /// Get the firefox preferences, if any have been set.
+    pub fn preferences(&self) -> FirefoxPreferences {
+        self.browser_option("prefs").unwrap_or_default()
+    }
+
+    /// Set the user agent, via the `general.useragent.override` preference.
+    ///
+    /// This keeps any other preferences that have already been set. The user agent of a
+    /// running Firefox session cannot be changed, so it must be set here, before the
+    /// session is created.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// caps.set_user_agent("my-crawler/1.0").unwrap();
+    /// ```
+    pub fn set_user_agent(&mut self, user_agent: &str) -> WebDriverResult<()> {
+        let mut preferences = self.preferences();
+        preferences.set_user_agent(user_agent.to_string())?;
+        self.set_preferences(preferences)
+    }
+
+    /// Set a single firefox preference, under `moz:firefoxOptions.prefs`.
+    ///
+    /// This keeps any other preferences that have already been set.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// caps.set_preference("browser.download.folderList", 2).unwrap();
+    /// caps.set_preference("browser.download.dir", "/tmp/downloads").unwrap();
+    /// caps.set_preference("app.update.auto", false).unwrap();
+    /// ```
+    pub fn set_preference(
+        &mut self,
+        key: &str,
+        value: impl Into<PrefValue>,
+    ) -> WebDriverResult<()> {
+        let mut preferences = self.preferences();
+        preferences.set(key, value.into())?;
+        self.set_preferences(preferences)
+    }
+
     /// Get the firefox profile zip as a base64-encoded string.
     pub fn encoded_profile(&self) -> Option<String> {
         self.browser_option("profile")
@@ -112,8 +134,30 @@ impl FirefoxCapabilities {
         self.insert_browser_option("args", to_value(args)?)
     }
 
-    firefox_arg_wrapper! {
-        headless => "-headless"
+    /// Enable or disable headless mode, by adding or removing the `-headless` argument.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// caps.set_headless(true).unwrap();
+    /// ```
+    pub fn set_headless(&mut self, headless: bool) -> WebDriverResult<()> {
+        if headless {
+            self.add_arg(HEADLESS_ARG)
+        } else {
+            self.remove_arg(HEADLESS_ARG)
+        }
+    }
+
+    /// Unset the -headless option.
+    pub fn unset_headless(&mut self) -> WebDriverResult<()> {
+        self.remove_arg(HEADLESS_ARG)
+    }
+
+    /// Return true if the -headless option is set.
+    pub fn is_headless(&self) -> bool {
+        self.has_arg(HEADLESS_ARG)
     }
 }
 
@@ -289,6 +333,61 @@ impl FirefoxPreferences {
     }
 }
 
+/// The value of a firefox preference. See [`FirefoxCapabilities::set_preference()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum PrefValue {
+    /// A boolean preference.
+    Bool(bool),
+    /// An integer preference.
+    Int(i64),
+    /// A string preference.
+    String(String),
+}
+
+impl From<bool> for PrefValue {
+    fn from(value: bool) -> Self {
+        PrefValue::Bool(value)
+    }
+}
+
+impl From<i64> for PrefValue {
+    fn from(value: i64) -> Self {
+        PrefValue::Int(value)
+    }
+}
+
+impl From<i32> for PrefValue {
+    fn from(value: i32) -> Self {
+        PrefValue::Int(value.into())
+    }
+}
+
+impl From<u32> for PrefValue {
+    fn from(value: u32) -> Self {
+        PrefValue::Int(value.into())
+    }
+}
+
+/// Values larger than `i64::MAX` are clamped to `i64::MAX`.
+impl From<usize> for PrefValue {
+    fn from(value: usize) -> Self {
+        PrefValue::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<&str> for PrefValue {
+    fn from(value: &str) -> Self {
+        PrefValue::String(value.to_string())
+    }
+}
+
+impl From<String> for PrefValue {
+    fn from(value: String) -> Self {
+        PrefValue::String(value)
+    }
+}
+
 /// Log level for Firefox profile.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -304,3 +403,64 @@ pub enum FirefoxProfileLogDriver {
     /// Disable logging.
     Off,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DesiredCapabilities;
+
+    #[test]
+    fn test_headless() {
+        let mut caps = DesiredCapabilities::firefox();
+        caps.set_headless(true).unwrap();
+        assert!(caps.is_headless());
+        assert_eq!(to_value(&caps).unwrap()["moz:firefoxOptions"]["args"], json!(["-headless"]));
+
+        caps.set_headless(false).unwrap();
+        assert!(!caps.is_headless());
+        assert!(caps.args().is_empty());
+    }
+
+    #[test]
+    fn test_set_user_agent() {
+        let mut caps = DesiredCapabilities::firefox();
+        let mut preferences = FirefoxPreferences::new();
+        preferences.set_accept_untrusted_certs(true).unwrap();
+        caps.set_preferences(preferences).unwrap();
+        caps.set_user_agent("custom/1.0").unwrap();
+        assert_eq!(
+            to_value(&caps).unwrap()["moz:firefoxOptions"]["prefs"],
+            json!({
+                "webdriver_accept_untrusted_certs": true,
+                "general.useragent.override": "custom/1.0"
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_preference() {
+        let mut caps = DesiredCapabilities::firefox();
+        caps.set_user_agent("custom/1.0").unwrap();
+        caps.set_preference("dom.disable_beforeunload", true).unwrap();
+        caps.set_preference("network.proxy.type", 1).unwrap();
+        caps.set_preference("browser.download.dir", "/tmp/downloads").unwrap();
+        caps.set_preference("browser.download.folderList", 2u32).unwrap();
+        caps.set_preference("dom.max_script_run_time", 30usize).unwrap();
+        let prefs = &to_value(&caps).unwrap()["moz:firefoxOptions"]["prefs"];
+        assert_eq!(
+            prefs,
+            &json!({
+                "general.useragent.override": "custom/1.0",
+                "dom.disable_beforeunload": true,
+                "network.proxy.type": 1,
+                "browser.download.dir": "/tmp/downloads",
+                "browser.download.folderList": 2,
+                "dom.max_script_run_time": 30
+            })
+        );
+        assert!(prefs["dom.disable_beforeunload"].is_boolean());
+        assert!(prefs["network.proxy.type"].is_i64());
+        assert!(prefs["browser.download.dir"].is_string());
+        assert_eq!(PrefValue::from(usize::MAX), PrefValue::Int(i64::MAX));
+    }
+}
@@ -5,7 +5,10 @@ use crate::common::{
     capabilities::desiredcapabilities::make_w3c_caps,
     cookie::Cookie,
     keys::TypingData,
-    types::{ElementId, OptionRect, SessionId, TimeoutConfiguration, WindowHandle},
+    types::{
+        ElementId, OptionRect, PermissionState, PrintParameters, SessionId, TimeoutConfiguration,
+        WindowHandle,
+    },
 };
 use crate::IntoArcStr;
 use crate::RequestData;
@@ -131,6 +134,18 @@ impl By {
     }
 }
 
+/// Convert a string into a CSS selector.
+///
+/// This allows a plain string to be passed anywhere a `By` is accepted, such as
+/// `driver.find("div.content")`. The string is **always** treated as a CSS selector,
+/// never as XPath or an id. Use `By::XPath()` (or the other `By` constructors) explicitly
+/// for any other strategy. The explicit `By::` form remains the recommended style.
+impl From<&str> for By {
+    fn from(css: &str) -> Self {
+        By::Css(css)
+    }
+}
+
 impl fmt::Display for BySelector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -250,16 +265,68 @@ pub enum Command {
     SendAlertText(TypingData),
     TakeScreenshot,
     TakeElementScreenshot(ElementId),
+    SetPermission(Arc<str>, PermissionState),
+    PrintPage(PrintParameters),
     ExtensionCommand(Box<dyn ExtensionCommand + Send + Sync>),
 }
 
+impl Command {
+    /// Return true if the command only reads state, and so can safely be sent more than once.
+    ///
+    /// Commands that change the state of the browser or page (e.g. clicking, typing,
+    /// navigating or executing scripts) are never considered idempotent.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            Command::Status
+                | Command::GetTimeouts
+                | Command::GetCurrentUrl
+                | Command::GetTitle
+                | Command::GetWindowHandle
+                | Command::GetWindowHandles
+                | Command::GetWindowRect
+                | Command::GetActiveElement
+                | Command::FindElement(_)
+                | Command::FindElements(_)
+                | Command::FindElementFromElement(..)
+                | Command::FindElementsFromElement(..)
+                | Command::IsElementSelected(_)
+                | Command::IsElementDisplayed(_)
+                | Command::GetElementAttribute(..)
+                | Command::GetElementProperty(..)
+                | Command::GetElementCssValue(..)
+                | Command::GetElementText(_)
+                | Command::GetElementTagName(_)
+                | Command::GetElementRect(_)
+                | Command::IsElementEnabled(_)
+                | Command::GetPageSource
+                | Command::GetAllCookies
+                | Command::GetNamedCookie(_)
+                | Command::GetAlertText
+                | Command::TakeScreenshot
+                | Command::TakeElementScreenshot(_)
+        )
+    }
+}
+
 /// Trait for formatting a WebDriver command into a `RequestData` struct.
 pub trait FormatRequestData: Debug {
     /// Format the command into a `RequestData` struct.
     fn format_request(&self, session_id: &SessionId) -> RequestData;
+
+    /// Return true if the command can safely be retried after a transient failure.
+    ///
+    /// This defaults to false. See `WebDriverConfig::retry_policy`.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 impl FormatRequestData for Command {
+    fn is_idempotent(&self) -> bool {
+        Command::is_idempotent(self)
+    }
+
     fn format_request(&self, session_id: &SessionId) -> RequestData {
         match self {
             Command::NewSession(caps) => {
@@ -500,6 +567,14 @@ impl FormatRequestData for Command {
                 Method::GET,
                 format!("/session/{}/element/{}/screenshot", session_id, element_id),
             ),
+            Command::SetPermission(name, state) => {
+                RequestData::new(Method::POST, format!("/session/{}/permissions", session_id))
+                    .add_body(json!({ "descriptor": { "name": name }, "state": state }))
+            }
+            Command::PrintPage(params) => {
+                RequestData::new(Method::POST, format!("/session/{}/print", session_id))
+                    .add_body(json!(params))
+            }
             Command::ExtensionCommand(command) => {
                 let request_data = RequestData::new(
                     command.method(),
@@ -513,3 +588,80 @@ impl FormatRequestData for Command {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn timeouts_body(timeouts: TimeoutConfiguration) -> Value {
+        let session_id = SessionId::from("test");
+        let request = Command::SetTimeouts(timeouts).format_request(&session_id);
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(&*request.uri, "/session/test/timeouts");
+        request.body.expect("timeouts request should have a body")
+    }
+
+    #[test]
+    fn test_set_timeouts_single_field() {
+        let d = Some(Duration::from_secs(5));
+        assert_eq!(
+            timeouts_body(TimeoutConfiguration::new(d, None, None)),
+            json!({"script": 5000})
+        );
+        assert_eq!(
+            timeouts_body(TimeoutConfiguration::new(None, d, None)),
+            json!({"pageLoad": 5000})
+        );
+        assert_eq!(
+            timeouts_body(TimeoutConfiguration::new(None, None, d)),
+            json!({"implicit": 5000})
+        );
+    }
+
+    #[test]
+    fn test_set_permission() {
+        let session_id = SessionId::from("test");
+        let request = Command::SetPermission("notifications".into(), PermissionState::Granted)
+            .format_request(&session_id);
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(&*request.uri, "/session/test/permissions");
+        assert_eq!(
+            request.body,
+            Some(json!({"descriptor": {"name": "notifications"}, "state": "granted"}))
+        );
+    }
+
+    #[test]
+    fn test_print_page() {
+        let session_id = SessionId::from("test");
+        let request = Command::PrintPage(PrintParameters::default()).format_request(&session_id);
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(&*request.uri, "/session/test/print");
+        assert_eq!(request.body.unwrap()["shrinkToFit"], json!(true));
+    }
+
+    #[test]
+    fn test_by_from_str_is_css() {
+        let selector: Selector = By::from("div.content").into();
+        assert_eq!(&*selector.name, "css selector");
+        assert_eq!(&*selector.query, "div.content");
+
+        let selector: Selector = By::from("//div").into();
+        assert_eq!(&*selector.name, "css selector");
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        let elem = ElementId::from("abc");
+        assert!(Command::GetTitle.is_idempotent());
+        assert!(Command::GetCurrentUrl.is_idempotent());
+        assert!(Command::FindElement(By::Id("x").into()).is_idempotent());
+        assert!(Command::GetElementText(elem.clone()).is_idempotent());
+
+        assert!(!Command::ElementClick(elem.clone()).is_idempotent());
+        assert!(!Command::ElementSendKeys(elem, "text".into()).is_idempotent());
+        assert!(!Command::NavigateTo("https://example.com".into()).is_idempotent());
+        assert!(!Command::ExecuteScript("return 1;".into(), Arc::new([])).is_idempotent());
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
 };
 use http::HeaderValue;
 use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 /// Configuration options used by a `WebDriver` instance and the related `SessionHandle`.
 ///
@@ -18,6 +19,105 @@ pub struct WebDriverConfig {
     pub poller: Arc<dyn IntoElementPoller + Send + Sync>,
     /// The user agent to use when sending commands to the webdriver server.
     pub user_agent: HeaderValue,
+    /// The number of times to retry an element screenshot that came back empty, or with
+    /// the `image` feature, that could not be decoded.
+    ///
+    /// Chrome occasionally returns an empty image when an element screenshot is taken
+    /// while the element is still being rendered (e.g. during a CSS transition).
+    pub screenshot_retries: u32,
+    /// If true, `WebDriver::goto()` clears cookies, `localStorage` and `sessionStorage`
+    /// after navigating to a page with a different origin than the previous page.
+    ///
+    /// The origin is the scheme, host and port of the URL, so same-site route changes
+    /// such as navigating from `/login` to `/home` do not clear anything.
+    ///
+    /// The storage is cleared once the new page has loaded, so scripts that run while it
+    /// loads can still see the old data. See `WebDriver::goto()`.
+    pub clear_storage_on_origin_change: bool,
+    /// The policy for retrying idempotent commands after a transient HTTP failure.
+    ///
+    /// This is `None` (no retries) by default.
+    pub retry_policy: Option<RetryPolicy>,
+    /// If true, keep a copy of the status, headers and body of the most recent response
+    /// from the WebDriver server, available via `WebDriver::last_response()`.
+    ///
+    /// This is off by default to avoid the overhead of copying the headers on every command.
+    pub record_last_response: bool,
+    /// The maximum size of a response body from the WebDriver server, in bytes.
+    ///
+    /// A larger response fails with `WebDriverError::ResponseTooLarge`, rather than
+    /// buffering it in memory, which protects against a page returning a huge result
+    /// from a script. The default is 64 MiB.
+    pub max_response_size: usize,
+    /// The overall timeout for each request to the WebDriver server, until the whole
+    /// response has been received.
+    ///
+    /// This is separate from the connect timeout (see `WebDriverBuilder::connect_timeout()`),
+    /// so it can be long enough for a slow script while the connect timeout still fails
+    /// quickly if the server cannot be reached at all.
+    ///
+    /// **The default is 120 seconds** (`DEFAULT_REQUEST_TIMEOUT`), for every HTTP client,
+    /// so a hung browser or driver cannot block a command forever. If a client ignores the
+    /// timeout, the command is abandoned shortly afterwards with `WebDriverError::Timeout`.
+    ///
+    /// Set this to `None` to leave it entirely to the HTTP client. Use
+    /// `WebDriver::clone_with_config()` to override it for a session, or
+    /// `SessionHandle::cmd_with_timeout()` to override it for a single command.
+    ///
+    /// Scripts and navigation commands (`WebDriver::execute()`, `WebDriver::goto()`,
+    /// `WebDriver::refresh()` and so on) wait for the session's script or page load
+    /// timeout, which may be longer, e.g. 300 seconds for page loads by default. For these
+    /// commands the request timeout is extended automatically to the session timeout (as
+    /// last set via `WebDriver::set_script_timeout()`, `WebDriver::set_page_load_timeout()`
+    /// or `WebDriver::execute_with_timeout()`) plus a margin, so they are not cut off before
+    /// the driver reports its own timeout.
+    pub request_timeout: Option<Duration>,
+    /// If true, serialize element references (for example in script arguments) with the
+    /// legacy `ELEMENT` key from the JSON Wire Protocol, instead of the W3C
+    /// `element-6066-11e4-a52e-4f735466cecf` key.
+    ///
+    /// This is only needed for older drivers that do not understand W3C element
+    /// references. Element references returned by the driver are always accepted with
+    /// either key. The default is false.
+    pub legacy_element_key: bool,
+}
+
+/// The default value of `WebDriverConfig::max_response_size`.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The default value of `WebDriverConfig::request_timeout`.
+///
+/// Scripts and navigation commands get longer if the session's script or page load
+/// timeout is longer, see `WebDriverConfig::request_timeout`.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Policy for retrying idempotent (read-only) commands, such as `GetTitle` or
+/// `FindElement`, when the request fails due to a transient HTTP error.
+///
+/// Commands that change state, such as `ElementClick` or `ElementSendKeys`, are never
+/// retried, so a click will never be sent twice. Errors returned by the WebDriver
+/// itself (e.g. `NoSuchElement`) are also never retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the first attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry. The delay doubles with each subsequent retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy`.
+    pub fn new(max_retries: u32, backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+        }
+    }
+
+    /// Get the delay before the specified retry, starting from 0.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        self.backoff.saturating_mul(2u32.saturating_pow(retry))
+    }
 }
 
 impl Default for WebDriverConfig {
@@ -57,6 +157,13 @@ pub struct WebDriverConfigBuilder {
     keep_alive: bool,
     poller: Option<Arc<dyn IntoElementPoller + Send + Sync>>,
     user_agent: Option<WebDriverResult<HeaderValue>>,
+    screenshot_retries: u32,
+    clear_storage_on_origin_change: bool,
+    retry_policy: Option<RetryPolicy>,
+    record_last_response: bool,
+    max_response_size: usize,
+    request_timeout: Option<Duration>,
+    legacy_element_key: bool,
 }
 
 impl Default for WebDriverConfigBuilder {
@@ -72,6 +179,13 @@ impl WebDriverConfigBuilder {
             keep_alive: true,
             poller: None,
             user_agent: None,
+            screenshot_retries: 3,
+            clear_storage_on_origin_change: false,
+            retry_policy: None,
+            record_last_response: false,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            legacy_element_key: false,
         }
     }
 
@@ -97,6 +211,62 @@ impl WebDriverConfigBuilder {
         self
     }
 
+    /// Set the number of times to retry an element screenshot that came back empty, or
+    /// with the `image` feature, that could not be decoded. The default is 3. Set this to
+    /// 0 to disable retries.
+    pub fn screenshot_retries(mut self, retries: u32) -> Self {
+        self.screenshot_retries = retries;
+        self
+    }
+
+    /// Set whether `WebDriver::goto()` should clear cookies and web storage whenever
+    /// navigation changes the origin. The default is false.
+    pub fn clear_storage_on_origin_change(mut self, clear: bool) -> Self {
+        self.clear_storage_on_origin_change = clear;
+        self
+    }
+
+    /// Set the policy for retrying idempotent commands after a transient HTTP failure.
+    /// By default, commands are not retried.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Set whether to record the most recent response from the WebDriver server.
+    /// The default is false.
+    pub fn record_last_response(mut self, record: bool) -> Self {
+        self.record_last_response = record;
+        self
+    }
+
+    /// Set the maximum size of a response body from the WebDriver server, in bytes.
+    /// The default is 64 MiB.
+    pub fn max_response_size(mut self, size: usize) -> Self {
+        self.max_response_size = size;
+        self
+    }
+
+    /// Set the overall timeout for each request to the WebDriver server.
+    /// The default is 120 seconds.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Do not apply any request timeout, leaving it entirely to the HTTP client.
+    pub fn no_request_timeout(mut self) -> Self {
+        self.request_timeout = None;
+        self
+    }
+
+    /// Set whether to serialize element references with the legacy `ELEMENT` key, for
+    /// older drivers that do not support W3C element references. The default is false.
+    pub fn legacy_element_key(mut self, legacy: bool) -> Self {
+        self.legacy_element_key = legacy;
+        self
+    }
+
     /// Build `WebDriverConfig` using builder options.
     pub fn build(self) -> WebDriverResult<WebDriverConfig> {
         Ok(WebDriverConfig {
@@ -105,6 +275,13 @@ impl WebDriverConfigBuilder {
             user_agent: self
                 .user_agent
                 .unwrap_or_else(|| Ok(WebDriverConfig::default_user_agent()))?,
+            screenshot_retries: self.screenshot_retries,
+            clear_storage_on_origin_change: self.clear_storage_on_origin_change,
+            retry_policy: self.retry_policy,
+            record_last_response: self.record_last_response,
+            max_response_size: self.max_response_size,
+            request_timeout: self.request_timeout,
+            legacy_element_key: self.legacy_element_key,
         })
     }
 }
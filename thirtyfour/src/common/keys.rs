@@ -149,6 +149,13 @@ impl From<Key> for char {
 }
 
 /// TypingData is a wrapper around a `Vec<char>` that can be used to send Key to the browser.
+///
+/// Text is stored as a sequence of Unicode scalar values (code points), so characters
+/// outside the Basic Multilingual Plane (such as emoji) are never split into surrogate
+/// pairs. Each code point is sent to the WebDriver as a separate entry in the keys array.
+///
+/// **NOTE:** chromedriver currently rejects characters outside the Basic Multilingual
+/// Plane when sending keys. Setting the value via JavaScript is a possible workaround.
 #[derive(Debug)]
 pub struct TypingData {
     data: Vec<char>,
@@ -232,3 +239,25 @@ impl Add<TypingData> for String {
         TypingData::from(self) + rhs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typing_data_unicode() {
+        let data = TypingData::from("a👍é");
+        assert_eq!(data.as_vec(), vec!['a', '👍', 'é']);
+        assert_eq!(data.to_string(), "a👍é");
+
+        // Each code point should serialize as a complete string (not a surrogate half).
+        let keys = serde_json::to_value(data.as_vec()).unwrap();
+        assert_eq!(keys, serde_json::json!(["a", "👍", "é"]));
+    }
+
+    #[test]
+    fn test_typing_data_emoji_with_key() {
+        let data = "👍🏽" + Key::Enter;
+        assert_eq!(data.as_vec(), vec!['👍', '\u{1f3fd}', Key::Enter.value()]);
+    }
+}
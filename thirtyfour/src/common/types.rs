@@ -4,7 +4,7 @@ use std::future::Future;
 use std::sync::Arc;
 use std::{fmt, time::Duration};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::error::WebDriverResult;
 use crate::WebElement;
@@ -119,6 +119,16 @@ impl ElementRect {
     pub fn center(&self) -> (f64, f64) {
         (self.x + (self.width / 2.0), self.y + (self.height / 2.0))
     }
+
+    /// Return a new rectangle with all coordinates and dimensions multiplied by `factor`.
+    pub fn scale(&self, factor: f64) -> ElementRect {
+        ElementRect {
+            x: self.x * factor,
+            y: self.y * factor,
+            width: self.width * factor,
+            height: self.height * factor,
+        }
+    }
 }
 
 /// Helper to Serialize/Deserialize ElementRef from JSON Value.
@@ -137,6 +147,15 @@ pub enum ElementRef {
         #[serde(rename = "shadow-6066-11e4-a52e-4f735466cecf")]
         id: String,
     },
+    /// Reference for a regular element, using the legacy `ELEMENT` key from the
+    /// JSON Wire Protocol that is still used by some older drivers.
+    ///
+    /// If a reference contains both keys, it is parsed as `ElementRef::Element`.
+    LegacyElement {
+        /// Element id, as returned by the webdriver.
+        #[serde(rename = "ELEMENT")]
+        id: String,
+    },
 }
 
 impl ElementRef {
@@ -149,6 +168,9 @@ impl ElementRef {
             ElementRef::ShadowElement {
                 id,
             } => id,
+            ElementRef::LegacyElement {
+                id,
+            } => id,
         }
     }
 }
@@ -256,23 +278,73 @@ impl fmt::Display for WindowType {
     }
 }
 
-/// Rectangle position and dimensions.
+/// Information about an open tab or window.
+///
+/// See `WebDriver::tabs()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabInfo {
+    /// The window handle, for use with `WebDriver::switch_to_window()`.
+    pub handle: WindowHandle,
+    /// The current URL, if known.
+    pub url: Option<String>,
+    /// The current title, if known.
+    pub title: Option<String>,
+    /// The target type reported by the browser (e.g. "page"), if known.
+    pub target_type: Option<String>,
+}
+
+/// The state to set for a browser permission.
+///
+/// See `WebDriver::set_permission()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    /// The permission is granted without prompting.
+    Granted,
+    /// The permission is denied without prompting.
+    Denied,
+    /// The user will be prompted for the permission.
+    Prompt,
+}
+
+impl fmt::Display for PermissionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PermissionState::Granted => "granted",
+                PermissionState::Denied => "denied",
+                PermissionState::Prompt => "prompt",
+            }
+        )
+    }
+}
+
+/// Window position and dimensions, in pixels.
+///
+/// Some drivers return fractional values for the window rect. These are rounded to the
+/// nearest integer when deserializing.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Rect {
+pub struct WindowRect {
     /// The x coordinate of the top-left corner.
+    #[serde(deserialize_with = "deserialize_rounded_i64")]
     pub x: i64,
     /// The y coordinate of the top-left corner.
+    #[serde(deserialize_with = "deserialize_rounded_i64")]
     pub y: i64,
-    /// The rectangle width.
+    /// The window width.
+    #[serde(deserialize_with = "deserialize_rounded_i64")]
     pub width: i64,
-    /// The rectangle height.
+    /// The window height.
+    #[serde(deserialize_with = "deserialize_rounded_i64")]
     pub height: i64,
 }
 
-impl Rect {
-    /// Create a new `Rect`.
+impl WindowRect {
+    /// Create a new `WindowRect`.
     pub fn new(x: i64, y: i64, width: i64, height: i64) -> Self {
-        Rect {
+        WindowRect {
             x,
             y,
             width,
@@ -281,6 +353,25 @@ impl Rect {
     }
 }
 
+/// Rectangle position and dimensions.
+#[deprecated(since = "0.33.0", note = "Rect has been renamed to WindowRect")]
+pub type Rect = WindowRect;
+
+/// Deserialize a JSON number as i64, rounding to the nearest integer if it is a float.
+fn deserialize_rounded_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let n = serde_json::Number::deserialize(deserializer)?;
+    match n.as_i64() {
+        Some(v) => Ok(v),
+        None => n
+            .as_f64()
+            .map(|v| v.round() as i64)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid number: {n}"))),
+    }
+}
+
 /// Generic element query function that returns some type T.
 pub trait ElementQueryFn<T: 'static>: Send + Sync + 'static {
     /// the future returned by ElementQueryFn::query
@@ -389,8 +480,8 @@ impl OptionRect {
     }
 }
 
-impl From<Rect> for OptionRect {
-    fn from(value: Rect) -> Self {
+impl From<WindowRect> for OptionRect {
+    fn from(value: WindowRect) -> Self {
         OptionRect {
             x: Some(value.x),
             y: Some(value.y),
@@ -479,6 +570,156 @@ pub struct WebDriverStatus {
     pub message: String,
 }
 
+/// Timing information for the navigation that loaded the current page, from the
+/// Navigation Timing API.
+///
+/// All times are in milliseconds, relative to the start of the navigation. Events that
+/// have not happened yet (for example `load_event_end` while the page is still loading)
+/// have a value of 0. Fields not reported by the browser also default to 0.
+///
+/// See `WebDriver::navigation_timing()`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NavigationTiming {
+    /// The URL of the page.
+    #[serde(rename = "name")]
+    pub url: String,
+    /// The navigation type, such as "navigate", "reload" or "back_forward".
+    #[serde(rename = "type")]
+    pub navigation_type: String,
+    /// The number of redirects followed before the page was loaded.
+    pub redirect_count: u32,
+    /// The time at which the navigation started. This is normally 0.
+    pub start_time: f64,
+    /// The time from `start_time` to `load_event_end`.
+    pub duration: f64,
+    /// The time at which the first redirect started.
+    pub redirect_start: f64,
+    /// The time at which the last redirect finished.
+    pub redirect_end: f64,
+    /// The time at which the browser was ready to fetch the document.
+    pub fetch_start: f64,
+    /// The time at which the DNS lookup started.
+    pub domain_lookup_start: f64,
+    /// The time at which the DNS lookup finished.
+    pub domain_lookup_end: f64,
+    /// The time at which the connection to the server started.
+    pub connect_start: f64,
+    /// The time at which the connection to the server was established.
+    pub connect_end: f64,
+    /// The time at which the TLS handshake started, or 0 if the connection is not secure.
+    pub secure_connection_start: f64,
+    /// The time at which the request for the document was sent.
+    pub request_start: f64,
+    /// The time at which the first byte of the response was received.
+    pub response_start: f64,
+    /// The time at which the last byte of the response was received.
+    pub response_end: f64,
+    /// The time at which the document became interactive.
+    pub dom_interactive: f64,
+    /// The time at which the `DOMContentLoaded` event started.
+    pub dom_content_loaded_event_start: f64,
+    /// The time at which the `DOMContentLoaded` event finished.
+    pub dom_content_loaded_event_end: f64,
+    /// The time at which the document became complete.
+    pub dom_complete: f64,
+    /// The time at which the `load` event started.
+    pub load_event_start: f64,
+    /// The time at which the `load` event finished.
+    pub load_event_end: f64,
+}
+
+/// The page orientation used when printing a page to PDF.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrintOrientation {
+    /// Portrait orientation (the default).
+    #[default]
+    Portrait,
+    /// Landscape orientation.
+    Landscape,
+}
+
+/// The paper size used when printing a page to PDF, in centimetres.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrintPageSize {
+    /// The page width.
+    pub width: f64,
+    /// The page height.
+    pub height: f64,
+}
+
+impl Default for PrintPageSize {
+    /// US Letter, the WebDriver default.
+    fn default() -> Self {
+        Self {
+            width: 21.59,
+            height: 27.94,
+        }
+    }
+}
+
+/// The page margins used when printing a page to PDF, in centimetres.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrintMargins {
+    /// The top margin.
+    pub top: f64,
+    /// The bottom margin.
+    pub bottom: f64,
+    /// The left margin.
+    pub left: f64,
+    /// The right margin.
+    pub right: f64,
+}
+
+impl Default for PrintMargins {
+    /// 1cm on every side, the WebDriver default.
+    fn default() -> Self {
+        Self {
+            top: 1.0,
+            bottom: 1.0,
+            left: 1.0,
+            right: 1.0,
+        }
+    }
+}
+
+/// The parameters for printing a page to PDF.
+///
+/// See `WebDriver::print_page()`. The defaults match the WebDriver specification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintParameters {
+    /// The page orientation.
+    pub orientation: PrintOrientation,
+    /// The scale of the page contents, between 0.1 and 2.
+    pub scale: f64,
+    /// Whether to print background colors and images.
+    pub background: bool,
+    /// The paper size.
+    pub page: PrintPageSize,
+    /// The page margins.
+    pub margin: PrintMargins,
+    /// Whether to shrink the page contents to fit the page width.
+    pub shrink_to_fit: bool,
+    /// The pages to print, such as `"1"` or `"2-4"`. All pages are printed if empty.
+    pub page_ranges: Vec<String>,
+}
+
+impl Default for PrintParameters {
+    fn default() -> Self {
+        Self {
+            orientation: PrintOrientation::default(),
+            scale: 1.0,
+            background: false,
+            page: PrintPageSize::default(),
+            margin: PrintMargins::default(),
+            shrink_to_fit: true,
+            page_ranges: Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -502,4 +743,110 @@ mod tests {
         assert_matches!(&elem_ref, ElementRef::ShadowElement { id: x} if x == id);
         assert_eq!(elem_ref.id(), id);
     }
+
+    #[test]
+    fn test_legacy_element_ref() {
+        let value = json!({ "ELEMENT": "legacy-id" });
+        let elem_ref: ElementRef = serde_json::from_value(value).unwrap();
+        assert_matches!(&elem_ref, ElementRef::LegacyElement { id } if id == "legacy-id");
+        assert_eq!(elem_ref.id(), "legacy-id");
+
+        // The W3C key takes priority when both are present.
+        let value =
+            json!({ "ELEMENT": "legacy-id", "element-6066-11e4-a52e-4f735466cecf": "w3c-id" });
+        let elem_ref: ElementRef = serde_json::from_value(value).unwrap();
+        assert_matches!(&elem_ref, ElementRef::Element { id } if id == "w3c-id");
+    }
+
+    #[test]
+    fn test_element_rect_scale() {
+        let rect = ElementRect {
+            x: 10.0,
+            y: 20.5,
+            width: 100.0,
+            height: 50.0,
+        };
+        let scaled = rect.scale(2.0);
+        assert_eq!(scaled.x, 20.0);
+        assert_eq!(scaled.y, 41.0);
+        assert_eq!(scaled.width, 200.0);
+        assert_eq!(scaled.height, 100.0);
+    }
+
+    #[test]
+    fn test_window_rect_deserialize() {
+        let rect: WindowRect =
+            serde_json::from_value(json!({"x": 10, "y": -20, "width": 800, "height": 600}))
+                .unwrap();
+        assert_eq!(rect, WindowRect::new(10, -20, 800, 600));
+
+        let rect: WindowRect = serde_json::from_value(
+            json!({"x": 10.4, "y": -20.6, "width": 800.5, "height": 599.9999}),
+        )
+        .unwrap();
+        assert_eq!(rect, WindowRect::new(10, -21, 801, 600));
+
+        let result: Result<WindowRect, _> =
+            serde_json::from_value(json!({"x": "10", "y": 0, "width": 0, "height": 0}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_print_parameters_serialize() {
+        let params = PrintParameters {
+            orientation: PrintOrientation::Landscape,
+            page_ranges: vec!["1-2".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(params).unwrap(),
+            json!({
+                "orientation": "landscape",
+                "scale": 1.0,
+                "background": false,
+                "page": {"width": 21.59, "height": 27.94},
+                "margin": {"top": 1.0, "bottom": 1.0, "left": 1.0, "right": 1.0},
+                "shrinkToFit": true,
+                "pageRanges": ["1-2"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_navigation_timing_deserialize() {
+        // A trimmed copy of `performance.getEntriesByType("navigation")[0].toJSON()`.
+        let timing: NavigationTiming = serde_json::from_value(json!({
+            "name": "https://example.com/",
+            "entryType": "navigation",
+            "startTime": 0,
+            "duration": 312.5,
+            "initiatorType": "navigation",
+            "fetchStart": 1.2,
+            "domainLookupStart": 3.4,
+            "domainLookupEnd": 5.6,
+            "connectStart": 5.6,
+            "connectEnd": 40.1,
+            "requestStart": 40.3,
+            "responseStart": 120.7,
+            "responseEnd": 125.0,
+            "domInteractive": 200.25,
+            "domContentLoadedEventStart": 201.0,
+            "domContentLoadedEventEnd": 202.5,
+            "domComplete": 310.0,
+            "loadEventStart": 310.1,
+            "loadEventEnd": 312.5,
+            "type": "navigate",
+            "redirectCount": 1
+        }))
+        .unwrap();
+        assert_eq!(timing.url, "https://example.com/");
+        assert_eq!(timing.navigation_type, "navigate");
+        assert_eq!(timing.redirect_count, 1);
+        assert_eq!(timing.response_end, 125.0);
+        assert_eq!(timing.dom_content_loaded_event_end, 202.5);
+        assert_eq!(timing.load_event_end, 312.5);
+        // Fields not reported by the browser default to 0.
+        assert_eq!(timing.secure_connection_start, 0.0);
+        assert_eq!(timing.redirect_start, 0.0);
+    }
 }
@@ -131,6 +131,9 @@ pub enum WebDriverError {
     DecodeError(#[from] DecodeError),
     #[error("IO Error: {0}")]
     IoError(#[from] std::io::Error),
+    #[cfg(feature = "image")]
+    #[error("Image error: {0}")]
+    ImageError(#[from] image::ImageError),
     #[error("The WebDriver request returned an error: {0}")]
     HttpError(String),
     #[error("The WebDriver response does not conform to the W3C WebDriver spec: {0}")]
@@ -151,6 +154,21 @@ pub enum WebDriverError {
     InvalidSelector(WebDriverErrorInfo),
     #[error("The WebDriver session id is invalid: {0}")]
     InvalidSessionId(WebDriverErrorInfo),
+    /// The Javascript code threw an exception.
+    ///
+    /// `message` is the exception's own message, without the driver's wrapper text.
+    /// `stack` is the stacktrace reported by the driver, if any. Geckodriver reports the
+    /// JavaScript stack, whereas chromedriver reports its own native stack.
+    #[error("The Javascript code returned an error: {message}{info}")]
+    JavaScript {
+        message: String,
+        stack: Option<String>,
+        info: WebDriverErrorInfo,
+    },
+    /// The Javascript code threw an exception.
+    ///
+    /// This error is no longer returned. Match `WebDriverError::JavaScript` instead.
+    #[deprecated(since = "0.33.0", note = "Javascript errors are now returned as JavaScript")]
     #[error("The Javascript code returned an error: {0}")]
     JavascriptError(WebDriverErrorInfo),
     #[error("Unable to scroll the element into the viewport: {0}")]
@@ -161,8 +179,14 @@ pub enum WebDriverError {
     NoSuchCookie(WebDriverErrorInfo),
     #[error("Element not found: {0}")]
     NoSuchElement(WebDriverErrorInfo),
+    /// The current frame does not exist, for example because it was removed from the page.
+    ///
+    /// Switch to the default content or the parent frame to recover.
     #[error("Frame not found: {0}")]
     NoSuchFrame(WebDriverErrorInfo),
+    /// The current window does not exist, for example because it was closed.
+    ///
+    /// Switch to another window (see `WebDriver::windows()`) to recover.
     #[error("Window not found: {0}")]
     NoSuchWindow(WebDriverErrorInfo),
     #[error("The Javascript code did not complete within the script timeout (see WebDriver::set_script_timeout()): {0}")]
@@ -195,6 +219,36 @@ pub enum WebDriverError {
     CommandSendError(String),
     #[error("Could not create session: {0}")]
     SessionCreateError(String),
+    #[error("Failed to reset the session: {}", join_reset_errors(.0))]
+    ResetFailed(Vec<ResetStepError>),
+    #[error("The WebDriver response exceeded the maximum size of {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("The element {element} does not have the attribute '{name}'")]
+    MissingAttribute {
+        element: String,
+        name: String,
+    },
+    #[error("Invalid WebDriver server url: {0}")]
+    InvalidUrl(String),
+}
+
+/// A step of `WebDriver::reset()` that failed.
+#[derive(Debug)]
+pub struct ResetStepError {
+    /// The name of the step that failed.
+    pub step: &'static str,
+    /// The error returned by the step.
+    pub error: WebDriverError,
+}
+
+impl Display for ResetStepError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.step, self.error)
+    }
+}
+
+fn join_reset_errors(errors: &[ResetStepError]) -> String {
+    errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
 }
 
 impl WebDriverError {
@@ -221,6 +275,19 @@ impl WebDriverError {
             }
         }
 
+        // Chromedriver reports some detached frames and closed windows as unknown errors.
+        if error == "unknown error" {
+            let message = &payload.value.message;
+            if message.contains("target frame detached") {
+                return WebDriverError::NoSuchFrame(payload);
+            }
+            if message.contains("web view not found")
+                || message.contains("target window already closed")
+            {
+                return WebDriverError::NoSuchWindow(payload);
+            }
+        }
+
         match error.as_str() {
             "element click intercepted" => WebDriverError::ElementClickIntercepted(payload),
             "element not interactable" => WebDriverError::ElementNotInteractable(payload),
@@ -230,7 +297,11 @@ impl WebDriverError {
             "invalid element state" => WebDriverError::InvalidElementState(payload),
             "invalid selector" => WebDriverError::InvalidSelector(payload),
             "invalid session id" => WebDriverError::InvalidSessionId(payload),
-            "javascript error" => WebDriverError::JavascriptError(payload),
+            "javascript error" => WebDriverError::JavaScript {
+                message: exception_message(&payload.value.message),
+                stack: payload.value.stacktrace.clone().filter(|s| !s.is_empty()),
+                info: payload,
+            },
             "move target out of bounds" => WebDriverError::MoveTargetOutOfBounds(payload),
             "no such alert" => WebDriverError::NoSuchAlert(payload),
             "no such cookie" => WebDriverError::NoSuchCookie(payload),
@@ -251,6 +322,61 @@ impl WebDriverError {
             _ => WebDriverError::NotInSpec(payload),
         }
     }
+
+    /// The error information returned by the WebDriver server, if this error came from
+    /// the server.
+    pub(crate) fn info_mut(&mut self) -> Option<&mut WebDriverErrorInfo> {
+        match self {
+            WebDriverError::NotInSpec(info)
+            | WebDriverError::ElementClickIntercepted(info)
+            | WebDriverError::ElementNotInteractable(info)
+            | WebDriverError::InsecureCertificate(info)
+            | WebDriverError::InvalidArgument(info)
+            | WebDriverError::InvalidCookieDomain(info)
+            | WebDriverError::InvalidElementState(info)
+            | WebDriverError::InvalidSelector(info)
+            | WebDriverError::InvalidSessionId(info)
+            | WebDriverError::JavaScript {
+                info,
+                ..
+            }
+            | WebDriverError::MoveTargetOutOfBounds(info)
+            | WebDriverError::NoSuchAlert(info)
+            | WebDriverError::NoSuchCookie(info)
+            | WebDriverError::NoSuchElement(info)
+            | WebDriverError::NoSuchFrame(info)
+            | WebDriverError::NoSuchWindow(info)
+            | WebDriverError::ScriptTimeout(info)
+            | WebDriverError::SessionNotCreated(info)
+            | WebDriverError::StaleElementReference(info)
+            | WebDriverError::WebDriverTimeout(info)
+            | WebDriverError::UnableToSetCookie(info)
+            | WebDriverError::UnableToCaptureScreen(info)
+            | WebDriverError::UnexpectedAlertOpen(info)
+            | WebDriverError::UnknownCommand(info)
+            | WebDriverError::UnknownError(info)
+            | WebDriverError::UnknownMethod(info)
+            | WebDriverError::UnsupportedOperation(info) => Some(info),
+            _ => None,
+        }
+    }
+}
+
+/// Extract the exception message from the message of a javascript error response.
+fn exception_message(message: &str) -> String {
+    // chromedriver: "javascript error: Boom\n  (Session info: chrome=120.0.6099.109)"
+    let message = message.strip_prefix("javascript error: ").unwrap_or(message);
+    let message = message.split("(Session info:").next().unwrap_or_default().trim_end();
+
+    // geckodriver: "TypeError: foo is undefined"
+    match message.split_once(": ") {
+        Some((name, rest))
+            if name.ends_with("Error") && name.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            rest.to_string()
+        }
+        _ => message.to_string(),
+    }
 }
 
 /// Convenience function to construct a simulated NoSuchElement error.
@@ -279,3 +405,92 @@ impl From<serde_json::Error> for WebDriverError {
         WebDriverError::Json(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_js_error(value: serde_json::Value) -> (String, Option<String>) {
+        let body = json!({ "value": value }).to_string();
+        match WebDriverError::parse(500, body) {
+            WebDriverError::JavaScript {
+                message,
+                stack,
+                info,
+            } => {
+                assert_eq!(info.status, 500);
+                (message, stack)
+            }
+            e => panic!("expected javascript error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_chromedriver_javascript_error() {
+        let (message, stack) = parse_js_error(json!({
+            "error": "javascript error",
+            "message": "javascript error: Boom\n  (Session info: chrome=120.0.6099.109)",
+            "stacktrace": "#0 0x55ab3f2e6f83 <unknown>\n#1 0x55ab3efc6cf7 <unknown>\n"
+        }));
+        assert_eq!(message, "Boom");
+        assert!(stack.unwrap().starts_with("#0 0x55ab3f2e6f83"));
+
+        let (message, stack) = parse_js_error(json!({
+            "error": "javascript error",
+            "message": "javascript error: Cannot read properties of null (reading 'x')",
+            "stacktrace": ""
+        }));
+        assert_eq!(message, "Cannot read properties of null (reading 'x')");
+        assert_eq!(stack, None);
+    }
+
+    #[test]
+    fn test_geckodriver_javascript_error() {
+        let (message, stack) = parse_js_error(json!({
+            "error": "javascript error",
+            "message": "Error: Boom",
+            "stacktrace": "@http://localhost:8081/sample_page.html, line 2 > Function:1:7\n"
+        }));
+        assert_eq!(message, "Boom");
+        assert!(stack.unwrap().contains("sample_page.html"));
+
+        let (message, _) = parse_js_error(json!({
+            "error": "javascript error",
+            "message": "TypeError: document.foo is undefined",
+            "stacktrace": "@debugger eval code:1:10\n"
+        }));
+        assert_eq!(message, "document.foo is undefined");
+
+        // Thrown values that are not errors are passed through unchanged.
+        let (message, _) = parse_js_error(json!({
+            "error": "javascript error",
+            "message": "Boom: not an Error",
+        }));
+        assert_eq!(message, "Boom: not an Error");
+    }
+
+    #[test]
+    fn test_no_such_window_and_frame() {
+        let parse = |error: &str, message: &str| {
+            let body = json!({ "value": { "error": error, "message": message } }).to_string();
+            WebDriverError::parse(404, body)
+        };
+
+        let err = parse("no such window", "no such window: target window already closed");
+        match err {
+            WebDriverError::NoSuchWindow(info) => assert_eq!(info.status, 404),
+            e => panic!("expected no such window, got {e:?}"),
+        }
+        let err = parse("no such frame", "no such frame: frame was removed");
+        assert!(matches!(err, WebDriverError::NoSuchFrame(_)), "{err:?}");
+
+        // Chromedriver sometimes reports these as unknown errors.
+        let err = parse("unknown error", "unknown error: target frame detached");
+        assert!(matches!(err, WebDriverError::NoSuchFrame(_)), "{err:?}");
+        let err = parse("unknown error", "unknown error: web view not found");
+        assert!(matches!(err, WebDriverError::NoSuchWindow(_)), "{err:?}");
+        let err = parse("unknown error", "unknown error: cannot determine loading status");
+        assert!(matches!(err, WebDriverError::UnknownError(_)), "{err:?}");
+    }
+}
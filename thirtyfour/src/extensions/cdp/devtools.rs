@@ -1,8 +1,9 @@
 use super::ChromeCommand;
 use super::NetworkConditions;
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
 use crate::session::handle::SessionHandle;
 use serde_json::{json, Value};
+use std::path::Path;
 use std::sync::Arc;
 
 /// The ChromeDevTools struct allows you to interact with Chromium-based browsers via
@@ -141,6 +142,117 @@ impl ChromeDevTools {
         Ok(())
     }
 
+    /// Allow downloads, saving them to the specified directory.
+    ///
+    /// This uses the CDP `Browser.setDownloadBehavior` command, which also works for
+    /// headless Chrome. See `WebDriver::wait_for_download()` for waiting until a download
+    /// has completed.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::path::Path;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_download_directory(Path::new("/tmp/downloads")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_download_directory(&self, dir: &Path) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Browser.setDownloadBehavior",
+            json!({"behavior": "allow", "downloadPath": dir}),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Ask the browser to free as much memory as it can, which is useful for long-running
+    /// sessions that accumulate memory.
+    ///
+    /// This runs a full garbage collection with the CDP `HeapProfiler.collectGarbage`
+    /// command, and then `Memory.forciblyPurgeJavaScriptMemory`. Both are best-effort:
+    /// the browser decides how much memory is actually released.
+    ///
+    /// Returns `WebDriverError::UnsupportedOperation` if the browser is not Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.purge_memory().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn purge_memory(&self) -> WebDriverResult<()> {
+        self.execute_chromium_only("HeapProfiler.collectGarbage", json!({})).await?;
+        self.execute_chromium_only("Memory.forciblyPurgeJavaScriptMemory", json!({})).await?;
+        Ok(())
+    }
+
+    /// Slow down the CPU by the specified factor, for performance testing.
+    ///
+    /// A `rate` of 1.0 means no throttling, and 4.0 means the CPU runs 4 times slower.
+    /// This uses the CDP `Emulation.setCPUThrottlingRate` command. Throttling is
+    /// approximate, and the effect depends on the load on the machine running the browser.
+    ///
+    /// Returns `WebDriverError::UnsupportedOperation` if the browser is not Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_cpu_throttling_rate(4.0).await?;
+    /// // ... measure the page ...
+    /// dev_tools.set_cpu_throttling_rate(1.0).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_cpu_throttling_rate(&self, rate: f64) -> WebDriverResult<()> {
+        self.execute_chromium_only("Emulation.setCPUThrottlingRate", json!({"rate": rate})).await?;
+        Ok(())
+    }
+
+    /// Execute the specified command, returning `WebDriverError::UnsupportedOperation`
+    /// if the WebDriver does not support CDP commands.
+    async fn execute_chromium_only(&self, cmd: &str, cmd_args: Value) -> WebDriverResult<Value> {
+        self.execute_cdp_with_params(cmd, cmd_args).await.map_err(|e| match e {
+            WebDriverError::UnknownCommand(info) | WebDriverError::UnknownMethod(info) => {
+                WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                    "{cmd} requires a Chromium-based browser: {}",
+                    info.value.message
+                )))
+            }
+            e => e,
+        })
+    }
+
     /// Execute the specified command without parameters.
     /// For commands that require parameters, use `execute_cdp_with_params()` instead.
     ///
@@ -236,3 +348,54 @@ impl ChromeDevTools {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::{MockClient, Reply};
+
+    /// Responds to CDP commands as a Chromium driver, or as an unknown command.
+    fn dev_tools(supported: bool) -> (Arc<MockClient>, ChromeDevTools) {
+        let reply = match supported {
+            true => Reply::Value(json!({})),
+            false => Reply::error(404, "unknown command", "nope"),
+        };
+        let client = Arc::new(MockClient::new().reply("", reply));
+        (client.clone(), ChromeDevTools::new(client.handle()))
+    }
+
+    #[tokio::test]
+    async fn test_purge_memory() {
+        let (client, dev_tools) = dev_tools(true);
+        dev_tools.purge_memory().await.unwrap();
+        let commands = client.bodies("");
+        let names: Vec<_> = commands.iter().map(|c| c["cmd"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec!["HeapProfiler.collectGarbage", "Memory.forciblyPurgeJavaScriptMemory"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_cpu_throttling_rate() {
+        let (client, dev_tools) = dev_tools(true);
+        dev_tools.set_cpu_throttling_rate(4.0).await.unwrap();
+        assert_eq!(
+            client.bodies("")[0],
+            json!({"cmd": "Emulation.setCPUThrottlingRate", "params": {"rate": 4.0}})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_browser() {
+        let (_, dev_tools) = dev_tools(false);
+        assert!(matches!(
+            dev_tools.purge_memory().await,
+            Err(WebDriverError::UnsupportedOperation(_))
+        ));
+        assert!(matches!(
+            dev_tools.set_cpu_throttling_rate(2.0).await,
+            Err(WebDriverError::UnsupportedOperation(_))
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+use super::poller::DEFAULT_POLL_INTERVAL;
+use super::{ElementPollerWithTimeout, ElementQuery, ElementQuerySource};
+use crate::error::WebDriverResult;
+use crate::session::handle::SessionHandle;
+use crate::{By, WebElement};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fluent interface for waiting on elements using an explicit timeout.
+///
+/// This is a thin convenience wrapper around [`ElementQuery`] that uses an
+/// [`ElementPollerWithTimeout`] configured with the specified timeout.
+///
+/// See `WebDriver::wait()` rather than creating this directly.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// # use std::time::Duration;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let elem = driver.wait(Duration::from_secs(10)).until_displayed(By::Id("button1")).await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebDriverWait {
+    handle: Arc<SessionHandle>,
+    timeout: Duration,
+    interval: Duration,
+}
+
+impl WebDriverWait {
+    /// Create a new `WebDriverWait` with the specified timeout.
+    pub fn new(handle: Arc<SessionHandle>, timeout: Duration) -> Self {
+        Self {
+            handle,
+            timeout,
+            interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Set the minimum interval between polls. The default is 500ms.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    fn query(&self, by: By) -> ElementQuery {
+        ElementQuery::new(
+            ElementQuerySource::Driver(self.handle.clone()),
+            by,
+            Arc::new(ElementPollerWithTimeout::new(self.timeout, self.interval)),
+        )
+    }
+
+    /// Wait until at least one element matching the selector is present,
+    /// and return the first one.
+    pub async fn until_present(&self, by: By) -> WebDriverResult<WebElement> {
+        self.query(by).first().await
+    }
+
+    /// Wait until at least one element matching the selector is present,
+    /// and return all matching elements.
+    pub async fn until_all_present(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
+        self.query(by).all_from_selector_required().await
+    }
+
+    /// Wait until an element matching the selector is displayed, and return it.
+    pub async fn until_displayed(&self, by: By) -> WebDriverResult<WebElement> {
+        self.query(by).and_displayed().first().await
+    }
+}
+
+#[cfg(test)]
+/// This function checks if the public async methods implement Send. It is not intended to be executed.
+async fn _test_is_send() -> WebDriverResult<()> {
+    use crate::prelude::*;
+
+    // Helper methods
+    fn is_send_val<T: Send>(_val: &T) {}
+
+    // Pre values
+    let caps = DesiredCapabilities::chrome();
+    let driver = WebDriver::new("http://localhost:4444", caps).await?;
+
+    // WebDriverWait
+    let wait = driver.wait(Duration::from_secs(1));
+    is_send_val(&wait.until_present(By::Css("div")));
+    is_send_val(&wait.until_all_present(By::Css("div")));
+    is_send_val(&wait.until_displayed(By::Css("div")));
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use super::conditions::{collect_arg_slice, handle_errors, negate};
 use super::{conditions, ElementPollerNoWait, ElementPollerWithTimeout, IntoElementPoller};
-use crate::error::WebDriverError;
+use crate::components::SelectElement;
+use crate::error::{WebDriverError, WebDriverErrorInfo};
 use crate::prelude::WebDriverResult;
 use crate::session::handle::SessionHandle;
 use crate::IntoArcStr;
@@ -46,7 +47,7 @@ fn no_such_element(selectors: &[ElementSelector], description: &str) -> WebDrive
 }
 
 /// Filter the specified elements using the specified filters.
-pub async fn filter_elements<'a, I, P, Ref>(
+pub async fn filter_elements<I, P, Ref>(
     mut elements: Vec<WebElement>,
     filters: I,
 ) -> WebDriverResult<Vec<WebElement>>
@@ -120,9 +121,10 @@ pub enum ElementQuerySource {
 }
 
 /// Options for wait characteristics for an element query.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum ElementQueryWaitOptions {
     /// Use the default poller.
+    #[default]
     WaitDefault,
     /// Use a poller with the specified timeout and interval.
     Wait {
@@ -135,12 +137,6 @@ pub enum ElementQueryWaitOptions {
     NoWait,
 }
 
-impl Default for ElementQueryWaitOptions {
-    fn default() -> Self {
-        Self::WaitDefault
-    }
-}
-
 /// All options applicable to an ElementQuery.
 ///
 /// These are stored in a separate struct so that they can be constructed
@@ -364,6 +360,23 @@ impl ElementQuery {
         }
     }
 
+    /// Return the first WebElement that matches any selector (including filters), wrapped
+    /// in a [`SelectElement`].
+    ///
+    /// Returns Err(WebDriverError::NoSuchElement) if no elements match, or
+    /// Err(WebDriverError::InvalidArgument) if the first element is not a `<select>`
+    /// element.
+    pub async fn first_select(&self) -> WebDriverResult<SelectElement> {
+        let element = self.first().await?;
+        let tag_name = element.tag_name().await?;
+        if !tag_name.eq_ignore_ascii_case("select") {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                "element is not a <select> element (found <{tag_name}>)"
+            ))));
+        }
+        SelectElement::new(&element).await
+    }
+
     /// Return only a single WebElement that matches any selector (including filters).
     ///
     /// This method requires that only one element was found, and will return
@@ -440,6 +453,108 @@ impl ElementQuery {
         disallow_empty!(elements, self)
     }
 
+    /// Wait until at least `n` WebElements match any selector (including filters), and
+    /// return all of them.
+    ///
+    /// This is useful for lists that are rendered lazily, where one matching element
+    /// does not mean the list is complete.
+    ///
+    /// Returns Err(WebDriverError::Timeout), including the number of elements found by
+    /// the last poll, if fewer than `n` elements match before the timeout.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let rows = driver.query(By::Css("#results tr")).until_count_at_least(3).await?;
+    /// assert!(rows.len() >= 3);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn until_count_at_least(&self, n: usize) -> WebDriverResult<Vec<WebElement>> {
+        self.run_count_poller(&format!("at least {n}"), |count| count >= n).await
+    }
+
+    /// Wait until exactly `n` WebElements match any selector (including filters), and
+    /// return them.
+    ///
+    /// Returns Err(WebDriverError::Timeout), including the number of elements found by
+    /// the last poll, if the number of matching elements is not `n` before the timeout.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let rows = driver.query(By::Css("#results tr")).until_count(10).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn until_count(&self, n: usize) -> WebDriverResult<Vec<WebElement>> {
+        self.run_count_poller(&format!("exactly {n}"), |count| count == n).await
+    }
+
+    /// Run the poller until the number of WebElements matched by all selectors satisfies
+    /// the predicate, and return them.
+    ///
+    /// Unlike `run_poller()`, the elements are collected afresh on each poll, so that
+    /// elements that have been removed from the page are not counted.
+    async fn run_count_poller(
+        &self,
+        expected: &str,
+        predicate: impl Fn(usize) -> bool,
+    ) -> WebDriverResult<Vec<WebElement>> {
+        let mut poller = self.poller.start();
+        loop {
+            let mut elements = IndexMap::new();
+            for selector in &self.selectors {
+                let mut new_elements =
+                    match self.fetch_elements_from_source(selector.by.clone()).await {
+                        Ok(x) => x,
+                        Err(WebDriverError::NoSuchElement(_)) => Vec::new(),
+                        Err(e) => return Err(e),
+                    };
+
+                if !new_elements.is_empty() {
+                    new_elements = filter_elements(new_elements, &selector.filters).await?;
+                }
+
+                for element in new_elements {
+                    elements.insert(element.element_id(), element);
+                }
+            }
+
+            if predicate(elements.len()) {
+                return Ok(elements.into_values().collect());
+            }
+
+            if !poller.tick().await {
+                let element_description: Cow<str> = match self.options.description.as_deref() {
+                    None | Some("") => "element(s)".into(),
+                    Some(description) => format!("'{description}' element(s)").into(),
+                };
+                return Err(WebDriverError::Timeout(format!(
+                    "expected {expected} {element_description} using selectors: {}, but found {}",
+                    get_selector_summary(&self.selectors),
+                    elements.len()
+                )));
+            }
+        }
+    }
+
     /// Run the poller for this ElementQuery and return the Vec of WebElements matched.
     ///
     /// NOTE: This function doesn't return a no_such_element error and the caller must handle it.
@@ -714,6 +829,35 @@ impl ElementQuery {
 
     /// Only match elements that have the specified attribute with the specified value.
     /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// The element is located and its attribute checked in the same poll loop, so this
+    /// also waits for the attribute to reach the value.
+    ///
+    /// The attribute name is matched case-insensitively for HTML documents, as with
+    /// `WebElement::attr()`. The value comparison is case-sensitive for `&str` and
+    /// `String`. Use `StringMatch::new(value).case_insensitive()` to ignore case.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::stringmatch::StringMatch;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let menu = driver.query(By::Id("menu")).with_attribute("aria-expanded", "true").first().await?;
+    /// let status = driver
+    ///     .query(By::Id("status"))
+    ///     .with_attribute("data-state", StringMatch::new("ready").case_insensitive())
+    ///     .first()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub fn with_attribute<S, N>(self, attribute_name: S, value: N) -> Self
     where
         S: IntoArcStr,
@@ -775,6 +919,10 @@ impl ElementQuery {
 
     /// Only match elements that have the specified property with the specified value.
     /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// Unlike attribute names, property names are case-sensitive (e.g. `readOnly`).
+    /// The value comparison is case-sensitive for `&str` and `String`.
+    /// Use `StringMatch::new(value).case_insensitive()` to ignore case.
     pub fn with_property<S, N>(self, property_name: S, value: N) -> Self
     where
         S: IntoArcStr,
@@ -961,8 +1109,11 @@ async fn _test_is_send() -> WebDriverResult<()> {
     is_send_val(&query.exists());
     is_send_val(&query.not_exists());
     is_send_val(&query.first());
+    is_send_val(&query.first_select());
     is_send_val(&query.all_from_selector());
     is_send_val(&query.all_from_selector_required());
+    is_send_val(&query.until_count_at_least(2));
+    is_send_val(&query.until_count(2));
 
     Ok(())
 }
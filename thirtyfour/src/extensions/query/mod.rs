@@ -145,9 +145,11 @@
 
 /// Predicates to use for element conditions.
 pub mod conditions;
+mod driver_wait;
 mod element_query;
 mod element_waiter;
 mod poller;
+pub use driver_wait::*;
 pub use element_query::*;
 pub use element_waiter::*;
 pub use poller::*;
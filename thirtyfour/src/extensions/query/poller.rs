@@ -1,7 +1,12 @@
+use crate::error::{WebDriverError, WebDriverResult};
 use crate::support::sleep;
 use std::fmt::Debug;
+use std::future::Future;
 use std::time::{Duration, Instant};
 
+/// The default interval between polls, when only a timeout is specified.
+pub(crate) const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Trait for implementing the element polling strategy.
 ///
 /// Each time the element condition is not met, the `tick()` method will be
@@ -97,6 +102,35 @@ impl IntoElementPoller for ElementPollerNoWait {
     }
 }
 
+/// Repeatedly fetch a value until it satisfies the predicate, or the timeout is reached.
+///
+/// Polling uses `ElementPollerWithTimeout` with the default interval. On timeout, a
+/// `WebDriverError::Timeout` is returned that includes the last observed value.
+pub(crate) async fn poll_value<T, F, Fut, P>(
+    timeout: Duration,
+    description: &str,
+    mut fetch: F,
+    mut predicate: P,
+) -> WebDriverResult<T>
+where
+    T: Debug,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = WebDriverResult<T>>,
+    P: FnMut(&T) -> bool,
+{
+    let mut poller = ElementPollerWithTimeout::new(timeout, DEFAULT_POLL_INTERVAL);
+    loop {
+        let value = fetch().await?;
+        if predicate(&value) {
+            return Ok(value);
+        }
+
+        if !poller.tick().await {
+            return Err(WebDriverError::Timeout(format!("{description} (last value: {value:?})")));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +151,39 @@ mod tests {
         let mut poller = ElementPollerNoWait;
         assert!(!poller.tick().await); // Should instantly return false.
     }
+
+    #[tokio::test]
+    async fn test_poll_value() {
+        let mut count = 0;
+        let value = poll_value(
+            Duration::from_secs(5),
+            "count",
+            || {
+                count += 1;
+                let value = count;
+                async move { Ok(value) }
+            },
+            |x| *x == 2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(value, 2);
+    }
+
+    #[tokio::test]
+    async fn test_poll_value_timeout() {
+        let result = poll_value(
+            Duration::from_millis(100),
+            "waiting for value",
+            || async { Ok("actual") },
+            |x| *x == "expected",
+        )
+        .await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => {
+                assert_eq!(msg, r#"waiting for value (last value: "actual")"#)
+            }
+            x => panic!("unexpected result: {x:?}"),
+        }
+    }
 }
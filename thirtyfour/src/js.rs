@@ -47,3 +47,238 @@ function simulateDragDrop(sourceNode, destinationNode) {
 }
 
 simulateDragDrop(arguments[0], arguments[1]);"#;
+
+/// A javascript function for simulating HTML5 drag and drop using native `DragEvent`s.
+///
+/// Unlike `SIMULATE_DRAG_AND_DROP`, this dispatches the full sequence of
+/// `dragstart`, `dragenter`, `dragover`, `drop` and `dragend` events, all sharing
+/// a single `DataTransfer` object.
+pub const HTML5_DRAG_AND_DROP: &str = r#"
+function html5DragDrop(sourceNode, destinationNode) {
+    var dataTransfer = new DataTransfer();
+
+    function center(node) {
+        var rect = node.getBoundingClientRect();
+        return {
+            x: rect.left + rect.width / 2,
+            y: rect.top + rect.height / 2
+        };
+    }
+
+    function fire(node, type, point) {
+        var event = new DragEvent(type, {
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            dataTransfer: dataTransfer,
+            clientX: point.x,
+            clientY: point.y
+        });
+        return node.dispatchEvent(event);
+    }
+
+    var sourcePoint = center(sourceNode);
+    var destinationPoint = center(destinationNode);
+
+    fire(sourceNode, 'dragstart', sourcePoint);
+    fire(destinationNode, 'dragenter', destinationPoint);
+    fire(destinationNode, 'dragover', destinationPoint);
+    fire(destinationNode, 'drop', destinationPoint);
+    fire(sourceNode, 'dragend', destinationPoint);
+}
+
+html5DragDrop(arguments[0], arguments[1]);"#;
+
+/// A javascript function that returns true if any part of the element is within the viewport.
+pub const IS_IN_VIEWPORT: &str = r#"
+var rect = arguments[0].getBoundingClientRect();
+return rect.bottom > 0 && rect.right > 0 &&
+    rect.top < window.innerHeight && rect.left < window.innerWidth;
+"#;
+
+/// A javascript function that returns the current height of the page.
+pub const SCROLL_HEIGHT: &str = "return document.body.scrollHeight;";
+
+/// A javascript function that scrolls to the bottom of the page.
+pub const SCROLL_TO_BOTTOM: &str = "window.scrollTo(0, document.body.scrollHeight);";
+
+/// A javascript function that returns the rect of each element in the array argument,
+/// relative to the document, matching the WebDriver "Get Element Rect" command.
+pub const ELEMENT_RECTS: &str = r#"
+return arguments[0].map(function (elem) {
+    var rect = elem.getBoundingClientRect();
+    return {
+        x: rect.left + window.pageXOffset,
+        y: rect.top + window.pageYOffset,
+        width: rect.width,
+        height: rect.height
+    };
+});
+"#;
+
+/// A javascript function that returns the PNG data url of a canvas element, or null if the
+/// element is not a canvas.
+pub const CANVAS_TO_DATA_URL: &str = r#"
+var elem = arguments[0];
+if (!(elem instanceof HTMLCanvasElement)) {
+    return null;
+}
+return elem.toDataURL("image/png");
+"#;
+
+/// A javascript function that returns the named property of an element, such as
+/// `nextElementSibling`, or null if it is not set.
+pub const ELEMENT_RELATIVE: &str = "return arguments[0][arguments[1]] || null;";
+
+/// A javascript function that returns the child elements of an element.
+pub const ELEMENT_CHILDREN: &str = "return Array.from(arguments[0].children);";
+
+/// A javascript function that returns the navigation timing entry for the current page,
+/// or null if there is none.
+pub const NAVIGATION_TIMING: &str = r#"
+var entry = performance.getEntriesByType("navigation")[0];
+return entry ? entry.toJSON() : null;
+"#;
+
+/// An async javascript function that reads text from the clipboard, returning either
+/// `{text: "..."}` or `{error: "..."}`.
+pub const READ_CLIPBOARD: &str = r#"
+var done = arguments[arguments.length - 1];
+if (!navigator.clipboard || !navigator.clipboard.readText) {
+    done({ error: "the Clipboard API is not available" });
+    return;
+}
+navigator.clipboard.readText().then(
+    function (text) { done({ text: text }); },
+    function (e) { done({ error: String(e) }); }
+);
+"#;
+
+/// An async javascript function that writes the text argument to the clipboard, returning
+/// either `{}` or `{error: "..."}`.
+pub const WRITE_CLIPBOARD: &str = r#"
+var done = arguments[arguments.length - 1];
+if (!navigator.clipboard || !navigator.clipboard.writeText) {
+    done({ error: "the Clipboard API is not available" });
+    return;
+}
+navigator.clipboard.writeText(arguments[0]).then(
+    function () { done({}); },
+    function (e) { done({ error: String(e) }); }
+);
+"#;
+
+/// A javascript function that dispatches a bubbling event of the specified type on an
+/// element.
+pub const DISPATCH_EVENT: &str = r#"
+arguments[0].dispatchEvent(new Event(arguments[1], { bubbles: true, cancelable: true }));
+"#;
+
+/// A javascript function that sets the value of an element and then dispatches `input`
+/// and `change` events.
+///
+/// The value is set via the prototype's setter rather than the element's own `value`
+/// property, because frameworks such as React wrap the latter to track the value, and
+/// ignore the `input` event if the tracked value has not changed.
+pub const SET_VALUE: &str = r#"
+var elem = arguments[0];
+var descriptor = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(elem), "value");
+if (descriptor && descriptor.set) {
+    descriptor.set.call(elem, arguments[1]);
+} else {
+    elem.value = arguments[1];
+}
+elem.dispatchEvent(new Event("input", { bubbles: true }));
+elem.dispatchEvent(new Event("change", { bubbles: true }));
+"#;
+
+/// A javascript function that returns true if the argument is a time zone name known to
+/// the browser, such as `Europe/London`.
+pub const IS_VALID_TIMEZONE: &str = r#"
+try {
+    new Intl.DateTimeFormat("en-US", { timeZone: arguments[0] });
+    return true;
+} catch (e) {
+    return false;
+}
+"#;
+
+/// A javascript function that tracks network activity in the page, and returns the number
+/// of pending `fetch()` and `XMLHttpRequest` requests and the number of milliseconds since
+/// any request started or finished.
+///
+/// The first call wraps `fetch()` and `XMLHttpRequest`, so only requests started after that
+/// are counted as pending. Resources loaded by the page itself (images, scripts, etc.) are
+/// observed via a `PerformanceObserver`, including those that had already finished.
+pub const NETWORK_ACTIVITY: &str = r#"
+var state = window.__thirtyfourNetwork;
+if (!state) {
+    state = window.__thirtyfourNetwork = { pending: 0, last: performance.now() };
+    var start = function () {
+        state.pending++;
+        state.last = performance.now();
+    };
+    var end = function () {
+        state.pending = Math.max(0, state.pending - 1);
+        state.last = performance.now();
+    };
+    if (window.fetch) {
+        var originalFetch = window.fetch;
+        window.fetch = function () {
+            start();
+            try {
+                var promise = originalFetch.apply(this, arguments);
+            } catch (e) {
+                end();
+                throw e;
+            }
+            promise.then(end, end);
+            return promise;
+        };
+    }
+    if (window.XMLHttpRequest) {
+        var originalSend = XMLHttpRequest.prototype.send;
+        XMLHttpRequest.prototype.send = function () {
+            start();
+            this.addEventListener("loadend", end);
+            try {
+                return originalSend.apply(this, arguments);
+            } catch (e) {
+                this.removeEventListener("loadend", end);
+                end();
+                throw e;
+            }
+        };
+    }
+    if (window.PerformanceObserver) {
+        try {
+            new PerformanceObserver(function (list) {
+                list.getEntries().forEach(function (entry) {
+                    state.last = Math.max(state.last, entry.responseEnd || 0);
+                });
+            }).observe({ type: "resource", buffered: true });
+        } catch (e) {}
+    }
+}
+return { pending: state.pending, idleMs: Math.max(0, performance.now() - state.last) };
+"#;
+
+/// A javascript function that scrolls the element into view and returns a short description
+/// of the element covering its center, such as `div#overlay`, or null if the element itself
+/// (or one of its descendants) would receive a click there.
+pub const OBSCURING_ELEMENT: &str = r#"
+var elem = arguments[0];
+elem.scrollIntoView({ block: "center", inline: "center" });
+var rect = elem.getBoundingClientRect();
+var root = elem.getRootNode();
+var hit = (root.elementFromPoint ? root : document)
+    .elementFromPoint(rect.left + rect.width / 2, rect.top + rect.height / 2);
+if (!hit || hit === elem || elem.contains(hit)) {
+    return null;
+}
+var description = hit.tagName.toLowerCase();
+if (hit.id) {
+    description += '#' + hit.id;
+}
+return description;
+"#;
@@ -32,6 +32,11 @@
 //! * `rustls-tls`: (Default) Use rustls to provide TLS support (via reqwest).
 //! * `native-tls`: Use native TLS (via reqwest).
 //! * `component`: (Default) Enable the `Component` derive macro (via thirtyfour-macros).
+//! * `regex`: Enable waiting for the URL to match a regular expression (via regex).
+//! * `image`: Enable cropping screenshots to a region of the page, and comparing element
+//!   screenshots against a baseline image (via image).
+//! * `unix-socket`: Enable connecting to a WebDriver server via a `unix://` socket url
+//!   (Unix platforms only, via reqwest).
 //!
 //! ## Example
 //!
@@ -145,6 +150,7 @@
 
 // Export types at root level.
 pub use alert::Alert;
+pub use cached_element::CachedWebElement;
 pub use common::cookie;
 pub use common::{
     capabilities::{
@@ -152,7 +158,7 @@ pub use common::{
         chromium::{ChromiumCapabilities, ChromiumLikeCapabilities},
         desiredcapabilities::*,
         edge::EdgeCapabilities,
-        firefox::FirefoxCapabilities,
+        firefox::{FirefoxCapabilities, PrefValue},
         ie::InternetExplorerCapabilities,
         opera::OperaCapabilities,
         safari::SafariCapabilities,
@@ -163,9 +169,15 @@ pub use common::{
     requestdata::*,
     types::*,
 };
-pub use switch_to::SwitchTo;
-pub use web_driver::WebDriver;
-pub use web_element::WebElement;
+#[cfg(feature = "image")]
+pub use image;
+#[cfg(feature = "image")]
+pub use png::DiffResult;
+pub use reset::ResetOptions;
+pub use switch_to::{FrameTarget, SwitchTo};
+pub use web_driver::{WebDriver, WebDriverBuilder};
+pub use web_element::{WebElement, WebElementSeed};
+pub use web_storage::{StorageKind, WebStorage};
 
 /// Allow importing the common types via `use thirtyfour::prelude::*`.
 pub mod prelude {
@@ -200,10 +212,15 @@ pub mod session;
 /// Miscellaneous support functions for `thirtyfour` tests.
 pub mod support;
 
+mod cached_element;
 mod js;
+#[cfg(feature = "image")]
+mod png;
+mod reset;
 mod switch_to;
 mod web_driver;
 mod web_element;
+mod web_storage;
 
 // Re-export StringMatch if needed.
 pub use stringmatch;
@@ -0,0 +1,204 @@
+//! PNG support for cropping and comparing screenshots, using the `image` crate.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// A rectangular region of an image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Decode the specified PNG data.
+pub(crate) fn decode(data: &[u8]) -> WebDriverResult<DynamicImage> {
+    Ok(image::load_from_memory_with_format(data, ImageFormat::Png)?)
+}
+
+/// Encode the specified image as PNG data.
+pub(crate) fn encode(image: &DynamicImage) -> WebDriverResult<Vec<u8>> {
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// Return a new image containing only the specified region.
+pub(crate) fn crop(image: &DynamicImage, region: Region) -> WebDriverResult<DynamicImage> {
+    let in_bounds = region.width > 0
+        && region.height > 0
+        && region.x.checked_add(region.width).is_some_and(|r| r <= image.width())
+        && region.y.checked_add(region.height).is_some_and(|b| b <= image.height());
+    if !in_bounds {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+            "region {region:?} is outside the {}x{} image",
+            image.width(),
+            image.height()
+        ))));
+    }
+    Ok(image.crop_imm(region.x, region.y, region.width, region.height))
+}
+
+/// Compare an image against a baseline, pixel by pixel.
+///
+/// Two pixels match if no RGBA channel differs by more than `tolerance`, as a fraction of
+/// the full range of 0 to 255. Pixels that are only present in one of the images, if
+/// their sizes differ, never match.
+pub(crate) fn diff(image: &DynamicImage, baseline: &DynamicImage, tolerance: f32) -> DiffResult {
+    let max_delta = (tolerance.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let (width, height) = image.dimensions();
+    let (baseline_width, baseline_height) = baseline.dimensions();
+    let in_both = |x, y| x < width.min(baseline_width) && y < height.min(baseline_height);
+
+    let mut diff_pixels = 0;
+    let diff_image =
+        RgbaImage::from_fn(width.max(baseline_width), height.max(baseline_height), |x, y| {
+            let pixel = match in_both(x, y) {
+                true => {
+                    let (a, b) = (image.get_pixel(x, y).0, baseline.get_pixel(x, y).0);
+                    a.iter().zip(&b).all(|(a, b)| a.abs_diff(*b) <= max_delta).then_some(a)
+                }
+                false => None,
+            };
+            match pixel {
+                // Matching pixels are kept, but faded so the differences stand out.
+                Some([r, g, b, _]) => Rgba([r, g, b, 64]),
+                None => {
+                    diff_pixels += 1;
+                    Rgba([255, 0, 0, 255])
+                }
+            }
+        });
+
+    DiffResult {
+        matches: diff_pixels == 0,
+        diff_pixels,
+        diff_image: DynamicImage::ImageRgba8(diff_image),
+        size: (width, height),
+        baseline_size: (baseline_width, baseline_height),
+    }
+}
+
+/// The result of comparing a screenshot against a baseline image.
+///
+/// See [`WebElement::screenshot_matches()`](crate::WebElement::screenshot_matches).
+#[derive(Debug, Clone)]
+pub struct DiffResult {
+    /// Whether every pixel matched the baseline, within the tolerance.
+    ///
+    /// This is always false if the screenshot and the baseline differ in size.
+    pub matches: bool,
+    /// The number of pixels that did not match the baseline.
+    ///
+    /// If the sizes differ, this includes every pixel that is only present in one of the
+    /// two images.
+    pub diff_pixels: usize,
+    /// An image highlighting the differences. Pixels that did not match are red, and
+    /// the others are faded copies of the screenshot.
+    ///
+    /// The image is large enough to contain both the screenshot and the baseline.
+    pub diff_image: DynamicImage,
+    /// The width and height of the screenshot, in pixels.
+    pub size: (u32, u32),
+    /// The width and height of the baseline image, in pixels.
+    pub baseline_size: (u32, u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn test_image(width: u32, height: u32) -> DynamicImage {
+        let pixels = (0..width * height * 3).map(|i| (i * 37 % 251) as u8).collect();
+        DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, pixels).unwrap())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let image = test_image(5, 4);
+        let decoded = decode(&encode(&image).unwrap()).unwrap();
+        assert_eq!(decoded.to_rgb8(), image.to_rgb8());
+    }
+
+    #[test]
+    fn test_crop() {
+        let image = test_image(5, 4);
+        let region = Region {
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 2,
+        };
+        let cropped = crop(&image, region).unwrap();
+        assert_eq!(cropped.dimensions(), (3, 2));
+        assert_eq!(cropped.get_pixel(0, 0), image.get_pixel(1, 2));
+        assert_eq!(cropped.get_pixel(2, 1), image.get_pixel(3, 3));
+    }
+
+    #[test]
+    fn test_crop_out_of_bounds() {
+        let image = test_image(5, 4);
+        for (x, y, width, height) in [(0, 0, 6, 1), (4, 0, 2, 1), (0, 3, 1, 2), (0, 0, 0, 1)] {
+            let result = crop(
+                &image,
+                Region {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+            );
+            assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let image = test_image(5, 4);
+        let mut changed = image.to_rgb8();
+        changed.get_pixel_mut(0, 0).0[0] = changed.get_pixel(0, 0).0[0].wrapping_add(10);
+        changed.get_pixel_mut(2, 1).0[1] = changed.get_pixel(2, 1).0[1].wrapping_add(100);
+        let baseline = DynamicImage::ImageRgb8(changed);
+
+        let result = diff(&image, &image, 0.0);
+        assert!(result.matches);
+        assert_eq!(result.diff_pixels, 0);
+
+        let result = diff(&image, &baseline, 0.0);
+        assert!(!result.matches);
+        assert_eq!(result.diff_pixels, 2);
+        let diff_image = &result.diff_image;
+        assert_eq!(diff_image.dimensions(), (5, 4));
+        assert_eq!(diff_image.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(diff_image.get_pixel(2, 1), Rgba([255, 0, 0, 255]));
+        let [r, g, b, _] = image.get_pixel(1, 0).0;
+        assert_eq!(diff_image.get_pixel(1, 0), Rgba([r, g, b, 64]));
+
+        // The first pixel only differs by 10/255.
+        let result = diff(&image, &baseline, 0.1);
+        assert_eq!(result.diff_pixels, 1);
+    }
+
+    #[test]
+    fn test_diff_size_mismatch() {
+        let image = test_image(5, 4);
+        let cropped = image.crop_imm(0, 0, 4, 4);
+        let result = diff(&image, &cropped, 1.0);
+        assert!(!result.matches);
+        assert_eq!(result.diff_pixels, 4);
+        assert_eq!((result.size, result.baseline_size), ((5, 4), (4, 4)));
+        let diff_image = &result.diff_image;
+        assert_eq!(diff_image.dimensions(), (5, 4));
+        assert_eq!(diff_image.get_pixel(4, 3), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(matches!(decode(b"not a png"), Err(WebDriverError::ImageError(_))));
+    }
+}
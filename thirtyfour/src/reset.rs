@@ -0,0 +1,208 @@
+use crate::common::command::Command;
+use crate::error::{ResetStepError, WebDriverError, WebDriverResult};
+use crate::session::handle::SessionHandle;
+use std::sync::Arc;
+
+/// The steps performed by `WebDriver::reset_with_options()`.
+///
+/// Every step is enabled by default. Set a field to `false` to skip that step.
+///
+/// # Example:
+/// ```
+/// use thirtyfour::ResetOptions;
+///
+/// let options = ResetOptions {
+///     close_extra_tabs: false,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetOptions {
+    /// Close all tabs and windows except the current one.
+    pub close_extra_tabs: bool,
+    /// Switch to the top-level browsing context, leaving any frame.
+    pub default_content: bool,
+    /// Clear `localStorage` and `sessionStorage` for the current page.
+    pub clear_storage: bool,
+    /// Delete all cookies for the current page.
+    pub clear_cookies: bool,
+    /// Navigate to `about:blank`, stopping any timers or connections from the current page.
+    pub navigate_blank: bool,
+}
+
+impl Default for ResetOptions {
+    fn default() -> Self {
+        Self {
+            close_extra_tabs: true,
+            default_content: true,
+            clear_storage: true,
+            clear_cookies: true,
+            navigate_blank: true,
+        }
+    }
+}
+
+const CLEAR_STORAGE: &str = r#"
+try { localStorage.clear(); } catch (e) {}
+try { sessionStorage.clear(); } catch (e) {}"#;
+
+impl SessionHandle {
+    /// Reset the browser to a clean state, for reusing the same session across tests.
+    ///
+    /// This closes all but the current tab, switches to the default content, clears
+    /// web storage and cookies, and then navigates to `about:blank`.
+    /// See `WebDriver::reset_with_options()` to skip some of these steps.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto("https://www.rust-lang.org").await?;
+    /// // ... run a test ...
+    /// driver.reset().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn reset(&self) -> WebDriverResult<()> {
+        self.reset_with_options(ResetOptions::default()).await
+    }
+
+    /// Reset the browser to a clean state, performing only the steps enabled in `options`.
+    ///
+    /// Storage and cookies are cleared before navigating away, so they are cleared for
+    /// the origin of the page that was open in the remaining tab. The origins of any
+    /// closed tabs are not cleared.
+    ///
+    /// Every enabled step is attempted, even if an earlier one fails. If any step fails,
+    /// a `WebDriverError::ResetFailed` error listing each failed step is returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::ResetOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let options = ResetOptions {
+    ///     clear_cookies: false,
+    ///     ..Default::default()
+    /// };
+    /// driver.reset_with_options(options).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn reset_with_options(&self, options: ResetOptions) -> WebDriverResult<()> {
+        let mut errors = Vec::new();
+        let mut record = |step: &'static str, result: WebDriverResult<()>| {
+            if let Err(error) = result {
+                errors.push(ResetStepError {
+                    step,
+                    error,
+                });
+            }
+        };
+
+        if options.close_extra_tabs {
+            record("close extra tabs", self.close_extra_tabs().await);
+        }
+        if options.default_content {
+            record("switch to default content", self.enter_default_frame().await);
+        }
+        if options.clear_storage {
+            let result = self.cmd(Command::ExecuteScript(CLEAR_STORAGE.into(), Arc::new([]))).await;
+            record("clear storage", result.map(|_| ()));
+        }
+        if options.clear_cookies {
+            record("clear cookies", self.delete_all_cookies().await);
+        }
+        if options.navigate_blank {
+            let result = self.cmd(Command::NavigateTo("about:blank".into())).await;
+            record("navigate to about:blank", result.map(|_| ()));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(WebDriverError::ResetFailed(errors))
+        }
+    }
+
+    /// Close all windows except the current one, or the first one if the current
+    /// window has already been closed.
+    async fn close_extra_tabs(&self) -> WebDriverResult<()> {
+        let current = self.window().await.ok();
+        let handles = self.windows().await?;
+        let keep = current.filter(|h| handles.contains(h)).or_else(|| handles.first().cloned());
+        for handle in handles {
+            if Some(&handle) != keep.as_ref() {
+                self.switch_to_window(handle).await?;
+                self.close_window().await?;
+            }
+        }
+        if let Some(keep) = keep {
+            self.switch_to_window(keep).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::{MockClient, Reply};
+
+    fn broken_handle() -> (Arc<MockClient>, Arc<SessionHandle>) {
+        let client = MockClient::new().reply("", Reply::error(500, "unknown error", "broken"));
+        let client = Arc::new(client);
+        (client.clone(), client.handle())
+    }
+
+    #[tokio::test]
+    async fn test_reset_aggregates_errors() {
+        let (_, handle) = broken_handle();
+        match handle.reset().await {
+            Err(WebDriverError::ResetFailed(errors)) => {
+                let steps: Vec<_> = errors.iter().map(|e| e.step).collect();
+                assert_eq!(
+                    steps,
+                    vec![
+                        "close extra tabs",
+                        "switch to default content",
+                        "clear storage",
+                        "clear cookies",
+                        "navigate to about:blank"
+                    ]
+                );
+                assert!(errors.iter().all(|e| matches!(e.error, WebDriverError::UnknownError(_))));
+            }
+            x => panic!("expected reset error, got {x:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reset_skips_disabled_steps() {
+        let (client, handle) = broken_handle();
+        let options = ResetOptions {
+            close_extra_tabs: false,
+            default_content: false,
+            clear_storage: false,
+            clear_cookies: false,
+            navigate_blank: true,
+        };
+        let result = handle.reset_with_options(options).await;
+        assert!(matches!(result, Err(WebDriverError::ResetFailed(e)) if e.len() == 1));
+        assert_eq!(client.paths(), ["/session/test/url"]);
+    }
+}
@@ -13,14 +13,29 @@ use crate::{
 
 use super::http::HttpClient;
 
-/// Start a new WebDriver session, returning the session id and the
-/// capabilities JSON that was received back from the server.
+/// Start a new WebDriver session, returning the session id.
+///
+/// See [`start_session_with_capabilities`] to also get the capabilities that were
+/// received back from the server.
 pub async fn start_session(
     http_client: &(impl HttpClient + ?Sized),
     server_url: &Url,
     config: &WebDriverConfig,
     capabilities: Capabilities,
 ) -> WebDriverResult<SessionId> {
+    let (session_id, _) =
+        start_session_with_capabilities(http_client, server_url, config, capabilities).await?;
+    Ok(session_id)
+}
+
+/// Start a new WebDriver session, returning the session id and the
+/// capabilities JSON that was received back from the server.
+pub async fn start_session_with_capabilities(
+    http_client: &(impl HttpClient + ?Sized),
+    server_url: &Url,
+    config: &WebDriverConfig,
+    capabilities: Capabilities,
+) -> WebDriverResult<(SessionId, Capabilities)> {
     let request_data = Command::NewSession(serde_json::Value::Object(capabilities))
         .format_request(&SessionId::null());
 
@@ -46,8 +61,8 @@ pub async fn start_session(
     struct ConnectionData {
         #[serde(default, rename(deserialize = "sessionId"))]
         session_id: String,
-        // #[serde(default)]
-        // capabilities: serde_json::Value,
+        #[serde(default)]
+        capabilities: Option<Capabilities>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -70,5 +85,5 @@ pub async fn start_session(
         Command::SetTimeouts(TimeoutConfiguration::default()).format_request(&session_id);
     run_webdriver_cmd(http_client, &request_data, server_url, config).await?;
 
-    Ok(session_id)
+    Ok((session_id, data.capabilities.unwrap_or_default()))
 }
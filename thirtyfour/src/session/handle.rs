@@ -1,27 +1,52 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
-use serde_json::Value;
+use bytes::Bytes;
+use futures::Stream;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use url::Url;
 
-use super::http::{run_webdriver_cmd, CmdResponse, HttpClient};
+use super::http::{
+    check_response_status, normalize_server_url, parse_response_body, send_webdriver_request,
+    CmdResponse, HttpClient, ResponseData,
+};
 
 use crate::action_chain::ActionChain;
-use crate::common::command::{Command, FormatRequestData};
+use crate::common::command::{Command, FormatRequestData, MAGIC_ELEMENTID};
 use crate::common::config::WebDriverConfig;
 use crate::common::cookie::Cookie;
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::cdp::ChromeCommand;
+use crate::extensions::query::poll_value;
+use crate::extensions::query::{ElementQueryable, ElementWaitable, WebDriverWait};
+use crate::js::{
+    ELEMENT_RECTS, IS_VALID_TIMEZONE, NAVIGATION_TIMING, NETWORK_ACTIVITY, READ_CLIPBOARD,
+    SCROLL_HEIGHT, SCROLL_TO_BOTTOM, WRITE_CLIPBOARD,
+};
 use crate::prelude::WebDriverError;
 use crate::session::scriptret::ScriptRet;
-use crate::support::base64_decode;
-use crate::{By, OptionRect, Rect, SessionId, SwitchTo, WebDriverStatus, WebElement};
-use crate::{IntoArcStr, IntoUrl};
-use crate::{TimeoutConfiguration, WindowHandle};
+use crate::session::scriptstream::ScriptStream;
+use crate::support::{base64_decode, sleep, write_file_atomic};
+use crate::WebElement;
+use crate::{By, Capabilities, ElementRect, NavigationTiming, OptionRect, SessionId, SwitchTo};
+use crate::{IntoArcStr, IntoUrl, RequestData};
+use crate::{
+    PermissionState, PrintParameters, TabInfo, TimeoutConfiguration, WebDriverStatus, WindowHandle,
+    WindowRect,
+};
+
+/// How much longer than the session's script or page load timeout to wait for the
+/// response to a script or navigation, so that the driver can report the timeout itself.
+const SESSION_TIMEOUT_MARGIN: Duration = Duration::from_secs(10);
 
 /// The SessionHandle contains a shared reference to the HTTP client
 /// to allow sending commands to the underlying WebDriver.
@@ -34,6 +59,16 @@ pub struct SessionHandle {
     session_id: SessionId,
     /// The config used by this instance.
     config: WebDriverConfig,
+    /// The most recent response, if `WebDriverConfig::record_last_response` is enabled.
+    last_response: Mutex<Option<ResponseData>>,
+    /// The capabilities returned by the server when the session was created.
+    capabilities: Arc<Capabilities>,
+    /// The network requests seen in the performance log, shared by all handles for
+    /// this session.
+    network_log: Arc<Mutex<NetworkLog>>,
+    /// The script and page load timeouts of the session, shared by all handles for this
+    /// session.
+    timeouts: Arc<Mutex<SessionTimeouts>>,
 }
 
 impl Debug for SessionHandle {
@@ -64,12 +99,32 @@ impl SessionHandle {
     ) -> WebDriverResult<Self> {
         Ok(Self {
             client,
-            server_url: Arc::new(server_url.into_url()?),
+            server_url: Arc::new(normalize_server_url(server_url.into_url()?)),
             session_id,
             config,
+            last_response: Mutex::new(None),
+            capabilities: Arc::new(Capabilities::new()),
+            network_log: Default::default(),
+            timeouts: Default::default(),
         })
     }
 
+    /// Attach the capabilities returned by the server when the session was created.
+    pub(crate) fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        {
+            let mut timeouts = self.timeouts.lock();
+            if let Some(capability) = capabilities.get("timeouts") {
+                timeouts.update(capability);
+            }
+            // `start_session_with_capabilities()` then sets the default timeouts.
+            if let Ok(defaults) = serde_json::to_value(TimeoutConfiguration::default()) {
+                timeouts.update(&defaults);
+            }
+        }
+        self.capabilities = Arc::new(capabilities);
+        self
+    }
+
     /// Clone this session handle but attach the specified `WebDriverConfig`.
     ///
     /// See `WebDriver::clone_with_config()`.
@@ -79,6 +134,10 @@ impl SessionHandle {
             server_url: self.server_url.clone(),
             session_id: self.session_id.clone(),
             config,
+            last_response: Mutex::new(None),
+            capabilities: self.capabilities.clone(),
+            network_log: self.network_log.clone(),
+            timeouts: self.timeouts.clone(),
         }
     }
 
@@ -87,6 +146,22 @@ impl SessionHandle {
         &self.session_id
     }
 
+    /// The capabilities returned by the WebDriver server when the session was created.
+    ///
+    /// These describe the session that was actually created, such as `browserName` and
+    /// `browserVersion`. They are empty if the session was not created by this instance,
+    /// for example after `WebDriver::reconnect()` or when using `SessionHandle::new()`.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// The name of the browser, from the `browserName` capability returned by the server.
+    ///
+    /// Returns `None` if the server did not return it. See `SessionHandle::capabilities()`.
+    pub fn browser_name(&self) -> Option<&str> {
+        self.capabilities.get("browserName").and_then(|v| v.as_str())
+    }
+
     /// The configuration used by this instance.
     ///
     /// NOTE: It's sometimes useful to have separate instances pointing at the same
@@ -99,9 +174,152 @@ impl SessionHandle {
     }
 
     /// Send the specified command to the webdriver server.
+    ///
+    /// The request is limited by `WebDriverConfig::request_timeout`, which is 120 seconds
+    /// by default. See `cmd_with_timeout()` to use a different timeout for one command.
+    ///
+    /// Scripts and navigation commands wait for the session's script or page load timeout,
+    /// so if that is longer than `WebDriverConfig::request_timeout`, the request timeout
+    /// for the command is extended to the session timeout plus a margin. The session
+    /// timeouts are those last set via this crate, starting from the values returned by
+    /// the server when the session was created, or the W3C defaults (30 seconds for
+    /// scripts and 300 seconds for page loads). If the script or page load timeout is
+    /// disabled, so is the request timeout for the command.
     pub async fn cmd(&self, command: impl FormatRequestData) -> WebDriverResult<CmdResponse> {
+        self.cmd_with_config(command, None).await
+    }
+
+    /// Send the specified command to the webdriver server, with the specified request
+    /// timeout instead of `WebDriverConfig::request_timeout`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use thirtyfour::common::command::Command;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.cmd_with_timeout(Command::Refresh, Duration::from_secs(600)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn cmd_with_timeout(
+        &self,
+        command: impl FormatRequestData,
+        timeout: Duration,
+    ) -> WebDriverResult<CmdResponse> {
+        let mut config = self.config.clone();
+        config.request_timeout = Some(timeout);
+        self.cmd_with_config(command, Some(&config)).await
+    }
+
+    /// Send the command with the specified config, or otherwise with the session's
+    /// config and the request timeout extended to suit the command.
+    async fn cmd_with_config(
+        &self,
+        command: impl FormatRequestData,
+        config: Option<&WebDriverConfig>,
+    ) -> WebDriverResult<CmdResponse> {
         let request_data = command.format_request(&self.session_id);
-        run_webdriver_cmd(self.client.as_ref(), &request_data, &self.server_url, &self.config).await
+        let extended;
+        let config = match config {
+            Some(config) => config,
+            None => match self.timeouts.lock().request_timeout(&request_data, &self.config) {
+                Some(request_timeout) => {
+                    extended = WebDriverConfig {
+                        request_timeout,
+                        ..self.config.clone()
+                    };
+                    &extended
+                }
+                None => &self.config,
+            },
+        };
+        let policy = match config.retry_policy {
+            Some(policy) if command.is_idempotent() => policy,
+            _ => return self.run_cmd(&request_data, config).await,
+        };
+
+        let mut retry = 0;
+        loop {
+            match self.run_cmd(&request_data, config).await {
+                Err(WebDriverError::HttpError(e)) if retry < policy.max_retries => {
+                    tracing::debug!("retrying {command:?} after http error: {e}");
+                    sleep(policy.delay(retry)).await;
+                    retry += 1;
+                }
+                r => return r,
+            }
+        }
+    }
+
+    async fn run_cmd(
+        &self,
+        request_data: &RequestData,
+        config: &WebDriverConfig,
+    ) -> WebDriverResult<CmdResponse> {
+        let (status, body) = self.run_cmd_raw(request_data, config).await?;
+        let response = parse_response_body(status, body)?;
+        let timeouts = match request_data.method {
+            http::Method::POST => request_data.body.as_ref(),
+            _ => response.body.get("value"),
+        };
+        if let Some(timeouts) = timeouts.filter(|_| request_data.uri.ends_with("/timeouts")) {
+            self.timeouts.lock().update(timeouts);
+        }
+        Ok(response)
+    }
+
+    async fn run_cmd_raw(
+        &self,
+        request_data: &RequestData,
+        config: &WebDriverConfig,
+    ) -> WebDriverResult<(u16, Bytes)> {
+        let response =
+            send_webdriver_request(self.client.as_ref(), request_data, &self.server_url, config)
+                .await?;
+        if config.record_last_response {
+            *self.last_response.lock() = Some(ResponseData::from(&response));
+        }
+        check_response_status(response)
+    }
+
+    /// Get the status, headers and body of the most recent response from the WebDriver
+    /// server, for debugging.
+    ///
+    /// This is only recorded if `WebDriverConfig::record_last_response` is enabled,
+    /// and returns `None` otherwise. Error responses are recorded too, but a request that
+    /// failed without any response (e.g. a connection error) leaves the previous value.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::common::config::WebDriverConfig;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let config = WebDriverConfig::builder().record_last_response(true).build()?;
+    /// let driver = WebDriver::new_with_config("http://localhost:4444", caps, config).await?;
+    /// if let Err(e) = driver.goto("https://www.rust-lang.org").await {
+    ///     if let Some(response) = driver.last_response() {
+    ///         println!("{e}: {} {:?} {}", response.status, response.headers, response.body_lossy());
+    ///     }
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn last_response(&self) -> Option<ResponseData> {
+        self.last_response.lock().clone()
     }
 
     /// Get the WebDriver status.
@@ -166,6 +384,15 @@ impl SessionHandle {
 
     /// Navigate to the specified URL.
     ///
+    /// If `WebDriverConfig::clear_storage_on_origin_change` is enabled, cookies and web
+    /// storage are cleared after navigating whenever the origin (scheme, host and port)
+    /// differs from that of the previous page.
+    ///
+    /// Storage can only be cleared for the origin of the current page, so this happens
+    /// once the new page has loaded. Scripts that run while the page loads can still see
+    /// the old cookies and storage. If that matters, call `refresh()` afterwards to load
+    /// the page again with empty storage.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -186,7 +413,26 @@ impl SessionHandle {
         if !url.starts_with("http") {
             url = format!("https://{url}").into();
         }
+        if !self.config.clear_storage_on_origin_change {
+            self.cmd(Command::NavigateTo(url)).await?;
+            return Ok(());
+        }
+
+        let before = self.current_url().await?.origin();
         self.cmd(Command::NavigateTo(url)).await?;
+        let after = self.current_url().await?.origin();
+        if before != after {
+            self.clear_origin_storage().await?;
+        }
+        Ok(())
+    }
+
+    /// Clear cookies, `localStorage` and `sessionStorage` for the current page.
+    async fn clear_origin_storage(&self) -> WebDriverResult<()> {
+        self.cmd(Command::DeleteAllCookies).await?;
+        // Accessing web storage throws for opaque origins such as `about:blank` or `data:` URLs.
+        let script = "try { localStorage.clear(); sessionStorage.clear(); } catch (e) {}";
+        self.cmd(Command::ExecuteScript(script.into(), Arc::new([]))).await?;
         Ok(())
     }
 
@@ -218,12 +464,13 @@ impl SessionHandle {
         self.cmd(Command::GetTitle).await?.value()
     }
 
-    /// Search for an element on the current page using the specified selector.
+    /// Get the Navigation Timing API values for the navigation that loaded the current page.
     ///
-    /// **NOTE**: For more powerful element queries including polling and filters, see the
-    ///           [`WebDriver::query`] method instead.
+    /// This reads `performance.getEntriesByType("navigation")[0]`, so it works in any
+    /// browser that supports the Navigation Timing API, without needing CDP.
     ///
-    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
+    /// Returns `WebDriverError::NotFound` if there is no navigation entry, for example
+    /// on `about:blank`.
     ///
     /// # Example:
     /// ```no_run
@@ -234,206 +481,325 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem_button = driver.find(By::Id("my-element-id")).await?;
-    /// let elem_text = driver.find(By::Name("my-text-input")).await?;
+    /// driver.goto("https://www.rust-lang.org").await?;
+    /// let timing = driver.navigation_timing().await?;
+    /// assert!(timing.dom_content_loaded_event_end < 2000.0);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn find(self: &Arc<Self>, by: By) -> WebDriverResult<WebElement> {
-        let r = self.cmd(Command::FindElement(by.into())).await?;
-        r.element(self.clone())
-    }
-
-    /// Search for an element on the current page using the specified selector.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to find()")]
-    pub async fn find_element(self: &Arc<Self>, by: By) -> WebDriverResult<WebElement> {
-        self.find(by).await
+    pub async fn navigation_timing(self: &Arc<Self>) -> WebDriverResult<NavigationTiming> {
+        let ret = self.execute(NAVIGATION_TIMING, Vec::new()).await?;
+        if ret.is_null() {
+            return Err(WebDriverError::NotFound(
+                "navigation timing".to_string(),
+                "the current page has no navigation entry".to_string(),
+            ));
+        }
+        ret.convert()
     }
 
-    /// Search for all elements on the current page that match the specified selector.
+    /// Wait until the current URL contains the specified string.
     ///
-    /// **NOTE**: For more powerful element queries including polling and filters, see the
-    ///           [`WebDriver::query`] method instead.
-    ///
-    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
+    /// Returns a `WebDriverError::Timeout` error, including the last URL seen,
+    /// if the URL does not match within the timeout.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elems = driver.find_all(By::ClassName("section")).await?;
-    /// for elem in elems {
-    ///     assert!(elem.attr("class").await?.expect("Missing class on element").contains("section"));
-    /// }
+    /// driver.find(By::Id("login")).await?.click().await?;
+    /// driver.wait_until_url_contains("/dashboard", Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn find_all(self: &Arc<Self>, by: By) -> WebDriverResult<Vec<WebElement>> {
-        let r = self.cmd(Command::FindElements(by.into())).await?;
-        r.elements(self.clone())
+    pub async fn wait_until_url_contains(
+        &self,
+        substr: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for url to contain '{substr}'"),
+            || async { Ok(self.current_url().await?.to_string()) },
+            |url| url.contains(substr),
+        )
+        .await?;
+        Ok(())
     }
 
-    /// Search for all elements on the current page that match the specified selector.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to find_all()")]
-    pub async fn find_elements(self: &Arc<Self>, by: By) -> WebDriverResult<Vec<WebElement>> {
-        self.find_all(by).await
+    /// Wait until the current URL matches the specified regular expression.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the last URL seen,
+    /// if the URL does not match within the timeout.
+    ///
+    /// This method requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub async fn wait_until_url_matches(
+        &self,
+        regex: &regex::Regex,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for url to match '{regex}'"),
+            || async { Ok(self.current_url().await?.to_string()) },
+            |url| regex.is_match(url),
+        )
+        .await?;
+        Ok(())
     }
 
-    /// Execute the specified Javascript synchronously and return the result.
+    /// Wait until the page title is equal to the specified string.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the last title seen,
+    /// if the title does not match within the timeout.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let ret = driver.execute(r#"
-    ///     let elem = document.getElementById("button1");
-    ///     elem.click();
-    ///     return elem;
-    ///     "#, Vec::new()
-    /// ).await?;
-    /// let elem_out: WebElement = ret.element()?;
+    /// driver.wait_until_title_is("Dashboard", Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    /// To supply an element as an input argument to a script, use
-    /// [`WebElement::to_json`] as follows:
+    pub async fn wait_until_title_is(&self, title: &str, timeout: Duration) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for title to be '{title}'"),
+            || self.title(),
+            |t| t == title,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Repeatedly run the specified script until it returns a truthy value, and return
+    /// that value.
+    ///
+    /// Truthiness follows JavaScript semantics: `null`, `false`, `0` and the empty string
+    /// are falsy, and anything else (including empty arrays and objects) is truthy.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the last value returned,
+    /// if the script does not return a truthy value within the timeout.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let ret = driver.execute(r#"
-    ///     arguments[0].innerHTML = arguments[1];
-    ///     return arguments[0];
-    ///     "#, vec![elem.to_json()?, serde_json::to_value("TESTING")?]
-    /// ).await?;
-    /// let elem_out = ret.element()?;
-    /// assert_eq!(elem_out.element_id(), elem.element_id());
-    /// assert_eq!(elem_out.text().await?, "TESTING");
+    /// driver.wait_for_script("return window.appReady === true;", Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn execute(
-        self: &Arc<Self>,
-        script: impl IntoArcStr,
-        args: impl Into<Arc<[Value]>>,
-    ) -> WebDriverResult<ScriptRet> {
-        let r = self.cmd(Command::ExecuteScript(script.into(), args.into())).await?;
-        Ok(ScriptRet::new(self.clone(), r.value()?))
-    }
-
-    /// Execute the specified Javascript synchronously and return the result.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to execute()")]
-    pub async fn execute_script(
+    pub async fn wait_for_script(
         self: &Arc<Self>,
         script: impl IntoArcStr,
-        args: Vec<Value>,
-    ) -> WebDriverResult<ScriptRet> {
-        self.execute(script, args).await
+        timeout: Duration,
+    ) -> WebDriverResult<Value> {
+        let script = script.into();
+        poll_value(
+            timeout,
+            "timed out waiting for script to return a truthy value",
+            || async { Ok(self.execute(script.clone(), Vec::new()).await?.json().clone()) },
+            is_truthy,
+        )
+        .await
     }
 
-    /// Execute the specified Javascrypt asynchronously and return the result.
+    /// Wait until there have been no pending network requests for at least `idle`, for
+    /// example after an action in a single-page app that loads data.
+    ///
+    /// On Chromium-based browsers with the performance log enabled (see
+    /// `ChromiumLikeCapabilities::enable_performance_logging()`), this tracks every request
+    /// using the CDP `Network` events recorded in the log, including requests that were
+    /// already in flight before the first call. This reads (and so clears) the performance
+    /// log. Idle time is measured from when the events are read, so a call may wait up to
+    /// `idle` even if the network was already idle.
+    ///
+    /// Otherwise, the first call installs a script in the page that wraps `fetch()` and
+    /// `XMLHttpRequest` to count in-flight requests, and uses a `PerformanceObserver` to
+    /// see when other resources finish loading. This only counts `fetch()` and
+    /// `XMLHttpRequest` requests that start after the script is installed, so call this
+    /// once before triggering the requests you want to wait for. Navigating to a new page
+    /// removes the script, which is installed again by the next call.
+    ///
+    /// Long-lived requests, such as an `EventSource` stream, keep the network busy
+    /// until they finish.
+    ///
+    /// Returns a `WebDriverError::Timeout` error if the network is not idle within the
+    /// timeout.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let ret = driver.execute_async(r#"
-    ///     // Selenium automatically provides an extra argument which is a
-    ///     // function that receives the return value(s).
-    ///     let done = arguments[0];
-    ///     window.setTimeout(() => {
-    ///         let elem = document.getElementById("button1");
-    ///         elem.click();
-    ///         done(elem);
-    ///     }, 1000);
-    ///     "#, Vec::new()
-    /// ).await?;
-    /// let elem_out: WebElement = ret.element()?;
+    /// driver.wait_for_network_idle(Duration::ZERO, Duration::from_secs(10)).await?;
+    /// driver.find(By::Id("load")).await?.click().await?;
+    /// driver.wait_for_network_idle(Duration::from_millis(500), Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    /// To supply an element as an input argument to a script, use
-    /// [`WebElement::to_json`] as follows:
+    pub async fn wait_for_network_idle(
+        self: &Arc<Self>,
+        idle: Duration,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        let idle_ms = idle.as_secs_f64() * 1000.0;
+        poll_value(
+            timeout,
+            "timed out waiting for the network to be idle",
+            || self.network_activity(),
+            |activity: &NetworkActivity| activity.pending == 0 && activity.idle_ms >= idle_ms,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Get the current network activity, from the performance log if it is available,
+    /// or otherwise from the `NETWORK_ACTIVITY` script.
+    async fn network_activity(self: &Arc<Self>) -> WebDriverResult<NetworkActivity> {
+        if self.network_log.lock().available != Some(false) {
+            match self.cmd(GetPerformanceLog).await {
+                Ok(r) => {
+                    let entries: Vec<Value> = r.value()?;
+                    let mut log = self.network_log.lock();
+                    log.available = Some(true);
+                    log.update(&entries);
+                    return Ok(log.activity());
+                }
+                Err(
+                    WebDriverError::UnknownCommand(_)
+                    | WebDriverError::UnknownMethod(_)
+                    | WebDriverError::InvalidArgument(_)
+                    | WebDriverError::UnknownError(_),
+                ) if self.network_log.lock().available.is_none() => {
+                    self.network_log.lock().available = Some(false);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.execute(NETWORK_ACTIVITY, Vec::new()).await?.convert()
+    }
+
+    /// Get the completed downloads in the specified directory, for use with
+    /// `wait_for_download()`.
+    ///
+    /// Partial downloads (`.crdownload` files from Chrome and `.part` files from Firefox)
+    /// are left out, as is any file with a partial download of the same name next to it
+    /// (Firefox creates the empty destination file when the download starts). A directory
+    /// that does not exist yet is treated as empty.
+    pub async fn completed_downloads(
+        &self,
+        dir: impl AsRef<Path>,
+    ) -> WebDriverResult<Vec<PathBuf>> {
+        completed_downloads(dir.as_ref()).await
+    }
+
+    /// Wait until the specified directory contains a new completed download that matches
+    /// the predicate, and return its path.
+    ///
+    /// Files in `before` are ignored, so that files left over from earlier downloads are
+    /// not returned. Get them with `completed_downloads()` before the action that starts
+    /// the download, since the download may complete before this method is called.
+    /// Partial downloads are ignored, as described in `completed_downloads()`.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the files last seen,
+    /// if no matching download completes within the timeout.
+    ///
+    /// On Chrome, set the download directory both in the capabilities, with
+    /// `ChromiumLikeCapabilities::set_download_directory()`, and once the session has
+    /// started, with `ChromeDevTools::set_download_directory()`. Headless Chrome ignores
+    /// the preferences set by the capabilities, and the CDP command only applies to the
+    /// running browser, so it cannot be set in advance.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use std::path::Path;
+    /// use std::time::Duration;
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
-    /// #         let caps = DesiredCapabilities::chrome();
-    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let args = vec![elem.to_json()?, serde_json::to_value("TESTING")?];
-    /// let ret = driver.execute_async(r#"
-    ///     // Selenium automatically provides an extra argument which is a
-    ///     // function that receives the return value(s).
-    ///     let done = arguments[2];
-    ///     window.setTimeout(() => {
-    ///         arguments[0].innerHTML = arguments[1];
-    ///         done(arguments[0]);
-    ///     }, 1000);
-    ///     "#, args
-    /// ).await?;
-    /// let elem_out = ret.element()?;
-    /// assert_eq!(elem_out.element_id(), elem.element_id());
-    /// assert_eq!(elem_out.text().await?, "TESTING");
+    /// let dir = Path::new("/tmp/downloads");
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.set_download_directory(dir)?;
+    /// let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// ChromeDevTools::new(driver.handle.clone()).set_download_directory(dir).await?;
+    ///
+    /// let before = driver.completed_downloads(dir).await?;
+    /// driver.find(By::Id("download-report")).await?.click().await?;
+    /// let is_csv = |p: &Path| p.extension() == Some("csv".as_ref());
+    /// let path = driver.wait_for_download(dir, &before, is_csv, Duration::from_secs(30)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn execute_async(
-        self: &Arc<Self>,
-        script: impl IntoArcStr,
-        args: impl Into<Arc<[Value]>>,
-    ) -> WebDriverResult<ScriptRet> {
-        let r = self.cmd(Command::ExecuteAsyncScript(script.into(), args.into())).await?;
-        Ok(ScriptRet::new(self.clone(), r.value()?))
-    }
-
-    /// Execute the specified Javascrypt asynchronously and return the result.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to execute_async()")]
-    pub async fn execute_script_async(
-        self: &Arc<Self>,
-        script: impl IntoArcStr,
-        args: impl Into<Arc<[Value]>>,
-    ) -> WebDriverResult<ScriptRet> {
-        self.execute_async(script, args.into()).await
+    pub async fn wait_for_download(
+        &self,
+        dir: impl AsRef<Path>,
+        before: &[PathBuf],
+        predicate: impl Fn(&Path) -> bool,
+        timeout: Duration,
+    ) -> WebDriverResult<PathBuf> {
+        let dir = dir.as_ref();
+        let desc = format!("timed out waiting for download in '{}'", dir.display());
+        let is_new = |f: &PathBuf| !before.contains(f) && predicate(f);
+        let files = poll_value(
+            timeout,
+            &desc,
+            || completed_downloads(dir),
+            |files| files.iter().any(is_new),
+        )
+        .await?;
+        // The predicate matched while polling, but may not be deterministic.
+        files.into_iter().find(is_new).ok_or_else(|| WebDriverError::Timeout(desc))
     }
 
-    /// Get the current window handle.
+    /// Search for an element on the current page using the specified selector.
+    ///
+    /// A plain string is treated as a CSS selector. See [`By`] for other strategies.
+    ///
+    /// **NOTE**: For more powerful element queries including polling and filters, see the
+    ///           [`WebDriver::query`] method instead.
+    ///
+    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
     ///
     /// # Example:
     /// ```no_run
@@ -444,40 +810,23 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// // Get the current window handle.
-    /// let handle = driver.window().await?;
-    ///
-    /// // Open a new tab.
-    /// driver.new_tab().await?;
-    ///
-    /// // Get window handles and switch to the new tab.
-    /// let handles = driver.windows().await?;
-    /// driver.switch_to_window(handles[1].clone()).await?;
-    ///
-    /// // We are now controlling the new tab.
-    /// driver.goto("https://www.rust-lang.org/").await?;
-    /// assert_ne!(driver.window().await?, handle);
-    ///
-    /// // Switch back to original tab.
-    /// driver.switch_to_window(handle.clone()).await?;
-    /// assert_eq!(driver.window().await?, handle);
+    /// let elem_button = driver.find(By::Id("my-element-id")).await?;
+    /// let elem_text = driver.find(By::Name("my-text-input")).await?;
+    /// // Equivalent to `By::Css("div.content")`.
+    /// let elem_div = driver.find("div.content").await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn window(&self) -> WebDriverResult<WindowHandle> {
-        let r = self.cmd(Command::GetWindowHandle).await?;
-        Ok(WindowHandle::from(r.value::<String>()?))
+    pub async fn find(self: &Arc<Self>, by: impl Into<By>) -> WebDriverResult<WebElement> {
+        let r = self.cmd(Command::FindElement(by.into().into())).await?;
+        r.element(self.clone())
     }
 
-    /// Get the current window handle.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to window()")]
-    pub async fn current_window_handle(&self) -> WebDriverResult<WindowHandle> {
-        self.window().await
-    }
-
-    /// Get all window handles for the current session.
+    /// Search for an element on the current page using the specified CSS selector.
+    ///
+    /// This is a shortcut for `driver.find(By::Css(css))`.
     ///
     /// # Example:
     /// ```no_run
@@ -488,32 +837,19 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// assert_eq!(driver.windows().await?.len(), 1);
-    /// // Open a new tab.
-    /// driver.new_tab().await?;
-    ///
-    /// // Get window handles and switch to the new tab.
-    /// let handles = driver.windows().await?;
-    /// assert_eq!(handles.len(), 2);
-    /// driver.switch_to_window(handles[1].clone()).await?;
+    /// let elem = driver.find_css("#my-element-id .title").await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn windows(&self) -> WebDriverResult<Vec<WindowHandle>> {
-        let r = self.cmd(Command::GetWindowHandles).await?;
-        let handles: Vec<String> = r.value()?;
-        Ok(handles.into_iter().map(WindowHandle::from).collect())
-    }
-
-    /// Get all window handles for the current session.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to windows()")]
-    pub async fn window_handles(&self) -> WebDriverResult<Vec<WindowHandle>> {
-        self.windows().await
+    pub async fn find_css(self: &Arc<Self>, css: &str) -> WebDriverResult<WebElement> {
+        self.find(By::Css(css)).await
     }
 
-    /// Maximize the current window.
+    /// Search for an element on the current page using the specified XPath.
+    ///
+    /// This is a shortcut for `driver.find(By::XPath(xpath))`.
     ///
     /// # Example:
     /// ```no_run
@@ -524,23 +860,31 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.maximize_window().await?;
+    /// let elem = driver.find_xpath("//div[@id='my-element-id']").await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn maximize_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::MaximizeWindow).await?;
-        Ok(())
+    pub async fn find_xpath(self: &Arc<Self>, xpath: &str) -> WebDriverResult<WebElement> {
+        self.find(By::XPath(xpath)).await
     }
 
-    /// Minimize the current window.
+    /// Search for an element on the current page using the specified selector.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to find()")]
+    pub async fn find_element(self: &Arc<Self>, by: By) -> WebDriverResult<WebElement> {
+        self.find(by).await
+    }
+
+    /// Search for all elements on the current page that match the specified selector.
+    ///
+    /// **NOTE**: For more powerful element queries including polling and filters, see the
+    ///           [`WebDriver::query`] method instead.
+    ///
+    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
     ///
     /// # Example:
     /// ```no_run
-    /// # // Minimize is not currently working on Chrome, but does work
-    /// # // on Firefox/geckodriver.
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// #
@@ -548,18 +892,32 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.minimize_window().await?;
+    /// let elems = driver.find_all(By::ClassName("section")).await?;
+    /// for elem in elems {
+    ///     assert!(elem.attr("class").await?.expect("Missing class on element").contains("section"));
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn minimize_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::MinimizeWindow).await?;
-        Ok(())
+    pub async fn find_all(self: &Arc<Self>, by: impl Into<By>) -> WebDriverResult<Vec<WebElement>> {
+        let r = self.cmd(Command::FindElements(by.into().into())).await?;
+        r.elements(self.clone())
     }
 
-    /// Make the current window fullscreen.
+    /// Search for all elements on the current page that match the specified selector.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to find_all()")]
+    pub async fn find_elements(self: &Arc<Self>, by: By) -> WebDriverResult<Vec<WebElement>> {
+        self.find_all(by).await
+    }
+
+    /// Get the bounding rectangles of all the specified elements in a single request.
+    ///
+    /// This is equivalent to calling `WebElement::rect()` on each element, but uses one
+    /// script execution rather than one request per element. The rectangles are returned
+    /// in the same order as the elements, and are relative to the top-left corner of the
+    /// document, like `rect()`.
     ///
     /// # Example:
     /// ```no_run
@@ -570,46 +928,54 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.fullscreen_window().await?;
+    /// let cells = driver.find_all(By::Css(".grid .cell")).await?;
+    /// let rects = driver.rects_of(&cells).await?;
+    /// for pair in rects.windows(2) {
+    ///     assert!(pair[0].x <= pair[1].x);
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn fullscreen_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::FullscreenWindow).await?;
-        Ok(())
+    pub async fn rects_of(
+        self: &Arc<Self>,
+        elements: &[WebElement],
+    ) -> WebDriverResult<Vec<ElementRect>> {
+        if elements.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let elements = elements.iter().map(WebElement::to_json).collect::<WebDriverResult<_>>()?;
+        self.execute(ELEMENT_RECTS, vec![Value::Array(elements)]).await?.convert()
     }
 
-    /// Get the current window rectangle, in pixels.
-    ///
-    /// The returned Rect struct has members `x`, `y`, `width`, `height`,
-    /// all i32.
+    /// Execute the specified Javascript synchronously and return the result.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::Rect;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.set_window_rect(0, 0, 600, 400).await?;
-    /// let rect = driver.get_window_rect().await?;
-    /// assert_eq!(rect, Rect::new(0, 0, 600, 400));
+    /// let ret = driver.execute(r#"
+    ///     let elem = document.getElementById("button1");
+    ///     elem.click();
+    ///     return elem;
+    ///     "#, Vec::new()
+    /// ).await?;
+    /// let elem_out: WebElement = ret.element()?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn get_window_rect(&self) -> WebDriverResult<Rect> {
-        self.cmd(Command::GetWindowRect).await?.value()
-    }
-
-    /// Set the current window rectangle, in pixels.
-    ///
+    /// To supply an element as an input argument to a script, use
+    /// [`WebElement::to_json`] as follows:
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
@@ -618,30 +984,35 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.set_window_rect(0, 0, 500, 400).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let ret = driver.execute(r#"
+    ///     arguments[0].innerHTML = arguments[1];
+    ///     return arguments[0];
+    ///     "#, vec![elem.to_json()?, serde_json::to_value("TESTING")?]
+    /// ).await?;
+    /// let elem_out = ret.element()?;
+    /// assert_eq!(elem_out.element_id(), elem.element_id());
+    /// assert_eq!(elem_out.text().await?, "TESTING");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_window_rect(
-        &self,
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> WebDriverResult<()> {
-        let rect = OptionRect {
-            x: Some(x as i64),
-            y: Some(y as i64),
-            width: Some(width as i64),
-            height: Some(height as i64),
-        };
-        self.cmd(Command::SetWindowRect(rect)).await?;
-        Ok(())
+    pub async fn execute(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+    ) -> WebDriverResult<ScriptRet> {
+        let r = self.cmd(Command::ExecuteScript(script.into(), args.into())).await?;
+        Ok(ScriptRet::new(self.clone(), r.value()?))
     }
 
-    /// Go back. This is equivalent to clicking the browser's back button.
+    /// Read the Javascript in the specified file and execute it synchronously, the same
+    /// as [`SessionHandle::execute()`].
+    ///
+    /// This allows helper scripts to be kept in their own `.js` files rather than in
+    /// string literals. The file is read each time this is called. If the file cannot be
+    /// read, `WebDriverError::IoError` is returned.
     ///
     /// # Example:
     /// ```no_run
@@ -652,18 +1023,41 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.back().await?;
+    /// // scripts/sum.js contains: return arguments[0] + arguments[1];
+    /// let ret = driver.execute_file("scripts/sum.js", vec![1.into(), 2.into()]).await?;
+    /// let sum: i64 = ret.convert()?;
+    /// assert_eq!(sum, 3);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn back(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Back).await?;
-        Ok(())
+    pub async fn execute_file(
+        self: &Arc<Self>,
+        path: impl AsRef<Path>,
+        args: impl Into<Arc<[Value]>>,
+    ) -> WebDriverResult<ScriptRet> {
+        let script = tokio::fs::read_to_string(path).await?;
+        self.execute(script, args).await
     }
 
-    /// Go forward. This is equivalent to clicking the browser's forward button.
+    /// Execute the specified Javascript synchronously, using any serializable value as
+    /// the script arguments.
+    ///
+    /// The arguments are mapped to `arguments[i]` within the script as follows:
+    /// - A tuple, `Vec` or array becomes the arguments in order, i.e. `(a, b)` maps to
+    ///   `arguments[0]` and `arguments[1]`.
+    /// - A struct (or any other value that serializes to a JSON object) is flattened
+    ///   into its field values, in declaration order. This includes maps, whose values
+    ///   are passed in iteration order (so the keys are lost, and the order of a
+    ///   `HashMap` is unpredictable). To pass a map as a single object, wrap it in a
+    ///   tuple, e.g. `(map,)`.
+    /// - A single `WebElement` is passed as `arguments[0]`, like any other value.
+    /// - `()` means no arguments.
+    /// - Any other value is passed as `arguments[0]`.
+    ///
+    /// Any `WebElement` contained in the arguments is serialized as an element reference,
+    /// so it will be received by the script as a DOM element.
     ///
     /// # Example:
     /// ```no_run
@@ -672,20 +1066,51 @@ impl SessionHandle {
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
-    /// let caps = DesiredCapabilities::chrome();
+    /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.forward().await?;
+    /// #[derive(serde::Serialize)]
+    /// struct Args {
+    ///     elem: WebElement,
+    ///     text: String,
+    /// }
+    ///
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let args = Args {
+    ///     elem: elem.clone(),
+    ///     text: "TESTING".to_string(),
+    /// };
+    /// driver.execute_with("arguments[0].innerHTML = arguments[1];", args).await?;
+    /// assert_eq!(elem.text().await?, "TESTING");
+    ///
+    /// // Tuples work too.
+    /// driver.execute_with("arguments[0].innerHTML = arguments[1];", (&elem, "OK")).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn forward(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Forward).await?;
-        Ok(())
+    pub async fn execute_with<A>(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: A,
+    ) -> WebDriverResult<ScriptRet>
+    where
+        A: Serialize,
+    {
+        self.execute(script, script_args(serde_json::to_value(args)?)).await
     }
 
-    /// Refresh the current page.
+    /// Execute the specified Javascript synchronously, and lazily deserialize each item of
+    /// the array it returns.
+    ///
+    /// This is intended for scripts that return very large arrays. Each item is
+    /// deserialized directly from the raw response as the returned iterator advances,
+    /// without first building a `serde_json::Value` for the whole array. See
+    /// [`ScriptStream`] for details on memory usage.
+    ///
+    /// The script must return an array, otherwise a `WebDriverError::Json` error is
+    /// returned. Items cannot be deserialized as `WebElement`; use
+    /// [`ScriptRet::elements`] for that instead.
     ///
     /// # Example:
     /// ```no_run
@@ -696,178 +1121,273 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.refresh().await?;
+    /// let hrefs = driver
+    ///     .execute_stream::<String>(
+    ///         "return Array.from(document.links, a => a.href);",
+    ///         Vec::new(),
+    ///     )
+    ///     .await?;
+    /// for href in hrefs {
+    ///     println!("{}", href?);
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn refresh(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Refresh).await?;
-        Ok(())
+    pub async fn execute_stream<T>(
+        &self,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+    ) -> WebDriverResult<ScriptStream<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let request_data =
+            Command::ExecuteScript(script.into(), args.into()).format_request(&self.session_id);
+        let (_, body) = self.run_cmd_raw(&request_data, &self.config).await?;
+        ScriptStream::new(body)
     }
 
-    /// Get all timeouts for the current session.
+    /// Execute the specified Javascript synchronously and return the result.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to execute()")]
+    pub async fn execute_script(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: Vec<Value>,
+    ) -> WebDriverResult<ScriptRet> {
+        self.execute(script, args).await
+    }
+
+    /// Execute the specified Javascrypt asynchronously and return the result.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let timeouts = driver.get_timeouts().await?;
-    /// println!("Page load timeout = {:?}", timeouts.page_load());
+    /// let ret = driver.execute_async(r#"
+    ///     // Selenium automatically provides an extra argument which is a
+    ///     // function that receives the return value(s).
+    ///     let done = arguments[0];
+    ///     window.setTimeout(() => {
+    ///         let elem = document.getElementById("button1");
+    ///         elem.click();
+    ///         done(elem);
+    ///     }, 1000);
+    ///     "#, Vec::new()
+    /// ).await?;
+    /// let elem_out: WebElement = ret.element()?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn get_timeouts(&self) -> WebDriverResult<TimeoutConfiguration> {
-        self.cmd(Command::GetTimeouts).await?.value()
-    }
-
-    /// Set all timeouts for the current session.
-    ///
-    /// **NOTE:** Setting the implicit wait timeout to a non-zero value will interfere with the use
-    /// of [`WebDriver::query`] and [`WebElement::wait_until`].
-    /// It is therefore recommended to use these methods (which provide polling
-    /// and explicit waits) instead rather than increasing the implicit wait timeout.
-    ///
-    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
-    /// [`WebElement::wait_until`]: crate::extensions::query::ElementWaitable::wait_until
-    ///
+    /// To supply an element as an input argument to a script, use
+    /// [`WebElement::to_json`] as follows:
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// // Setting timeouts to None means those timeout values will not be updated.
-    /// let timeouts = TimeoutConfiguration::new(None, Some(Duration::new(11, 0)), None);
-    /// driver.update_timeouts(timeouts).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let args = vec![elem.to_json()?, serde_json::to_value("TESTING")?];
+    /// let ret = driver.execute_async(r#"
+    ///     // Selenium automatically provides an extra argument which is a
+    ///     // function that receives the return value(s).
+    ///     let done = arguments[2];
+    ///     window.setTimeout(() => {
+    ///         arguments[0].innerHTML = arguments[1];
+    ///         done(arguments[0]);
+    ///     }, 1000);
+    ///     "#, args
+    /// ).await?;
+    /// let elem_out = ret.element()?;
+    /// assert_eq!(elem_out.element_id(), elem.element_id());
+    /// assert_eq!(elem_out.text().await?, "TESTING");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn update_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
-        self.cmd(Command::SetTimeouts(timeouts)).await?;
-        Ok(())
-    }
-
-    /// Set all timeouts for the current session.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to update_timeouts()")]
-    pub async fn set_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
-        self.update_timeouts(timeouts).await
+    pub async fn execute_async(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+    ) -> WebDriverResult<ScriptRet> {
+        let r = self.cmd(Command::ExecuteAsyncScript(script.into(), args.into())).await?;
+        Ok(ScriptRet::new(self.clone(), r.value()?))
     }
 
-    /// Set the implicit wait timeout.
+    /// Execute the specified Javascript synchronously, using the specified script timeout
+    /// for this call only.
     ///
-    /// This is how long the WebDriver will wait when querying elements.
-    /// By default this is set to 0 seconds.
-    ///
-    /// **NOTE:** Setting the implicit wait timeout to a non-zero value will interfere with the use
-    /// of [`WebDriver::query`] and [`WebElement::wait_until`].
-    /// It is therefore recommended to use these methods (which provide polling
-    /// and explicit waits) instead rather than increasing the implicit wait timeout.
+    /// This is useful for guarding against a script that might never finish, such as an
+    /// injected script with an infinite loop, without changing the script timeout for
+    /// the rest of the session.
     ///
-    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
-    /// [`WebElement::wait_until`]: crate::extensions::query::ElementWaitable::wait_until
+    /// The current script timeout is read first, then set to `timeout` for the call,
+    /// and then restored afterwards, even if the script fails or the returned future is
+    /// dropped before it completes. In that case the previous timeout is restored in the
+    /// background. As for any script (see `SessionHandle::cmd()`), if `timeout` is longer
+    /// than `WebDriverConfig::request_timeout`, the request timeout for the script is
+    /// extended to `timeout` plus a margin, so that the script is not cut off before the
+    /// driver reports its own timeout.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::TimeoutConfiguration;
     /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_implicit_wait_timeout(delay).await?;
+    /// let ret = driver
+    ///     .execute_with_timeout("return document.title;", Vec::new(), Duration::from_secs(2))
+    ///     .await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_implicit_wait_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(None, None, Some(time_to_wait));
-        self.update_timeouts(timeouts).await
-    }
-
-    /// Set the script timeout.
-    ///
-    /// This is how long the WebDriver will wait for a Javascript script to execute.
-    /// By default this is set to 60 seconds.
+    pub async fn execute_with_timeout(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+        timeout: Duration,
+    ) -> WebDriverResult<ScriptRet> {
+        let command = Command::ExecuteScript(script.into(), args.into());
+        let r = self.with_script_timeout(timeout, || self.cmd(command)).await?;
+        Ok(ScriptRet::new(self.clone(), r.value()?))
+    }
+
+    /// Execute the specified Javascript asynchronously, using the specified script timeout
+    /// for this call only.
+    ///
+    /// See `WebDriver::execute_with_timeout()` for how the timeout is set and restored.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::TimeoutConfiguration;
     /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_script_timeout(delay).await?;
+    /// let ret = driver
+    ///     .execute_async_with_timeout(
+    ///         "let done = arguments[0]; window.setTimeout(() => done(1), 100);",
+    ///         Vec::new(),
+    ///         Duration::from_secs(2),
+    ///     )
+    ///     .await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_script_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(Some(time_to_wait), None, None);
-        self.update_timeouts(timeouts).await
+    pub async fn execute_async_with_timeout(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+        timeout: Duration,
+    ) -> WebDriverResult<ScriptRet> {
+        let command = Command::ExecuteAsyncScript(script.into(), args.into());
+        let r = self.with_script_timeout(timeout, || self.cmd(command)).await?;
+        Ok(ScriptRet::new(self.clone(), r.value()?))
     }
 
-    /// Set the page load timeout.
-    ///
-    /// This is how long the WebDriver will wait for the page to finish loading.
-    /// By default this is set to 60 seconds.
+    /// Run `f` with the script timeout set to `timeout`, restoring the previous script
+    /// timeout afterwards whether or not `f` succeeded.
+    async fn with_script_timeout<T, F, Fut>(
+        self: &Arc<Self>,
+        timeout: Duration,
+        f: F,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = WebDriverResult<T>>,
+    {
+        let previous = self.get_timeouts().await?.script();
+        self.set_script_timeout(timeout).await?;
+        let guard = RestoreScriptTimeout {
+            handle: Some(self.clone()),
+            previous,
+        };
+        let result = f().await;
+        let restored = guard.restore().await;
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Execute the specified Javascrypt asynchronously and return the result.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to execute_async()")]
+    pub async fn execute_script_async(
+        self: &Arc<Self>,
+        script: impl IntoArcStr,
+        args: impl Into<Arc<[Value]>>,
+    ) -> WebDriverResult<ScriptRet> {
+        self.execute_async(script, args.into()).await
+    }
+
+    /// Get the current window handle.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_page_load_timeout(delay).await?;
+    /// // Get the current window handle.
+    /// let handle = driver.window().await?;
+    ///
+    /// // Open a new tab.
+    /// driver.new_tab().await?;
+    ///
+    /// // Get window handles and switch to the new tab.
+    /// let handles = driver.windows().await?;
+    /// driver.switch_to_window(handles[1].clone()).await?;
+    ///
+    /// // We are now controlling the new tab.
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// assert_ne!(driver.window().await?, handle);
+    ///
+    /// // Switch back to original tab.
+    /// driver.switch_to_window(handle.clone()).await?;
+    /// assert_eq!(driver.window().await?, handle);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_page_load_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(None, Some(time_to_wait), None);
-        self.update_timeouts(timeouts).await
+    pub async fn window(&self) -> WebDriverResult<WindowHandle> {
+        let r = self.cmd(Command::GetWindowHandle).await?;
+        Ok(WindowHandle::from(r.value::<String>()?))
     }
 
-    /// Create a new action chain for this session.
-    ///
-    /// Action chains can be used to simulate more complex user input actions
-    /// involving key combinations, mouse movements, mouse click, right-click,
-    /// and more.
+    /// Get the current window handle.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to window()")]
+    pub async fn current_window_handle(&self) -> WebDriverResult<WindowHandle> {
+        self.window().await
+    }
+
+    /// Get all window handles for the current session.
     ///
     /// # Example:
     /// ```no_run
@@ -878,55 +1398,78 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem_text = driver.find(By::Name("input1")).await?;
-    /// let elem_button = driver.find(By::Id("button-set")).await?;
+    /// assert_eq!(driver.windows().await?.len(), 1);
+    /// // Open a new tab.
+    /// driver.new_tab().await?;
     ///
-    /// driver.action_chain()
-    ///     .send_keys_to_element(&elem_text, "thirtyfour")
-    ///     .move_to_element_center(&elem_button)
-    ///     .click()
-    ///     .perform()
-    ///     .await?;
+    /// // Get window handles and switch to the new tab.
+    /// let handles = driver.windows().await?;
+    /// assert_eq!(handles.len(), 2);
+    /// driver.switch_to_window(handles[1].clone()).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn action_chain(self: &Arc<SessionHandle>) -> ActionChain {
-        ActionChain::new(self.clone())
+    pub async fn windows(&self) -> WebDriverResult<Vec<WindowHandle>> {
+        let r = self.cmd(Command::GetWindowHandles).await?;
+        let handles: Vec<String> = r.value()?;
+        Ok(handles.into_iter().map(WindowHandle::from).collect())
     }
 
-    /// Get all cookies.
+    /// Wait until a window handle appears that is not in `before`, and return it.
+    ///
+    /// This is the reliable way to get the handle of a window opened by the page, for
+    /// example by clicking a link with `target="_blank"`, since the new window may not be
+    /// open yet when the click returns. Get the handles with `windows()` before the action
+    /// that opens the window, and then pass them to this method. It does not switch to the
+    /// new window, so use `switch_to_window()` with the returned handle to do that.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the handles last seen,
+    /// if no new window appears within the timeout.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let cookies = driver.get_all_cookies().await?;
-    /// for cookie in &cookies {
-    ///     println!("Got cookie: {}", cookie.value);
-    /// }
+    /// let before = driver.windows().await?;
+    /// driver.find(By::Id("open-popup")).await?.click().await?;
+    /// let popup = driver.wait_for_new_window(&before, Duration::from_secs(10)).await?;
+    /// driver.switch_to_window(popup).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
-        self.cmd(Command::GetAllCookies).await?.value()
-    }
-
-    /// Get all cookies.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to get_all_cookies()")]
-    pub async fn get_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
-        self.get_all_cookies().await
+    pub async fn wait_for_new_window(
+        &self,
+        before: &[WindowHandle],
+        timeout: Duration,
+    ) -> WebDriverResult<WindowHandle> {
+        let is_new = |handle: &WindowHandle| !before.contains(handle);
+        let handles = poll_value(
+            timeout,
+            "timed out waiting for a new window",
+            || self.windows(),
+            |handles| handles.iter().any(is_new),
+        )
+        .await?;
+        handles.into_iter().find(is_new).ok_or_else(|| {
+            WebDriverError::Timeout("timed out waiting for a new window".to_string())
+        })
     }
 
-    /// Get the specified cookie.
+    /// Get the handle, url, title and type of every open tab and window.
+    ///
+    /// On Chromium-based browsers, the details are fetched for all tabs at once using the
+    /// CDP `Target.getTargets` command, without switching to each tab. On other browsers,
+    /// only the handles are returned, with the other fields set to `None`.
     ///
     /// # Example:
     /// ```no_run
@@ -937,24 +1480,75 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let cookie = driver.get_named_cookie("key").await?;
-    /// println!("Got cookie: {}", cookie.value);
+    /// let tabs = driver.tabs().await?;
+    /// if let Some(tab) = tabs.iter().find(|t| t.url.as_deref() == Some("https://www.rust-lang.org/")) {
+    ///     driver.switch_to_window(tab.handle.clone()).await?;
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn get_named_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<Cookie> {
-        self.cmd(Command::GetNamedCookie(name.into())).await?.value()
+    pub async fn tabs(&self) -> WebDriverResult<Vec<TabInfo>> {
+        #[derive(Debug, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TargetInfo {
+            target_id: String,
+            #[serde(rename = "type")]
+            target_type: String,
+            title: String,
+            url: String,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Targets {
+            target_infos: Vec<TargetInfo>,
+        }
+
+        let handles = self.windows().await?;
+        let cdp = ChromeCommand::ExecuteCdpCommand("Target.getTargets".to_string(), json!({}));
+        let targets = match self.cmd(cdp).await {
+            Ok(r) => r.value::<Targets>()?.target_infos,
+            Err(WebDriverError::UnknownCommand(_) | WebDriverError::UnknownMethod(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let tabs = handles
+            .into_iter()
+            .map(|handle| {
+                let target = targets.iter().find(|t| t.target_id == handle.to_string());
+                TabInfo {
+                    url: target.map(|t| t.url.clone()),
+                    title: target.map(|t| t.title.clone()),
+                    target_type: target.map(|t| t.target_type.clone()),
+                    handle,
+                }
+            })
+            .collect();
+        Ok(tabs)
     }
 
-    /// Get the specified cookie.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to get_named_cookie()")]
-    pub async fn get_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<Cookie> {
-        self.get_named_cookie(name).await
+    /// Get all window handles for the current session.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to windows()")]
+    pub async fn window_handles(&self) -> WebDriverResult<Vec<WindowHandle>> {
+        self.windows().await
     }
 
-    /// Delete the specified cookie.
+    /// Send a command that returns the resulting window rectangle.
+    ///
+    /// Some drivers return null or an empty object instead, in which case the rectangle
+    /// is fetched separately.
+    async fn window_rect_cmd(&self, command: Command) -> WebDriverResult<WindowRect> {
+        match self.cmd(command).await?.value() {
+            Ok(rect) => Ok(rect),
+            Err(_) => self.get_window_rect().await,
+        }
+    }
+
+    /// Maximize the current window.
+    ///
+    /// Returns the resulting window rectangle.
     ///
     /// # Example:
     /// ```no_run
@@ -965,21 +1559,24 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.delete_cookie("key").await?;
+    /// driver.maximize_window().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn delete_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<()> {
-        self.cmd(Command::DeleteCookie(name.into())).await?;
-        Ok(())
+    pub async fn maximize_window(&self) -> WebDriverResult<WindowRect> {
+        self.window_rect_cmd(Command::MaximizeWindow).await
     }
 
-    /// Delete all cookies.
+    /// Minimize the current window.
+    ///
+    /// Returns the resulting window rectangle.
     ///
     /// # Example:
     /// ```no_run
+    /// # // Minimize is not currently working on Chrome, but does work
+    /// # // on Firefox/geckodriver.
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// #
@@ -987,18 +1584,19 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.delete_all_cookies().await?;
+    /// driver.minimize_window().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn delete_all_cookies(&self) -> WebDriverResult<()> {
-        self.cmd(Command::DeleteAllCookies).await?;
-        Ok(())
+    pub async fn minimize_window(&self) -> WebDriverResult<WindowRect> {
+        self.window_rect_cmd(Command::MinimizeWindow).await
     }
 
-    /// Add the specified cookie.
+    /// Make the current window fullscreen.
+    ///
+    /// Returns the resulting window rectangle.
     ///
     /// # Example:
     /// ```no_run
@@ -1009,99 +1607,47 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// driver.goto("https://wikipedia.org").await?;
-    /// let mut cookie = Cookie::new("key", "value");
-    /// cookie.set_domain("wikipedia.org");
-    /// cookie.set_path("/");
-    /// cookie.set_same_site(SameSite::Lax);
-    /// driver.add_cookie(cookie.clone()).await?;
+    /// driver.fullscreen_window().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
-        self.cmd(Command::AddCookie(cookie)).await?;
-        Ok(())
-    }
-
-    /// Take a screenshot of the current window and return it as PNG, base64 encoded.
-    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
-        self.cmd(Command::TakeScreenshot).await?.value()
-    }
-
-    /// Take a screenshot of the current window and return it as PNG bytes.
-    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
-        base64_decode(&self.screenshot_as_png_base64().await?)
-    }
-
-    /// Take a screenshot of the current window and write it to the specified filename.
-    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
-        let png = self.screenshot_as_png().await?;
-        let mut file = File::create(path).await?;
-        file.write_all(&png).await?;
-        Ok(())
-    }
-
-    /// Return a SwitchTo struct for switching to another window or frame.
-    #[deprecated(
-        since = "0.30.0",
-        note = "SwitchTo has been deprecated. Use WebDriver::switch_to_*() methods instead"
-    )]
-    pub fn switch_to(self: &Arc<SessionHandle>) -> SwitchTo {
-        SwitchTo::new(self.clone())
+    pub async fn fullscreen_window(&self) -> WebDriverResult<WindowRect> {
+        self.window_rect_cmd(Command::FullscreenWindow).await
     }
 
-    /// Set the current window name.
-    ///
-    /// Useful for switching between windows/tabs using [`WebDriver::switch_to_named_window`]
+    /// Get the current window rectangle, in pixels.
     ///
-    /// [`WebDriver::switch_to_named_window`]: SessionHandle::switch_to_named_window
+    /// The returned `WindowRect` struct has members `x`, `y`, `width`, `height`,
+    /// all i64.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::WindowRect;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// // Get the current window handle.
-    /// let handle = driver.window().await?;
-    /// driver.set_window_name("main").await?;
-    ///
-    /// // Open a new tab.
-    /// let new_handle = driver.new_tab().await?;
-    ///
-    /// // Get window handles and switch to the new tab.
-    /// driver.switch_to_window(new_handle).await?;
-    ///
-    /// // We are now controlling the new tab.
-    /// driver.goto("https://www.rust-lang.org").await?;
-    /// assert_ne!(driver.window().await?, handle);
-    ///
-    /// // Switch back to original tab using window name.
-    /// driver.switch_to_named_window("main").await?;
-    /// assert_eq!(driver.window().await?, handle);
+    /// driver.set_window_rect(0, 0, 600, 400).await?;
+    /// let rect = driver.get_window_rect().await?;
+    /// assert_eq!(rect, WindowRect::new(0, 0, 600, 400));
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_window_name(
-        self: &Arc<SessionHandle>,
-        window_name: impl Display,
-    ) -> WebDriverResult<()> {
-        let script = format!(r#"window.name = "{}""#, window_name);
-        self.execute(script, Vec::new()).await?;
-        Ok(())
+    pub async fn get_window_rect(&self) -> WebDriverResult<WindowRect> {
+        self.cmd(Command::GetWindowRect).await?.value()
     }
 
-    /// Execute the specified function in a new browser tab, closing the tab when complete.
+    /// Set the current window rectangle, in pixels.
     ///
-    /// The return value will be that of the supplied function, unless an error occurs while
-    /// opening or closing the tab.
+    /// Returns the resulting window rectangle, which may differ from the one requested
+    /// if the window could not be resized or moved exactly as specified.
     ///
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -1111,34 +1657,2305 @@ impl SessionHandle {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let window_title = driver.in_new_tab(|| async {
-    ///     driver.goto("https://www.google.com").await?;
-    ///     driver.title().await
-    /// }).await?;
-    /// assert_eq!(window_title, "Google");
+    /// driver.set_window_rect(0, 0, 500, 400).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn in_new_tab<F, Fut, T>(&self, f: F) -> WebDriverResult<T>
-    where
-        F: FnOnce() -> Fut + Send,
-        Fut: Future<Output = WebDriverResult<T>> + Send,
-        T: Send,
-    {
-        let handle = self.window().await?;
-
-        // Open new tab.
-        let tab_handle = self.new_tab().await?;
-        self.switch_to_window(tab_handle).await?;
-
-        let result = f().await;
-
-        // Close tab.
+    pub async fn set_window_rect(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> WebDriverResult<WindowRect> {
+        let rect = OptionRect {
+            x: Some(x as i64),
+            y: Some(y as i64),
+            width: Some(width as i64),
+            height: Some(height as i64),
+        };
+        self.window_rect_cmd(Command::SetWindowRect(rect)).await
+    }
+
+    /// Go back. This is equivalent to clicking the browser's back button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.back().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn back(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Back).await?;
+        Ok(())
+    }
+
+    /// Go forward. This is equivalent to clicking the browser's forward button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.forward().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn forward(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Forward).await?;
+        Ok(())
+    }
+
+    /// Refresh the current page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.refresh().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn refresh(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Refresh).await?;
+        Ok(())
+    }
+
+    /// Get all timeouts for the current session.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let timeouts = driver.get_timeouts().await?;
+    /// println!("Page load timeout = {:?}", timeouts.page_load());
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_timeouts(&self) -> WebDriverResult<TimeoutConfiguration> {
+        self.cmd(Command::GetTimeouts).await?.value()
+    }
+
+    /// Set all timeouts for the current session.
+    ///
+    /// **NOTE:** Setting the implicit wait timeout to a non-zero value will interfere with the use
+    /// of [`WebDriver::query`] and [`WebElement::wait_until`].
+    /// It is therefore recommended to use these methods (which provide polling
+    /// and explicit waits) instead rather than increasing the implicit wait timeout.
+    ///
+    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
+    /// [`WebElement::wait_until`]: crate::extensions::query::ElementWaitable::wait_until
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Setting timeouts to None means those timeout values will not be updated.
+    /// let timeouts = TimeoutConfiguration::new(None, Some(Duration::new(11, 0)), None);
+    /// driver.update_timeouts(timeouts).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn update_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
+        self.cmd(Command::SetTimeouts(timeouts)).await?;
+        Ok(())
+    }
+
+    /// Set all timeouts for the current session.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to update_timeouts()")]
+    pub async fn set_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
+        self.update_timeouts(timeouts).await
+    }
+
+    /// Set the implicit wait timeout.
+    ///
+    /// This is how long the WebDriver will wait when querying elements.
+    /// By default this is set to 0 seconds.
+    /// Only this timeout is sent to the WebDriver, so other timeouts are left unchanged.
+    ///
+    /// **NOTE:** Setting the implicit wait timeout to a non-zero value will interfere with the use
+    /// of [`WebDriver::query`] and [`WebElement::wait_until`].
+    /// It is therefore recommended to use these methods (which provide polling
+    /// and explicit waits) instead rather than increasing the implicit wait timeout.
+    ///
+    /// [`WebDriver::query`]: crate::extensions::query::ElementQueryable::query
+    /// [`WebElement::wait_until`]: crate::extensions::query::ElementWaitable::wait_until
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_implicit_wait_timeout(delay).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_implicit_wait_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(None, None, Some(time_to_wait));
+        self.update_timeouts(timeouts).await
+    }
+
+    /// Set the script timeout.
+    ///
+    /// This is how long the WebDriver will wait for a Javascript script to execute.
+    /// By default this is set to 60 seconds.
+    /// Only this timeout is sent to the WebDriver, so other timeouts are left unchanged.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_script_timeout(delay).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_script_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(Some(time_to_wait), None, None);
+        self.update_timeouts(timeouts).await
+    }
+
+    /// Set the page load timeout.
+    ///
+    /// This is how long the WebDriver will wait for the page to finish loading.
+    /// By default this is set to 60 seconds.
+    /// Only this timeout is sent to the WebDriver, so other timeouts are left unchanged.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_page_load_timeout(delay).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_page_load_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(None, Some(time_to_wait), None);
+        self.update_timeouts(timeouts).await
+    }
+
+    /// Create a new action chain for this session.
+    ///
+    /// Action chains can be used to simulate more complex user input actions
+    /// involving key combinations, mouse movements, mouse click, right-click,
+    /// and more.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem_text = driver.find(By::Name("input1")).await?;
+    /// let elem_button = driver.find(By::Id("button-set")).await?;
+    ///
+    /// driver.action_chain()
+    ///     .send_keys_to_element(&elem_text, "thirtyfour")
+    ///     .move_to_element_center(&elem_button)
+    ///     .click()
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn action_chain(self: &Arc<SessionHandle>) -> ActionChain {
+        ActionChain::new(self.clone())
+    }
+
+    /// Create a new [`WebDriverWait`] for waiting on elements with the specified timeout.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.wait(Duration::from_secs(10)).until_present(By::Id("button1")).await?;
+    /// let elems = driver.wait(Duration::from_secs(10)).until_all_present(By::Tag("button")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn wait(self: &Arc<SessionHandle>, timeout: Duration) -> WebDriverWait {
+        WebDriverWait::new(self.clone(), timeout)
+    }
+
+    /// Iterate over a paginated set of results, returning a stream of page results.
+    ///
+    /// For each page, all elements matching `rows` are passed to the `extract` closure,
+    /// and its output is yielded by the stream. Before reading the next page, the
+    /// `next_button` element is clicked and the old rows are waited on until they become
+    /// stale, to ensure the same page is not read twice.
+    ///
+    /// The stream ends when the `next_button` element is absent or disabled, or after
+    /// `max_pages` pages have been read.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use futures::TryStreamExt;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let pages: Vec<Vec<String>> = driver
+    ///     .paginate(By::Css("table tr"), By::Id("next-page"), 10, |rows| async move {
+    ///         let mut texts = Vec::new();
+    ///         for row in rows {
+    ///             texts.push(row.text().await?);
+    ///         }
+    ///         Ok(texts)
+    ///     })
+    ///     .try_collect()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn paginate<T, F, Fut>(
+        self: &Arc<SessionHandle>,
+        rows: By,
+        next_button: By,
+        max_pages: usize,
+        extract: F,
+    ) -> impl Stream<Item = WebDriverResult<T>>
+    where
+        F: FnMut(Vec<WebElement>) -> Fut,
+        Fut: Future<Output = WebDriverResult<T>>,
+    {
+        struct PageState<F> {
+            handle: Arc<SessionHandle>,
+            rows: By,
+            next_button: By,
+            extract: F,
+            page: usize,
+            prev_rows: Vec<WebElement>,
+        }
+
+        let state = PageState {
+            handle: self.clone(),
+            rows,
+            next_button,
+            extract,
+            page: 0,
+            prev_rows: Vec::new(),
+        };
+
+        futures::stream::try_unfold(state, move |mut state| async move {
+            if state.page >= max_pages {
+                return Ok(None);
+            }
+
+            if state.page > 0 {
+                match state.handle.query(state.next_button.clone()).nowait().first_opt().await? {
+                    Some(button) if button.is_enabled().await? => button.click().await?,
+                    _ => return Ok(None),
+                }
+
+                if let Some(row) = state.prev_rows.first() {
+                    row.wait_until().stale().await?;
+                }
+            }
+
+            let elements = state.handle.query(state.rows.clone()).all_from_selector().await?;
+            let result = (state.extract)(elements.clone()).await?;
+            state.page += 1;
+            state.prev_rows = elements;
+            Ok(Some((result, state)))
+        })
+    }
+
+    /// Repeatedly scroll to the bottom of the page until the page stops growing, for
+    /// pages that load more content as you scroll (infinite scroll).
+    ///
+    /// After each scroll to `document.body.scrollHeight`, this waits for `settle` and
+    /// then checks whether the height increased. It stops as soon as the height did not
+    /// increase, or once `max_scrolls` scrolls have been performed, and returns the number
+    /// of scrolls performed.
+    ///
+    /// A longer `settle` delay gives slow pages more time to load the next batch of
+    /// content, at the cost of waiting that long after the final scroll too. If content
+    /// loads slower than `settle`, this may stop before the page is fully loaded.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let scrolls = driver.scroll_to_bottom_until_stable(50, Duration::from_millis(500)).await?;
+    /// let items = driver.find_all(By::Css(".feed-item")).await?;
+    /// println!("Loaded {} items after {scrolls} scrolls", items.len());
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn scroll_to_bottom_until_stable(
+        self: &Arc<Self>,
+        max_scrolls: usize,
+        settle: Duration,
+    ) -> WebDriverResult<usize> {
+        let mut height: u64 = self.execute(SCROLL_HEIGHT, Vec::new()).await?.convert()?;
+        let mut scrolls = 0;
+        while scrolls < max_scrolls {
+            self.execute(SCROLL_TO_BOTTOM, Vec::new()).await?;
+            scrolls += 1;
+            sleep(settle).await;
+
+            let new_height: u64 = self.execute(SCROLL_HEIGHT, Vec::new()).await?.convert()?;
+            if new_height <= height {
+                break;
+            }
+            height = new_height;
+        }
+        Ok(scrolls)
+    }
+
+    /// Get all cookies.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let cookies = driver.get_all_cookies().await?;
+    /// for cookie in &cookies {
+    ///     println!("Got cookie: {}", cookie.value);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        self.cmd(Command::GetAllCookies).await?.value()
+    }
+
+    /// Get all cookies.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to get_all_cookies()")]
+    pub async fn get_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        self.get_all_cookies().await
+    }
+
+    /// Get the specified cookie.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let cookie = driver.get_named_cookie("key").await?;
+    /// println!("Got cookie: {}", cookie.value);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_named_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<Cookie> {
+        self.cmd(Command::GetNamedCookie(name.into())).await?.value()
+    }
+
+    /// Get the specified cookie, or `None` if no cookie with that name exists.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// if let Some(cookie) = driver.get_named_cookie_opt("key").await? {
+    ///     println!("Got cookie: {}", cookie.value);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_named_cookie_opt(
+        &self,
+        name: impl IntoArcStr,
+    ) -> WebDriverResult<Option<Cookie>> {
+        match self.get_named_cookie(name).await {
+            Ok(cookie) => Ok(Some(cookie)),
+            Err(WebDriverError::NoSuchCookie(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return true if a cookie with the specified name exists for the current page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// if driver.cookie_exists("session").await? {
+    ///     println!("Logged in");
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn cookie_exists(&self, name: impl IntoArcStr) -> WebDriverResult<bool> {
+        Ok(self.get_named_cookie_opt(name).await?.is_some())
+    }
+
+    /// Get all cookies for the current page that match the specified predicate.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let insecure = driver.cookies_matching(|c| c.secure != Some(true)).await?;
+    /// for cookie in insecure {
+    ///     println!("Insecure cookie: {}", cookie.name);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn cookies_matching(
+        &self,
+        mut predicate: impl FnMut(&Cookie) -> bool,
+    ) -> WebDriverResult<Vec<Cookie>> {
+        let mut cookies = self.get_all_cookies().await?;
+        cookies.retain(|c| predicate(c));
+        Ok(cookies)
+    }
+
+    /// Get the cookies that are visible to JavaScript on the current page, by parsing
+    /// `document.cookie`, as a map of cookie names to values.
+    ///
+    /// This differs from [`SessionHandle::get_all_cookies()`], which uses the WebDriver
+    /// cookie API: that includes `HttpOnly` cookies, which JavaScript cannot see, and
+    /// returns details such as the domain, path and expiry. `document.cookie` only has
+    /// the names and values, so this is useful for asserting on what the page's own
+    /// scripts can read. The values are returned exactly as they appear in
+    /// `document.cookie`, without any decoding. If more than one cookie has the same
+    /// name, for example with different paths, only the first one is kept.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let cookies = driver.js_cookies().await?;
+    /// assert!(!cookies.contains_key("session"), "the session cookie should be HttpOnly");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn js_cookies(self: &Arc<Self>) -> WebDriverResult<HashMap<String, String>> {
+        let cookies: String =
+            self.execute("return document.cookie;", Vec::new()).await?.convert()?;
+        Ok(parse_document_cookie(&cookies))
+    }
+
+    /// Get the specified cookie.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to get_named_cookie()")]
+    pub async fn get_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<Cookie> {
+        self.get_named_cookie(name).await
+    }
+
+    /// Delete the specified cookie.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.delete_cookie("key").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn delete_cookie(&self, name: impl IntoArcStr) -> WebDriverResult<()> {
+        self.cmd(Command::DeleteCookie(name.into())).await?;
+        Ok(())
+    }
+
+    /// Delete all cookies.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.delete_all_cookies().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn delete_all_cookies(&self) -> WebDriverResult<()> {
+        self.cmd(Command::DeleteAllCookies).await?;
+        Ok(())
+    }
+
+    /// Add the specified cookie.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto("https://wikipedia.org").await?;
+    /// let mut cookie = Cookie::new("key", "value");
+    /// cookie.set_domain("wikipedia.org");
+    /// cookie.set_path("/");
+    /// cookie.set_same_site(SameSite::Lax);
+    /// driver.add_cookie(cookie.clone()).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
+        self.cmd(Command::AddCookie(cookie)).await?;
+        Ok(())
+    }
+
+    /// Add the specified cookie, defaulting its domain and path to those of the current URL.
+    ///
+    /// If the cookie has no domain, it is set to the host of the current URL. If it has
+    /// no path, it is set to the directory of the current URL's path, which is the
+    /// default a browser uses for a cookie set by the page. Values that are already set
+    /// are not changed, so a domain that does not match the current page is still
+    /// rejected by the WebDriver server.
+    ///
+    /// For IP addresses and single-label hosts such as `localhost`, which some drivers
+    /// reject as an explicit cookie domain, the domain is left unset. The WebDriver server
+    /// then adds the cookie for the current host only.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto("https://www.wikipedia.org/wiki/Rust").await?;
+    /// // The cookie is added for domain "www.wikipedia.org" and path "/wiki".
+    /// driver.add_cookie_for_current(Cookie::new("key", "value")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn add_cookie_for_current(&self, mut cookie: Cookie) -> WebDriverResult<()> {
+        if cookie.domain.is_none() || cookie.path.is_none() {
+            let url = self.current_url().await?;
+            if cookie.domain.is_none() {
+                cookie.domain = match url.host() {
+                    Some(url::Host::Domain(host)) if host.contains('.') => Some(host.to_string()),
+                    _ => None,
+                };
+            }
+            if cookie.path.is_none() {
+                cookie.path = Some(default_cookie_path(url.path()).to_string());
+            }
+        }
+        self.add_cookie(cookie).await
+    }
+
+    /// Take a screenshot of the current window and return it as PNG, base64 encoded.
+    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
+        self.cmd(Command::TakeScreenshot).await?.value()
+    }
+
+    /// Take a screenshot of the current window and return it as PNG bytes.
+    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        base64_decode(&self.screenshot_as_png_base64().await?)
+    }
+
+    /// Take a screenshot of the current window, cropped to the specified region,
+    /// and return it as PNG bytes.
+    ///
+    /// The region is in CSS pixels relative to the top-left corner of the viewport, the
+    /// same as the coordinates returned by `WebElement::rect()`. It is scaled by
+    /// `window.devicePixelRatio` before cropping, so the result has the full resolution of
+    /// the screenshot. Returns `WebDriverError::InvalidArgument` if the region is empty or
+    /// extends past the edge of the screenshot.
+    ///
+    /// This method requires the `image` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let png = driver.screenshot_region(0, 0, 200, 100).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn screenshot_region(
+        self: &Arc<Self>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> WebDriverResult<Vec<u8>> {
+        use crate::png::{self, Region};
+
+        let ratio: f64 =
+            self.execute("return window.devicePixelRatio || 1;", Vec::new()).await?.convert()?;
+        let scale = |v: u32| (v as f64 * ratio).round() as u32;
+        let image = png::decode(&self.screenshot_as_png().await?)?;
+        let cropped = png::crop(
+            &image,
+            Region {
+                x: scale(x),
+                y: scale(y),
+                width: scale(width),
+                height: scale(height),
+            },
+        )?;
+        png::encode(&cropped)
+    }
+
+    /// Take a screenshot of the current window and write it to the specified filename.
+    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.screenshot_as_png().await?;
+        let mut file = File::create(path).await?;
+        file.write_all(&png).await?;
+        Ok(())
+    }
+
+    /// Print the current page to PDF and return the PDF bytes.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::{PrintOrientation, PrintParameters};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let params = PrintParameters {
+    ///     orientation: PrintOrientation::Landscape,
+    ///     ..Default::default()
+    /// };
+    /// let pdf = driver.print_page(params).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn print_page(&self, params: PrintParameters) -> WebDriverResult<Vec<u8>> {
+        let pdf: String = self.cmd(Command::PrintPage(params)).await?.value()?;
+        base64_decode(&pdf)
+    }
+
+    /// Print the current page to PDF and save it to the specified file.
+    ///
+    /// Any missing parent directories are created. The PDF is written to a temporary file
+    /// first and then renamed, so `path` never contains a partially written PDF.
+    /// Filesystem errors are returned as `WebDriverError::IoError`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::path::Path;
+    /// use thirtyfour::PrintParameters;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.print_page_to_file(Path::new("reports/page.pdf"), PrintParameters::default()).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn print_page_to_file(
+        &self,
+        path: &Path,
+        params: PrintParameters,
+    ) -> WebDriverResult<()> {
+        let pdf = self.print_page(params).await?;
+        write_file_atomic(path, &pdf).await
+    }
+
+    /// Set the state of the specified browser permission, such as `"notifications"`
+    /// or `"clipboard-read"`, for all origins.
+    ///
+    /// On Chromium-based browsers this uses the CDP `Browser.setPermission` command.
+    /// Other browsers use the WebDriver `Set Permission` extension command from the
+    /// Permissions specification, which recent versions of geckodriver support for
+    /// a subset of permissions.
+    ///
+    /// Returns `WebDriverError::UnsupportedOperation` if the browser supports neither.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::PermissionState;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.set_permission("notifications", PermissionState::Granted).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_permission(&self, name: &str, state: PermissionState) -> WebDriverResult<()> {
+        let params = json!({ "permission": { "name": name }, "setting": state });
+        let cdp = ChromeCommand::ExecuteCdpCommand("Browser.setPermission".to_string(), params);
+        match self.cmd(cdp).await {
+            Ok(_) => return Ok(()),
+            Err(WebDriverError::UnknownCommand(_) | WebDriverError::UnknownMethod(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        match self.cmd(Command::SetPermission(name.into(), state)).await {
+            Ok(_) => Ok(()),
+            Err(WebDriverError::UnknownCommand(_) | WebDriverError::UnknownMethod(_)) => {
+                Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                    "setting the '{name}' permission is not supported by this browser"
+                ))))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Grant the specified clipboard permission, then run the clipboard script.
+    async fn clipboard(
+        self: &Arc<Self>,
+        permission: &str,
+        script: &str,
+        args: Vec<Value>,
+    ) -> WebDriverResult<Option<String>> {
+        #[derive(Deserialize)]
+        struct ClipboardResult {
+            text: Option<String>,
+            error: Option<String>,
+        }
+
+        // The page may already have access, so try the Clipboard API even if the browser
+        // cannot grant the permission. Any other error is returned.
+        match self.set_permission(permission, PermissionState::Granted).await {
+            Ok(()) | Err(WebDriverError::UnsupportedOperation(_)) => {}
+            Err(e) => return Err(e),
+        }
+        let ret: ClipboardResult = self.execute_async(script, args).await?.convert()?;
+        match ret.error {
+            Some(error) => Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                format!("clipboard access failed: {error}"),
+            ))),
+            None => Ok(ret.text),
+        }
+    }
+
+    /// Read text from the clipboard.
+    ///
+    /// The WebDriver spec has no clipboard commands, so this first grants the
+    /// `clipboard-read` permission (see `WebDriver::set_permission()`), which uses CDP on
+    /// Chromium-based browsers, and then reads the clipboard with
+    /// `navigator.clipboard.readText()` in the current page. The Clipboard API is the
+    /// only mechanism used on every browser; CDP is only used to grant the permission.
+    /// The permission stays granted afterwards.
+    ///
+    /// The Clipboard API is only available in secure contexts (`https://` pages and
+    /// `http://localhost`), and the browser window usually needs to have focus. Returns
+    /// `WebDriverError::UnsupportedOperation` if the clipboard cannot be read, for
+    /// example because the permission was not granted or the Clipboard API is missing.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.find(By::Id("copy-link")).await?.click().await?;
+    /// assert_eq!(driver.read_clipboard().await?, "https://example.com/share/123");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn read_clipboard(self: &Arc<Self>) -> WebDriverResult<String> {
+        let text = self.clipboard("clipboard-read", READ_CLIPBOARD, Vec::new()).await?;
+        Ok(text.unwrap_or_default())
+    }
+
+    /// Write text to the clipboard.
+    ///
+    /// This grants the `clipboard-write` permission and then calls
+    /// `navigator.clipboard.writeText()` in the current page. See
+    /// `WebDriver::read_clipboard()` for the requirements and errors.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.write_clipboard("pasted text").await?;
+    /// let elem = driver.find(By::Id("input")).await?;
+    /// elem.send_keys(Key::Control + "v").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn write_clipboard(self: &Arc<Self>, text: &str) -> WebDriverResult<()> {
+        let args = vec![Value::String(text.to_string())];
+        self.clipboard("clipboard-write", WRITE_CLIPBOARD, args).await?;
+        Ok(())
+    }
+
+    /// Override the user agent string for the current session.
+    ///
+    /// The mechanism depends on the browser, which is read from the `browserName`
+    /// capability returned when the session was created:
+    ///
+    /// - Chromium-based browsers (Chrome, Edge) use the CDP `Network.setUserAgentOverride`
+    ///   command. The override applies to the current tab, to both requests and
+    ///   `navigator.userAgent`, starting from the next request.
+    /// - Firefox cannot change the user agent of a running session, so this returns
+    ///   `WebDriverError::UnsupportedOperation`. Use `FirefoxCapabilities::set_user_agent()`
+    ///   to set it before the session is created instead.
+    /// - Safari does not support overriding the user agent, so this returns
+    ///   `WebDriverError::UnsupportedOperation`.
+    ///
+    /// If the browser is unknown, the CDP command is attempted, and
+    /// `WebDriverError::UnsupportedOperation` is returned if the driver does not support it.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.set_user_agent("my-crawler/1.0").await?;
+    /// driver.goto("https://www.rust-lang.org").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_user_agent(&self, user_agent: &str) -> WebDriverResult<()> {
+        let unsupported = |msg: &str| {
+            Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(msg.to_string())))
+        };
+        match self.browser_name().map(|name| name.to_ascii_lowercase()).as_deref() {
+            Some("firefox") => {
+                return unsupported(
+                    "Firefox cannot change the user agent of a running session. \
+                     Use FirefoxCapabilities::set_user_agent() before creating the session",
+                );
+            }
+            Some("safari") => {
+                return unsupported("Safari does not support overriding the user agent")
+            }
+            _ => {}
+        }
+
+        let params = json!({ "userAgent": user_agent });
+        let cdp =
+            ChromeCommand::ExecuteCdpCommand("Network.setUserAgentOverride".to_string(), params);
+        match self.cmd(cdp).await {
+            Ok(_) => Ok(()),
+            Err(WebDriverError::UnknownCommand(_) | WebDriverError::UnknownMethod(_)) => {
+                unsupported("overriding the user agent is not supported by this browser")
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Override the time zone of the current session, using an IANA time zone name such
+    /// as `"America/New_York"`. An empty string removes the override.
+    ///
+    /// This uses the CDP `Emulation.setTimezoneOverride` command, so it is only supported
+    /// by Chromium-based browsers (Chrome, Edge). For Firefox and Safari this returns
+    /// `WebDriverError::UnsupportedOperation`, as it does if the driver does not support
+    /// the CDP command.
+    ///
+    /// The name is checked against the time zones known to the browser first, and
+    /// `WebDriverError::InvalidArgument` is returned if it is not one of them.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.set_timezone("Asia/Tokyo").await?;
+    /// let script = "return Intl.DateTimeFormat().resolvedOptions().timeZone;";
+    /// let timezone: String = driver.execute(script, Vec::new()).await?.convert()?;
+    /// assert_eq!(timezone, "Asia/Tokyo");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_timezone(self: &Arc<Self>, timezone: &str) -> WebDriverResult<()> {
+        let unsupported = |msg: &str| {
+            Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(msg.to_string())))
+        };
+        match self.browser_name().map(|name| name.to_ascii_lowercase()).as_deref() {
+            Some("firefox") => {
+                return unsupported("Firefox does not support overriding the time zone")
+            }
+            Some("safari") => {
+                return unsupported("Safari does not support overriding the time zone")
+            }
+            _ => {}
+        }
+
+        if !timezone.is_empty() {
+            let args = vec![Value::String(timezone.to_string())];
+            let valid: bool = self.execute(IS_VALID_TIMEZONE, args).await?.convert()?;
+            if !valid {
+                return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                    "'{timezone}' is not a valid IANA time zone name, e.g. 'Europe/London'"
+                ))));
+            }
+        }
+
+        let params = json!({ "timezoneId": timezone });
+        let cdp =
+            ChromeCommand::ExecuteCdpCommand("Emulation.setTimezoneOverride".to_string(), params);
+        match self.cmd(cdp).await {
+            Ok(_) => Ok(()),
+            Err(WebDriverError::UnknownCommand(_) | WebDriverError::UnknownMethod(_)) => {
+                unsupported("overriding the time zone is not supported by this browser")
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return a `SwitchTo` builder for switching to another window, frame or alert.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.switch_to().frame_index(0).await?;
+    /// driver.switch_to().parent_frame().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn switch_to(self: &Arc<SessionHandle>) -> SwitchTo {
+        SwitchTo::new(self.clone())
+    }
+
+    /// Set the current window name.
+    ///
+    /// Useful for switching between windows/tabs using [`WebDriver::switch_to_named_window`]
+    ///
+    /// [`WebDriver::switch_to_named_window`]: SessionHandle::switch_to_named_window
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Get the current window handle.
+    /// let handle = driver.window().await?;
+    /// driver.set_window_name("main").await?;
+    ///
+    /// // Open a new tab.
+    /// let new_handle = driver.new_tab().await?;
+    ///
+    /// // Get window handles and switch to the new tab.
+    /// driver.switch_to_window(new_handle).await?;
+    ///
+    /// // We are now controlling the new tab.
+    /// driver.goto("https://www.rust-lang.org").await?;
+    /// assert_ne!(driver.window().await?, handle);
+    ///
+    /// // Switch back to original tab using window name.
+    /// driver.switch_to_named_window("main").await?;
+    /// assert_eq!(driver.window().await?, handle);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_window_name(
+        self: &Arc<SessionHandle>,
+        window_name: impl Display,
+    ) -> WebDriverResult<()> {
+        let script = format!(r#"window.name = "{}""#, window_name);
+        self.execute(script, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Execute the specified function in a new browser tab, closing the tab when complete.
+    ///
+    /// The return value will be that of the supplied function, unless an error occurs while
+    /// opening or closing the tab.
+    ///
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let window_title = driver.in_new_tab(|| async {
+    ///     driver.goto("https://www.google.com").await?;
+    ///     driver.title().await
+    /// }).await?;
+    /// assert_eq!(window_title, "Google");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn in_new_tab<F, Fut, T>(&self, f: F) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        let handle = self.window().await?;
+
+        // Open new tab.
+        let tab_handle = self.new_tab().await?;
+        self.switch_to_window(tab_handle).await?;
+
+        let result = f().await;
+
+        // Close tab.
         self.close_window().await?;
         self.switch_to_window(handle).await?;
 
         result
     }
 }
+
+/// Convert the serialized value into a list of script arguments.
+///
+/// See [`SessionHandle::execute_with`] for details of the mapping.
+/// Gets the entries in the Chromium performance log since it was last read.
+#[derive(Debug)]
+struct GetPerformanceLog;
+
+impl FormatRequestData for GetPerformanceLog {
+    fn format_request(&self, session_id: &SessionId) -> RequestData {
+        RequestData::new(http::Method::POST, format!("/session/{}/se/log", session_id))
+            .add_body(json!({ "type": "performance" }))
+    }
+}
+
+/// The script and page load timeouts of a session, where `None` means no timeout.
+#[derive(Debug, Clone, Copy)]
+struct SessionTimeouts {
+    script: Option<Duration>,
+    page_load: Option<Duration>,
+}
+
+impl Default for SessionTimeouts {
+    /// The defaults from the W3C WebDriver spec.
+    fn default() -> Self {
+        Self {
+            script: Some(Duration::from_secs(30)),
+            page_load: Some(Duration::from_secs(300)),
+        }
+    }
+}
+
+impl SessionTimeouts {
+    /// Update the timeouts from a JSON timeouts object. Missing keys are left unchanged.
+    fn update(&mut self, timeouts: &Value) {
+        for (key, timeout) in [("script", &mut self.script), ("pageLoad", &mut self.page_load)] {
+            match timeouts.get(key) {
+                Some(Value::Null) => *timeout = None,
+                Some(ms) => {
+                    if let Some(ms) = ms.as_u64() {
+                        *timeout = Some(Duration::from_millis(ms));
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// The request timeout needed for the specified request, if the one in `config` is
+    /// not long enough.
+    fn request_timeout(
+        &self,
+        request: &RequestData,
+        config: &WebDriverConfig,
+    ) -> Option<Option<Duration>> {
+        let request_timeout = config.request_timeout?;
+        if request.method != http::Method::POST {
+            return None;
+        }
+        let uri = &*request.uri;
+        let session_timeout = if uri.ends_with("/execute/sync") || uri.ends_with("/execute/async") {
+            self.script
+        } else if ["/url", "/refresh", "/back", "/forward"].iter().any(|s| uri.ends_with(s)) {
+            self.page_load
+        } else {
+            return None;
+        };
+        match session_timeout {
+            Some(timeout) => {
+                let needed = timeout.saturating_add(SESSION_TIMEOUT_MARGIN);
+                (request_timeout < needed).then_some(Some(needed))
+            }
+            None => Some(None),
+        }
+    }
+}
+
+/// The number of pending network requests, and how long the network has been idle.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkActivity {
+    pending: u32,
+    idle_ms: f64,
+}
+
+/// The network requests seen in the performance log, for `wait_for_network_idle()`.
+#[derive(Debug, Default)]
+struct NetworkLog {
+    /// Whether the performance log is available, or `None` if it has not been read yet.
+    available: Option<bool>,
+    /// The ids of the requests that have started but not finished.
+    pending: std::collections::HashSet<String>,
+    /// When a request last started or finished.
+    last_activity: Option<std::time::Instant>,
+}
+
+impl NetworkLog {
+    /// Update the pending requests from the specified performance log entries.
+    ///
+    /// The message of each entry is a JSON string containing a CDP event.
+    fn update(&mut self, entries: &[Value]) {
+        for entry in entries {
+            let Some(message) = entry["message"].as_str() else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_str::<Value>(message) else {
+                continue;
+            };
+            let event = &message["message"];
+            let Some(id) = event["params"]["requestId"].as_str() else {
+                continue;
+            };
+            match event["method"].as_str() {
+                Some("Network.requestWillBeSent") => {
+                    self.pending.insert(id.to_string());
+                }
+                Some("Network.loadingFinished" | "Network.loadingFailed") => {
+                    self.pending.remove(id);
+                }
+                _ => continue,
+            }
+            self.last_activity = Some(std::time::Instant::now());
+        }
+    }
+
+    fn activity(&self) -> NetworkActivity {
+        let idle = self.last_activity.map_or(Duration::MAX, |t| t.elapsed());
+        NetworkActivity {
+            pending: self.pending.len() as u32,
+            idle_ms: idle.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Sets only the script timeout, where `None` means no timeout.
+///
+/// Unlike `Command::SetTimeouts`, this sends `null` rather than omitting the timeout.
+#[derive(Debug)]
+struct SetScriptTimeout(Option<Duration>);
+
+impl FormatRequestData for SetScriptTimeout {
+    fn format_request(&self, session_id: &SessionId) -> RequestData {
+        let script = self.0.map(|timeout| timeout.as_millis() as u64);
+        RequestData::new(http::Method::POST, format!("/session/{}/timeouts", session_id))
+            .add_body(json!({ "script": script }))
+    }
+}
+
+/// Restores the previous script timeout for `SessionHandle::with_script_timeout()`.
+///
+/// If this is dropped without calling `restore()`, because the future running the script
+/// was dropped, the timeout is restored in the background instead.
+struct RestoreScriptTimeout {
+    handle: Option<Arc<SessionHandle>>,
+    previous: Option<Duration>,
+}
+
+impl RestoreScriptTimeout {
+    async fn restore(mut self) -> WebDriverResult<()> {
+        match self.handle.take() {
+            Some(handle) => handle.cmd(SetScriptTimeout(self.previous)).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RestoreScriptTimeout {
+    fn drop(&mut self) {
+        let (Some(handle), Ok(runtime)) =
+            (self.handle.take(), tokio::runtime::Handle::try_current())
+        else {
+            return;
+        };
+        let previous = self.previous;
+        runtime.spawn(async move {
+            if let Err(e) = handle.cmd(SetScriptTimeout(previous)).await {
+                tracing::debug!("failed to restore the script timeout: {e}");
+            }
+        });
+    }
+}
+
+/// Convert the serialized arguments for `SessionHandle::execute_with()` into the list of
+/// script arguments.
+///
+/// Objects are flattened into their values, except for element and shadow root
+/// references, which are passed as a single argument.
+fn script_args(value: Value) -> Vec<Value> {
+    match value {
+        Value::Null => Vec::new(),
+        Value::Array(values) => values,
+        Value::Object(map) if !is_element_reference(&map) => {
+            map.into_iter().map(|(_, v)| v).collect()
+        }
+        value => vec![value],
+    }
+}
+
+/// Return true if the object is a W3C or legacy element reference, or a shadow root
+/// reference.
+fn is_element_reference(map: &serde_json::Map<String, Value>) -> bool {
+    let keys = [MAGIC_ELEMENTID, "ELEMENT", "shadow-6066-11e4-a52e-4f735466cecf"];
+    map.len() == 1 && map.keys().all(|key| keys.contains(&key.as_str()))
+}
+
+/// Return the default path for a cookie set by a page at the specified URL path,
+/// as described in RFC 6265 section 5.1.4.
+fn default_cookie_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(i) => &path[..i],
+    }
+}
+
+/// Parse the value of `document.cookie`, which has the form `name1=value1; name2=value2`.
+///
+/// A cookie without a `=` has an empty name, and is returned with that name.
+fn parse_document_cookie(cookies: &str) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for pair in cookies.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or(("", pair));
+        map.entry(name.trim().to_string()).or_insert_with(|| value.trim().to_string());
+    }
+    map
+}
+
+/// Return true if the value is truthy according to JavaScript semantics.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// List the files in the specified directory, excluding partial downloads.
+async fn completed_downloads(dir: &Path) -> WebDriverResult<Vec<PathBuf>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut files = Vec::new();
+    let mut partial = std::collections::HashSet::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("crdownload" | "part") => {
+                partial.insert(path.with_extension(""));
+            }
+            _ if entry.file_type().await?.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+    // Skip files that are still being written, alongside their partial download.
+    files.retain(|f| !partial.contains(f));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::RetryPolicy;
+    use crate::session::http::test_client::{test_handle, MockClient, Reply};
+    use crate::ElementRef;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct Args {
+        first: u32,
+        element: ElementRef,
+        last: &'static str,
+    }
+
+    #[test]
+    fn test_script_args() {
+        assert_eq!(script_args(serde_json::to_value(()).unwrap()), Vec::<Value>::new());
+        assert_eq!(script_args(serde_json::to_value(5).unwrap()), vec![json!(5)]);
+        assert_eq!(
+            script_args(serde_json::to_value((1, "two", [3])).unwrap()),
+            vec![json!(1), json!("two"), json!([3])]
+        );
+
+        let args = Args {
+            first: 1,
+            element: ElementRef::Element {
+                id: "abc".to_string(),
+            },
+            last: "end",
+        };
+        assert_eq!(
+            script_args(serde_json::to_value(args).unwrap()),
+            vec![json!(1), json!({"element-6066-11e4-a52e-4f735466cecf": "abc"}), json!("end")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_element() {
+        let client = Arc::new(MockClient::new().reply("/execute/sync", Reply::null()));
+        let elem = WebElement::from_reference(client.handle(), "abc");
+        client.handle().execute_with("arguments[0].click();", &elem).await.unwrap();
+        let config = WebDriverConfig::builder().legacy_element_key(true).build().unwrap();
+        let legacy = WebElement::from_reference(client.handle_with_config(config), "abc");
+        client.handle().execute_with("arguments[0].click();", &legacy).await.unwrap();
+        let shadow = ElementRef::ShadowElement {
+            id: "def".to_string(),
+        };
+        client.handle().execute_with("return arguments[0];", shadow).await.unwrap();
+
+        let args: Vec<_> =
+            client.bodies("/execute/sync").iter().map(|b| b["args"].clone()).collect();
+        assert_eq!(
+            args,
+            [
+                json!([{"element-6066-11e4-a52e-4f735466cecf": "abc"}]),
+                json!([{"ELEMENT": "abc"}]),
+                json!([{"shadow-6066-11e4-a52e-4f735466cecf": "def"}])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_args_map() {
+        let map = std::collections::BTreeMap::from([("b", 2), ("a", 1)]);
+        assert_eq!(script_args(serde_json::to_value(&map).unwrap()), vec![json!(1), json!(2)]);
+        assert_eq!(
+            script_args(serde_json::to_value((&map,)).unwrap()),
+            vec![json!({"a": 1, "b": 2})]
+        );
+
+        // An object with other keys as well as an element key is not an element reference.
+        let value = json!({"ELEMENT": "abc", "other": 1});
+        assert_eq!(script_args(value), vec![json!("abc"), json!(1)]);
+    }
+
+    fn flaky_client(failures: usize) -> Arc<MockClient> {
+        let failures = std::iter::repeat_n(Reply::HttpError("connection reset"), failures);
+        Arc::new(MockClient::new().sequence("", failures.chain([Reply::null()])))
+    }
+
+    fn flaky_handle(client: &Arc<MockClient>, max_retries: u32) -> Arc<SessionHandle> {
+        let config = WebDriverConfig::builder()
+            .retry_policy(RetryPolicy::new(max_retries, Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        client.handle_with_config(config)
+    }
+
+    #[tokio::test]
+    async fn test_retry_idempotent_command() {
+        let client = flaky_client(2);
+        let handle = flaky_handle(&client, 3);
+        handle.cmd(Command::GetTitle).await.unwrap();
+        assert_eq!(client.requests().len(), 3);
+
+        let client = flaky_client(5);
+        let handle = flaky_handle(&client, 2);
+        assert!(matches!(handle.cmd(Command::GetTitle).await, Err(WebDriverError::HttpError(_))));
+        assert_eq!(client.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_for_state_changing_command() {
+        let client = flaky_client(1);
+        let handle = flaky_handle(&client, 3);
+        let result = handle.cmd(Command::ElementClick("abc".into())).await;
+        assert!(matches!(result, Err(WebDriverError::HttpError(_))));
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_by_default() {
+        let client = flaky_client(1);
+        assert!(client.handle().cmd(Command::GetTitle).await.is_err());
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    fn status_client() -> Arc<MockClient> {
+        let client = MockClient::new()
+            .reply("/title", json!("Title"))
+            .reply("", Reply::error(404, "no such element", "gone"))
+            .header("x-request-id", "abc");
+        Arc::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_last_response_disabled_by_default() {
+        let handle = status_client().handle();
+        handle.cmd(Command::GetTitle).await.unwrap();
+        assert!(handle.last_response().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_last_response_recorded() {
+        let config = WebDriverConfig::builder().record_last_response(true).build().unwrap();
+        let handle = status_client().handle_with_config(config);
+        handle.cmd(Command::GetTitle).await.unwrap();
+        let response = handle.last_response().unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers["x-request-id"], "abc");
+        assert_eq!(response.body_lossy(), r#"{"value":"Title"}"#);
+
+        // Error responses are recorded too.
+        assert!(handle.cmd(Command::GetCurrentUrl).await.is_err());
+        let response = handle.last_response().unwrap();
+        assert_eq!(response.status, 404);
+        assert!(response.body_lossy().contains("no such element"));
+
+        // A cloned handle starts without a recorded response.
+        let clone = handle.clone_with_config(handle.config().clone());
+        assert!(clone.last_response().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_download() {
+        let dir = std::env::temp_dir().join(format!("thirtyfour-download-{}", std::process::id()));
+        let handle = test_handle();
+
+        let writer = {
+            let dir = dir.clone();
+            tokio::spawn(async move {
+                tokio::fs::create_dir_all(&dir).await.unwrap();
+                tokio::fs::write(dir.join("other.txt"), "x").await.unwrap();
+                let partial = dir.join("report.csv.crdownload");
+                tokio::fs::write(&partial, "a,b").await.unwrap();
+                sleep(Duration::from_millis(300)).await;
+                tokio::fs::rename(&partial, dir.join("report.csv")).await.unwrap();
+            })
+        };
+
+        let is_csv = |p: &Path| p.extension() == Some("csv".as_ref());
+        let path =
+            handle.wait_for_download(&dir, &[], is_csv, Duration::from_secs(5)).await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(path, dir.join("report.csv"));
+
+        // Files that were already there are not new downloads.
+        let before = handle.completed_downloads(&dir).await.unwrap();
+        assert_eq!(before.len(), 2);
+        let result =
+            handle.wait_for_download(&dir, &before, is_csv, Duration::from_millis(100)).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("report.csv")),
+            x => panic!("expected timeout error, got {x:?}"),
+        }
+
+        // Firefox creates the empty destination file next to the partial download.
+        let part = dir.join("data.json.part");
+        tokio::fs::write(dir.join("data.json"), "").await.unwrap();
+        tokio::fs::write(&part, "{}").await.unwrap();
+        let writer = {
+            let (dir, part) = (dir.clone(), part.clone());
+            tokio::spawn(async move {
+                sleep(Duration::from_millis(300)).await;
+                tokio::fs::rename(&part, dir.join("data.json")).await.unwrap();
+            })
+        };
+        let before = handle.completed_downloads(&dir).await.unwrap();
+        let is_json = |p: &Path| p.extension() == Some("json".as_ref());
+        let path = handle.wait_for_download(&dir, &before, is_json, Duration::from_secs(5)).await;
+        let path = path.unwrap();
+        assert!(!part.exists());
+        writer.await.unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "{}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rects_of_no_elements() {
+        // No request is sent for an empty list.
+        assert!(test_handle().rects_of(&[]).await.unwrap().is_empty());
+    }
+
+    /// Serves a page whose height grows by 100 after each scroll, up to `max_height`.
+    fn scroll_handle(max_height: u64) -> Arc<SessionHandle> {
+        let height = Mutex::new(1000);
+        let client = MockClient::new().route("/execute/sync", move |request| {
+            let mut height = height.lock();
+            match request.body["script"] == SCROLL_TO_BOTTOM {
+                true => {
+                    *height = (*height + 100).min(max_height);
+                    Value::Null
+                }
+                false => json!(*height),
+            }
+        });
+        Arc::new(client).handle()
+    }
+
+    #[tokio::test]
+    async fn test_scroll_to_bottom_until_stable() {
+        // Grows 3 times, then the 4th scroll shows the height has stopped growing.
+        let handle = scroll_handle(1300);
+        assert_eq!(handle.scroll_to_bottom_until_stable(10, Duration::ZERO).await.unwrap(), 4);
+
+        let handle = scroll_handle(u64::MAX);
+        assert_eq!(handle.scroll_to_bottom_until_stable(5, Duration::ZERO).await.unwrap(), 5);
+        assert_eq!(handle.scroll_to_bottom_until_stable(0, Duration::ZERO).await.unwrap(), 0);
+    }
+
+    /// Fails every script with a JavaScript error.
+    fn script_timeout_client() -> Arc<MockClient> {
+        let client = MockClient::new()
+            .reply("/execute/sync", Reply::error(500, "javascript error", "boom"))
+            .route("/timeouts", |request| match request.method {
+                http::Method::GET => json!({"script": 30000, "pageLoad": 60000, "implicit": 0}),
+                _ => Value::Null,
+            });
+        Arc::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_timeout_restores_on_error() {
+        let client = script_timeout_client();
+        let result = client
+            .handle()
+            .execute_with_timeout("while (true) {}", Vec::new(), Duration::from_secs(1))
+            .await;
+        assert!(matches!(result, Err(WebDriverError::JavaScript { .. })), "{result:?}");
+
+        assert_eq!(
+            client.paths(),
+            vec![
+                "/session/test/timeouts",
+                "/session/test/timeouts",
+                "/session/test/execute/sync",
+                "/session/test/timeouts"
+            ]
+        );
+        let requests = client.requests();
+        assert_eq!(requests[1].body, json!({"script": 1000}));
+        assert_eq!(requests[3].body, json!({"script": 30000}));
+    }
+
+    fn null_client() -> Arc<MockClient> {
+        Arc::new(MockClient::new().reply("", Reply::null()))
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_timeout_restores_null() {
+        let client = MockClient::new()
+            .reply("/execute/sync", json!(1))
+            .reply("/execute/async", Reply::Pending)
+            .route("/timeouts", |request| match request.method {
+                http::Method::GET => json!({"script": null, "pageLoad": 60000, "implicit": 0}),
+                _ => Value::Null,
+            });
+        let client = Arc::new(client);
+        let handle = client.handle();
+        let timeout = Duration::from_secs(1);
+        handle.execute_with_timeout("return 1;", Vec::new(), timeout).await.unwrap();
+        assert_eq!(
+            client.bodies("/timeouts"),
+            [Value::Null, json!({"script": 1000}), json!({"script": null})]
+        );
+
+        // The timeout is restored even if the script is abandoned.
+        let script = handle.execute_async_with_timeout("", Vec::new(), timeout);
+        assert!(tokio::time::timeout(Duration::from_millis(100), script).await.is_err());
+        for _ in 0..100 {
+            if client.count("/timeouts") == 6 {
+                break;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(client.bodies("/timeouts").last(), Some(&json!({"script": null})));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_long_timeout() {
+        // The script takes longer than the request timeout (plus its grace period), but
+        // finishes within the script timeout.
+        let client = MockClient::new()
+            .reply("/execute/sync", Reply::delayed(Duration::from_millis(1500), json!(1)))
+            .reply("/execute/async", Reply::delayed(Duration::from_millis(1500), json!(2)))
+            .route("/timeouts", |request| match request.method {
+                http::Method::GET => json!({"script": 30000, "pageLoad": 60000, "implicit": 0}),
+                _ => Value::Null,
+            });
+        let config =
+            WebDriverConfig::builder().request_timeout(Duration::from_millis(100)).build().unwrap();
+        let handle = Arc::new(client).handle_with_config(config);
+        let timeout = Duration::from_secs(5);
+        let ret = handle.execute_with_timeout("return 1;", Vec::new(), timeout).await.unwrap();
+        assert_eq!(ret.json(), &json!(1));
+        let ret = handle.execute_async_with_timeout("", Vec::new(), timeout).await.unwrap();
+        assert_eq!(ret.json(), &json!(2));
+
+        // The previous script timeout of 30 seconds is restored, and still extends the
+        // request timeout for a plain script.
+        let ret = handle.execute("return 1;", Vec::new()).await.unwrap();
+        assert_eq!(ret.json(), &json!(1));
+    }
+
+    #[test]
+    fn test_session_timeouts_after_new_session() {
+        let mut capabilities = crate::Capabilities::new();
+        capabilities.insert("timeouts".to_string(), json!({"script": null, "pageLoad": 1000}));
+        let handle = SessionHandle::new(
+            Arc::new(MockClient::new()),
+            "http://localhost:4444",
+            SessionId::from("test"),
+        )
+        .unwrap()
+        .with_capabilities(capabilities);
+        let timeouts = *handle.timeouts.lock();
+        assert_eq!(timeouts.script, Some(Duration::from_secs(60)));
+        assert_eq!(timeouts.page_load, Some(Duration::from_secs(60)));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_follows_session_timeouts() {
+        let delayed = || Reply::delayed(Duration::from_millis(1500), Value::Null);
+        let client = MockClient::new()
+            .reply("/url", delayed())
+            .reply("/title", delayed())
+            .reply("/execute/sync", delayed())
+            .reply("/timeouts", Value::Null);
+        let config =
+            WebDriverConfig::builder().request_timeout(Duration::from_millis(100)).build().unwrap();
+        let handle = Arc::new(client).handle_with_config(config);
+        handle.set_page_load_timeout(Duration::from_secs(5)).await.unwrap();
+        handle.goto("https://example.com").await.unwrap();
+        handle.cmd(SetScriptTimeout(None)).await.unwrap();
+        handle.execute("return 1;", Vec::new()).await.unwrap();
+
+        // Commands that do not wait for scripts or page loads keep the request timeout.
+        let result = handle.title().await;
+        assert!(matches!(result, Err(WebDriverError::Timeout(_))), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_set_user_agent() {
+        let client = null_client();
+        client.browser_handle("chrome").set_user_agent("custom/1.0").await.unwrap();
+        client.handle().set_user_agent("custom/2.0").await.unwrap();
+        assert_eq!(
+            client.bodies(""),
+            [
+                json!({"cmd": "Network.setUserAgentOverride", "params": {"userAgent": "custom/1.0"}}),
+                json!({"cmd": "Network.setUserAgentOverride", "params": {"userAgent": "custom/2.0"}})
+            ]
+        );
+
+        for name in ["firefox", "Safari"] {
+            let result = client.browser_handle(name).set_user_agent("custom/3.0").await;
+            assert!(matches!(result, Err(WebDriverError::UnsupportedOperation(_))), "{name}");
+        }
+        assert_eq!(client.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_window_rect() {
+        // Return the rect set by `POST /window/rect` from `GET /window/rect`.
+        let rect = Mutex::new(Value::Null);
+        let client = Arc::new(MockClient::new().route("/window/rect", move |request| {
+            let mut rect = rect.lock();
+            if request.method == http::Method::POST {
+                *rect = request.body.clone();
+            }
+            rect.clone()
+        }));
+        let handle = client.handle();
+        handle.set_window_rect(20, 30, 800, 600).await.unwrap();
+        let rect = handle.get_window_rect().await.unwrap();
+        assert_eq!(rect, WindowRect::new(20, 30, 800, 600));
+        let requests: Vec<_> =
+            client.requests().iter().map(|r| (r.method.clone(), r.path.clone())).collect();
+        assert_eq!(
+            requests,
+            [
+                (http::Method::POST, "/session/test/window/rect".to_string()),
+                (http::Method::GET, "/session/test/window/rect".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_window_rect_fallback() {
+        // Commands that change the window reply with `reply` instead of the rect.
+        for reply in [Value::Null, json!({})] {
+            let replied = reply.clone();
+            let client = MockClient::new()
+                .route("/window/rect", move |request| match request.method {
+                    http::Method::GET => json!({"x": 0, "y": 0, "width": 1920, "height": 1080}),
+                    _ => replied.clone(),
+                })
+                .reply("", reply);
+            let client = Arc::new(client);
+            let handle = client.handle();
+            let expected = WindowRect::new(0, 0, 1920, 1080);
+            assert_eq!(handle.maximize_window().await.unwrap(), expected);
+            assert_eq!(handle.minimize_window().await.unwrap(), expected);
+            assert_eq!(handle.fullscreen_window().await.unwrap(), expected);
+            assert_eq!(handle.set_window_rect(0, 0, 1920, 1080).await.unwrap(), expected);
+            assert_eq!(client.count("/window/rect"), 5);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_timezone() {
+        // Scripts treat arguments starting with an uppercase letter as valid time zones.
+        let client = MockClient::new()
+            .route("/execute/sync", |request| {
+                let arg = request.body["args"][0].as_str().unwrap_or_default();
+                json!(arg.starts_with(|c: char| c.is_ascii_uppercase()))
+            })
+            .reply("/goog/cdp/execute", Reply::null());
+        let client = Arc::new(client);
+
+        let chrome = client.browser_handle("chrome");
+        chrome.set_timezone("Asia/Tokyo").await.unwrap();
+        chrome.set_timezone("").await.unwrap();
+        let result = chrome.set_timezone("not/a-zone").await;
+        assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+        let cdp = "/goog/cdp/execute";
+        assert_eq!(
+            client.bodies(cdp),
+            [
+                json!({"cmd": "Emulation.setTimezoneOverride", "params": {"timezoneId": "Asia/Tokyo"}}),
+                json!({"cmd": "Emulation.setTimezoneOverride", "params": {"timezoneId": ""}})
+            ]
+        );
+
+        for name in ["firefox", "Safari"] {
+            let result = client.browser_handle(name).set_timezone("Asia/Tokyo").await;
+            assert!(matches!(result, Err(WebDriverError::UnsupportedOperation(_))), "{name}");
+        }
+        assert_eq!(client.count(cdp), 2);
+    }
+
+    /// Responds to async scripts with `result`, and to everything else with null.
+    fn clipboard_client(result: Value) -> Arc<MockClient> {
+        let client = MockClient::new().reply("/execute/async", result).reply("", Reply::null());
+        Arc::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_clipboard() {
+        let client = clipboard_client(json!({ "text": "copied" }));
+        assert_eq!(client.handle().read_clipboard().await.unwrap(), "copied");
+        {
+            let requests = client.requests();
+            assert_eq!(requests[0].body["params"]["permission"]["name"], "clipboard-read");
+            assert_eq!(requests[1].body["script"], READ_CLIPBOARD);
+        }
+
+        let client = clipboard_client(json!({}));
+        client.handle().write_clipboard("pasted").await.unwrap();
+        {
+            let requests = client.requests();
+            assert_eq!(requests[0].body["params"]["permission"]["name"], "clipboard-write");
+            assert_eq!(requests[1].body["args"], json!(["pasted"]));
+        }
+
+        let client = clipboard_client(json!({ "error": "NotAllowedError" }));
+        let result = client.handle().read_clipboard().await;
+        assert!(matches!(result, Err(WebDriverError::UnsupportedOperation(_))), "{result:?}");
+
+        // The Clipboard API is still tried if the permission cannot be granted.
+        let client = MockClient::new()
+            .reply("/execute/async", json!({ "text": "copied" }))
+            .reply("", Reply::error(404, "unknown command", "no permissions"));
+        assert_eq!(Arc::new(client).handle().read_clipboard().await.unwrap(), "copied");
+
+        // But other errors are returned.
+        let client = MockClient::new()
+            .reply("/execute/async", json!({ "text": "copied" }))
+            .reply("", Reply::error(404, "no such window", "closed"));
+        let client = Arc::new(client);
+        let result = client.handle().read_clipboard().await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchWindow(_))), "{result:?}");
+        assert_eq!(client.count("/execute/async"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_navigation_timing_missing() {
+        let result = null_client().handle().navigation_timing().await;
+        assert!(matches!(result, Err(WebDriverError::NotFound(..))));
+    }
+
+    /// A client where each request for the current URL returns the next of `urls`.
+    fn goto_client(urls: &'static [&'static str]) -> Arc<MockClient> {
+        let gets = std::sync::atomic::AtomicUsize::new(0);
+        let client = MockClient::new()
+            .route("/url", move |request| match request.method {
+                http::Method::GET => {
+                    let i = gets.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    json!(urls[i.min(urls.len() - 1)])
+                }
+                _ => Value::Null,
+            })
+            .reply("", Reply::null());
+        Arc::new(client)
+    }
+
+    fn clearing_handle(client: &Arc<MockClient>) -> Arc<SessionHandle> {
+        let config = WebDriverConfig::builder().clear_storage_on_origin_change(true).build();
+        client.handle_with_config(config.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_goto_clears_storage_on_origin_change() {
+        let client = goto_client(&["http://a.test/", "http://b.test/page"]);
+        clearing_handle(&client).goto("http://b.test/page").await.unwrap();
+        let requests: Vec<_> = client
+            .requests()
+            .iter()
+            .map(|r| (r.method.clone(), r.path.trim_start_matches("/session/test").to_string()))
+            .collect();
+        // The storage is cleared after navigating, because only the current page's
+        // storage can be cleared.
+        assert_eq!(
+            requests,
+            vec![
+                (http::Method::GET, "/url".to_string()),
+                (http::Method::POST, "/url".to_string()),
+                (http::Method::GET, "/url".to_string()),
+                (http::Method::DELETE, "/cookie".to_string()),
+                (http::Method::POST, "/execute/sync".to_string()),
+            ]
+        );
+        assert_eq!(client.bodies("/url")[1], json!({ "url": "http://b.test/page" }));
+
+        // Changing the path or query alone does not clear anything.
+        let client = goto_client(&["http://a.test/login", "http://a.test/home?x=1"]);
+        clearing_handle(&client).goto("http://a.test/home?x=1").await.unwrap();
+        assert_eq!(client.count("/cookie"), 0);
+        assert_eq!(client.count("/execute/sync"), 0);
+
+        // The current URL is not checked unless the option is enabled.
+        let client = goto_client(&["http://a.test/"]);
+        client.handle().goto("http://b.test/").await.unwrap();
+        assert_eq!(client.paths(), vec!["/session/test/url"]);
+    }
+
+    #[test]
+    fn test_default_cookie_path() {
+        assert_eq!(default_cookie_path(""), "/");
+        assert_eq!(default_cookie_path("/"), "/");
+        assert_eq!(default_cookie_path("/index.html"), "/");
+        assert_eq!(default_cookie_path("/wiki/Rust"), "/wiki");
+        assert_eq!(default_cookie_path("/a/b/"), "/a/b");
+    }
+
+    #[tokio::test]
+    async fn test_execute_file() {
+        // Respond to scripts with the script itself.
+        let client = MockClient::new().route("/execute/sync", |r| r.body["script"].clone());
+        let handle = Arc::new(client).handle();
+        let path = std::env::temp_dir().join(format!("thirtyfour-{}.js", std::process::id()));
+        tokio::fs::write(&path, "return 42;\n").await.unwrap();
+        let ret = handle.execute_file(&path, Vec::new()).await;
+        tokio::fs::remove_file(&path).await.unwrap();
+        let script: String = ret.unwrap().convert().unwrap();
+        assert_eq!(script, "return 42;\n");
+
+        let result = handle.execute_file(&path, Vec::new()).await;
+        assert!(matches!(result, Err(WebDriverError::IoError(_))));
+    }
+
+    #[test]
+    fn test_parse_document_cookie() {
+        assert!(parse_document_cookie("").is_empty());
+        let cookies = parse_document_cookie("a=1; token=abc==; a=2;  empty=; flag");
+        assert_eq!(cookies.len(), 4);
+        assert_eq!(cookies["a"], "1");
+        assert_eq!(cookies["token"], "abc==");
+        assert_eq!(cookies["empty"], "");
+        assert_eq!(cookies[""], "flag");
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        for value in [json!(true), json!(1), json!(-0.5), json!("0"), json!([]), json!({})] {
+            assert!(is_truthy(&value), "{value}");
+        }
+        for value in [json!(null), json!(false), json!(0), json!(0.0), json!("")] {
+            assert!(!is_truthy(&value), "{value}");
+        }
+    }
+
+    /// Returns each of the values in turn from every request, repeating the last one.
+    fn sequence_handle(values: Vec<Value>) -> Arc<SessionHandle> {
+        Arc::new(MockClient::new().sequence("", values)).handle()
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_script() {
+        let handle = sequence_handle(vec![json!(null), json!(""), json!(0), json!("ready")]);
+        let value = handle.wait_for_script("return x;", Duration::from_secs(5)).await.unwrap();
+        assert_eq!(value, json!("ready"));
+
+        let handle = sequence_handle(vec![json!(false)]);
+        match handle.wait_for_script("return x;", Duration::ZERO).await {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("last value: Bool(false)")),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_network_idle() {
+        let client = MockClient::new()
+            .reply("/se/log", Reply::error(404, "unknown command", "no such log"))
+            .sequence(
+                "",
+                vec![
+                    json!({ "pending": 2, "idleMs": 0 }),
+                    json!({ "pending": 0, "idleMs": 100 }),
+                    json!({ "pending": 1, "idleMs": 0 }),
+                    json!({ "pending": 0, "idleMs": 600 }),
+                ],
+            );
+        let client = Arc::new(client);
+        let handle = client.handle();
+        handle
+            .wait_for_network_idle(Duration::from_millis(500), Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        // The performance log is only tried once.
+        assert_eq!(client.count("/se/log"), 1);
+
+        let client = MockClient::new()
+            .reply("/se/log", Reply::error(404, "unknown command", "no such log"))
+            .reply("", json!({ "pending": 1, "idleMs": 5000 }));
+        let handle = Arc::new(client).handle();
+        match handle.wait_for_network_idle(Duration::ZERO, Duration::ZERO).await {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("pending: 1")),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_network_idle_performance_log() {
+        let event = |method: &str, id: &str| {
+            let message = json!({ "message": { "method": method, "params": { "requestId": id } } });
+            json!({ "level": "INFO", "message": message.to_string(), "timestamp": 0 })
+        };
+        // Request 1 was already in flight before the first call.
+        let client = Arc::new(MockClient::new().sequence(
+            "/se/log",
+            vec![
+                json!([event("Network.requestWillBeSent", "1")]),
+                json!([
+                    event("Network.requestWillBeSent", "2"),
+                    event("Network.dataReceived", "1"),
+                    event("Network.loadingFailed", "2"),
+                ]),
+                json!([event("Network.loadingFinished", "1")]),
+                json!([]),
+            ],
+        ));
+        let handle = client.handle();
+        handle
+            .wait_for_network_idle(Duration::from_millis(100), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(client.count("/se/log") >= 4);
+        assert_eq!(client.bodies("/se/log")[0], json!({ "type": "performance" }));
+        assert!(client.paths().iter().all(|p| p.ends_with("/se/log")));
+
+        let client =
+            MockClient::new().reply("/se/log", json!([event("Network.requestWillBeSent", "1")]));
+        let handle = Arc::new(client).handle();
+        match handle.wait_for_network_idle(Duration::ZERO, Duration::ZERO).await {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("pending: 1")),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_new_window() {
+        let before = [WindowHandle::from("main"), WindowHandle::from("other")];
+        let handle = sequence_handle(vec![
+            json!(["main"]),
+            json!(["main", "other"]),
+            json!(["main", "popup", "other"]),
+        ]);
+        let popup = handle.wait_for_new_window(&before, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(popup, WindowHandle::from("popup"));
+
+        let handle = sequence_handle(vec![json!(["main"])]);
+        match handle.wait_for_new_window(&before, Duration::ZERO).await {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("main"), "{msg}"),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+    }
+
+    fn tabs_client(cdp: bool) -> Arc<MockClient> {
+        let targets = json!([
+            {"targetId": "worker", "type": "service_worker", "title": "", "url": "sw.js"},
+            {"targetId": "tab-b", "type": "page", "title": "B", "url": "http://b/"},
+            {"targetId": "tab-a", "type": "page", "title": "A", "url": "http://a/"},
+        ]);
+        let client = MockClient::new().reply("/window/handles", json!(["tab-a", "tab-b"]));
+        let client = match cdp {
+            true => client.reply("", json!({ "targetInfos": targets })),
+            false => client.reply("", Reply::error(404, "unknown command", "not found")),
+        };
+        Arc::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_tabs() {
+        let tabs = tabs_client(true).handle().tabs().await.unwrap();
+        assert_eq!(
+            tabs,
+            vec![
+                TabInfo {
+                    handle: "tab-a".into(),
+                    url: Some("http://a/".to_string()),
+                    title: Some("A".to_string()),
+                    target_type: Some("page".to_string()),
+                },
+                TabInfo {
+                    handle: "tab-b".into(),
+                    url: Some("http://b/".to_string()),
+                    title: Some("B".to_string()),
+                    target_type: Some("page".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tabs_without_cdp() {
+        let tabs = tabs_client(false).handle().tabs().await.unwrap();
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(tabs[1].handle, WindowHandle::from("tab-b"));
+        assert!(tabs.iter().all(|t| t.url.is_none() && t.target_type.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        assert_eq!(
+            WebDriverConfig::default().request_timeout,
+            Some(crate::common::config::DEFAULT_REQUEST_TIMEOUT)
+        );
+
+        let config =
+            WebDriverConfig::builder().request_timeout(Duration::from_millis(100)).build().unwrap();
+        // Never respond, ignoring the `RequestTimeout` extension.
+        let client = Arc::new(MockClient::new().reply("", Reply::Pending));
+        let handle = client.handle_with_config(config);
+        let start = std::time::Instant::now();
+        let result = handle.cmd(Command::GetTitle).await;
+        assert!(matches!(result, Err(WebDriverError::Timeout(_))), "{result:?}");
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        // The timeout can be overridden for a single command.
+        let result = tokio::time::timeout(
+            Duration::from_millis(500),
+            handle.cmd_with_timeout(Command::GetTitle, Duration::from_secs(60)),
+        )
+        .await;
+        assert!(result.is_err(), "command should still be waiting");
+        let result = handle.cmd_with_timeout(Command::GetTitle, Duration::ZERO).await;
+        assert!(matches!(result, Err(WebDriverError::Timeout(_))), "{result:?}");
+    }
+}
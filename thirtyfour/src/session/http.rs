@@ -4,7 +4,7 @@ use base64::Engine;
 use bytes::Bytes;
 use http::{
     header::{ACCEPT, AUTHORIZATION, CONNECTION, CONTENT_TYPE, USER_AGENT},
-    HeaderValue, Request, Response,
+    HeaderMap, HeaderValue, Request, Response,
 };
 use serde_json::Value;
 use url::Url;
@@ -39,9 +39,114 @@ impl<'a, T: Into<Option<&'a Value>>> From<T> for Body<'a> {
 #[async_trait::async_trait]
 pub trait HttpClient: Send + Sync + 'static {
     /// Send an HTTP request and return the response.
+    ///
+    /// Requests sent by thirtyfour include a [`MaxResponseSize`] in their extensions.
+    /// Implementations should stop reading the response body and return
+    /// `WebDriverError::ResponseTooLarge` once it exceeds that size.
+    ///
+    /// If `WebDriverConfig::request_timeout` is set (the default), requests also include a
+    /// [`RequestTimeout`] in their extensions, which implementations should apply as the
+    /// timeout for this request. A request that has not completed shortly after the
+    /// timeout is dropped and fails with `WebDriverError::Timeout`.
     async fn send(&self, request: Request<Body<'_>>) -> WebDriverResult<Response<Bytes>>;
 }
 
+/// The maximum size of a response body, in bytes.
+///
+/// This is inserted into the extensions of every request passed to `HttpClient::send()`,
+/// from `WebDriverConfig::max_response_size`. Responses are also checked after they are
+/// received, so a client that ignores this still cannot return a larger body, but it may
+/// buffer the whole body first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxResponseSize(pub usize);
+
+impl MaxResponseSize {
+    /// Return an error if `size` exceeds the maximum.
+    pub fn check(self, size: usize) -> WebDriverResult<()> {
+        if size > self.0 {
+            Err(WebDriverError::ResponseTooLarge(self.0))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The overall timeout for a single request, including the time to connect.
+///
+/// This is inserted into the extensions of each request passed to `HttpClient::send()`
+/// when `WebDriverConfig::request_timeout` is set. The `reqwest` client maps it to
+/// `RequestBuilder::timeout()`, which overrides the client-wide timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeout(pub std::time::Duration);
+
+/// How long after the request timeout to wait for the client before dropping the
+/// request, so that a client which applies the timeout itself reports its own error.
+const REQUEST_TIMEOUT_GRACE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Trait for inspecting or modifying the requests sent to the WebDriver server, and the
+/// responses received back.
+///
+/// This is useful for adding custom headers (e.g. for a cloud grid provider),
+/// or for logging. See `WebDriverBuilder::interceptor()`.
+///
+/// # Example:
+/// ```
+/// use http::{HeaderValue, Request};
+/// use thirtyfour::session::http::{Body, RequestInterceptor};
+///
+/// struct ApiKey(&'static str);
+///
+/// impl RequestInterceptor for ApiKey {
+///     fn on_request(&self, request: &mut Request<Body<'_>>) {
+///         request.headers_mut().insert("x-api-key", HeaderValue::from_static(self.0));
+///     }
+/// }
+/// ```
+pub trait RequestInterceptor: Send + Sync + 'static {
+    /// Called before each request is sent.
+    fn on_request(&self, request: &mut Request<Body<'_>>) {
+        let _ = request;
+    }
+
+    /// Called after each response is received, before the status is checked.
+    fn on_response(&self, response: &Response<Bytes>) {
+        let _ = response;
+    }
+}
+
+/// HTTP client that runs the specified interceptors around each request.
+pub(crate) struct InterceptingClient {
+    inner: Arc<dyn HttpClient>,
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+}
+
+impl InterceptingClient {
+    /// Create a new `InterceptingClient` that sends requests using the specified client.
+    pub(crate) fn new(
+        inner: Arc<dyn HttpClient>,
+        interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    ) -> Self {
+        Self {
+            inner,
+            interceptors,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for InterceptingClient {
+    async fn send(&self, mut request: Request<Body<'_>>) -> WebDriverResult<Response<Bytes>> {
+        for interceptor in &self.interceptors {
+            interceptor.on_request(&mut request);
+        }
+        let response = self.inner.send(request).await?;
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&response);
+        }
+        Ok(response)
+    }
+}
+
 #[cfg(feature = "reqwest")]
 #[async_trait::async_trait]
 impl HttpClient for reqwest::Client {
@@ -49,6 +154,9 @@ impl HttpClient for reqwest::Client {
         let (parts, body) = request.into_parts();
 
         let mut req = self.request(parts.method, parts.uri.to_string());
+        if let Some(&RequestTimeout(timeout)) = parts.extensions.get::<RequestTimeout>() {
+            req = req.timeout(timeout);
+        }
         for (key, value) in parts.headers.into_iter() {
             let key = match key {
                 Some(x) => x,
@@ -63,7 +171,7 @@ impl HttpClient for reqwest::Client {
             }
         }
 
-        let resp = req.send().await?;
+        let mut resp = req.send().await?;
         let status = resp.status();
         let mut builder = Response::builder();
 
@@ -72,7 +180,20 @@ impl HttpClient for reqwest::Client {
             builder = builder.header(key.clone(), value.clone());
         }
 
-        let body = resp.bytes().await?;
+        let body = match parts.extensions.get::<MaxResponseSize>() {
+            Some(&limit) => {
+                let expected = resp.content_length().unwrap_or_default();
+                let expected = usize::try_from(expected).unwrap_or(usize::MAX);
+                limit.check(expected)?;
+                let mut body = bytes::BytesMut::with_capacity(expected);
+                while let Some(chunk) = resp.chunk().await? {
+                    limit.check(body.len() + chunk.len())?;
+                    body.extend_from_slice(&chunk);
+                }
+                body.freeze()
+            }
+            None => resp.bytes().await?,
+        };
         let body_str = String::from_utf8_lossy(&body).into_owned();
         let resp = builder
             .body(body)
@@ -82,8 +203,22 @@ impl HttpClient for reqwest::Client {
 }
 
 #[cfg(feature = "reqwest")]
-pub(crate) fn create_reqwest_client(timeout: std::time::Duration) -> reqwest::Client {
-    reqwest::Client::builder().timeout(timeout).build().expect("Failed to create reqwest client")
+pub(crate) fn create_reqwest_client(
+    timeout: std::time::Duration,
+    connect_timeout: Option<std::time::Duration>,
+    unix_socket: Option<&str>,
+) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder().timeout(timeout);
+    if let Some(connect_timeout) = connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    #[cfg(all(feature = "unix-socket", unix))]
+    if let Some(path) = unix_socket {
+        builder = builder.unix_socket(path);
+    }
+    #[cfg(not(all(feature = "unix-socket", unix)))]
+    let _ = unix_socket;
+    builder.build().expect("Failed to create reqwest client")
 }
 
 // Null client so that we can compile without the `reqwest` feature.
@@ -105,16 +240,286 @@ pub(crate) mod null_client {
     }
 }
 
-#[tracing::instrument(skip_all)]
+// Mock client and session handles for unit tests.
+#[cfg(test)]
+pub(crate) mod test_client {
+    use std::collections::VecDeque;
+
+    use parking_lot::{Mutex, MutexGuard};
+
+    use super::*;
+    use crate::SessionId;
+
+    /// A request received by a `MockClient`.
+    #[derive(Debug, Clone)]
+    pub(crate) struct MockRequest {
+        pub method: http::Method,
+        pub uri: http::Uri,
+        pub path: String,
+        pub headers: HeaderMap,
+        pub extensions: http::Extensions,
+        /// The JSON body, or null if the request had no body.
+        pub body: Value,
+    }
+
+    /// The response to a request received by a `MockClient`.
+    #[derive(Debug, Clone)]
+    pub(crate) enum Reply {
+        /// A successful response with the specified value.
+        Value(Value),
+        /// A WebDriver error response with the specified status, error code and message.
+        Error(u16, &'static str, String),
+        /// Fail to send the request, with `WebDriverError::HttpError`.
+        HttpError(&'static str),
+        /// Respond with the specified reply after a delay.
+        Delayed(std::time::Duration, Box<Reply>),
+        /// Never respond.
+        Pending,
+    }
+
+    impl Reply {
+        /// A successful response with a null value.
+        pub(crate) fn null() -> Self {
+            Reply::Value(Value::Null)
+        }
+
+        /// A WebDriver error response with the specified status, error code and message.
+        pub(crate) fn error(status: u16, error: &'static str, message: impl Into<String>) -> Self {
+            Reply::Error(status, error, message.into())
+        }
+
+        /// Respond with `reply` after the specified delay.
+        pub(crate) fn delayed(delay: std::time::Duration, reply: impl Into<Reply>) -> Self {
+            Reply::Delayed(delay, Box::new(reply.into()))
+        }
+    }
+
+    impl From<Value> for Reply {
+        fn from(value: Value) -> Self {
+            Reply::Value(value)
+        }
+    }
+
+    type Handler = Box<dyn Fn(&MockRequest) -> Reply + Send + Sync>;
+
+    /// An `HttpClient` that responds to each request from the first route whose path
+    /// suffix matches the request path, recording every request it receives.
+    ///
+    /// Requests that match no route fail with `WebDriverError::RequestFailed`.
+    #[derive(Default)]
+    pub(crate) struct MockClient {
+        routes: Vec<(&'static str, Handler)>,
+        headers: Vec<(&'static str, &'static str)>,
+        requests: Mutex<Vec<MockRequest>>,
+    }
+
+    impl MockClient {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        /// Respond to requests whose path ends with `suffix` using the specified handler.
+        ///
+        /// An empty suffix matches every request.
+        pub(crate) fn route<F, R>(mut self, suffix: &'static str, handler: F) -> Self
+        where
+            F: Fn(&MockRequest) -> R + Send + Sync + 'static,
+            R: Into<Reply>,
+        {
+            self.routes.push((suffix, Box::new(move |request| handler(request).into())));
+            self
+        }
+
+        /// Respond to requests whose path ends with `suffix` with the specified reply.
+        pub(crate) fn reply(self, suffix: &'static str, reply: impl Into<Reply>) -> Self {
+            let reply = reply.into();
+            self.route(suffix, move |_| reply.clone())
+        }
+
+        /// Respond to requests whose path ends with `suffix` with each of the replies in
+        /// turn, repeating the last one.
+        pub(crate) fn sequence<R: Into<Reply>>(
+            self,
+            suffix: &'static str,
+            replies: impl IntoIterator<Item = R>,
+        ) -> Self {
+            let replies: VecDeque<Reply> = replies.into_iter().map(Into::into).collect();
+            assert!(!replies.is_empty(), "a sequence needs at least one reply");
+            let replies = Mutex::new(replies);
+            self.route(suffix, move |_| {
+                let mut replies = replies.lock();
+                match replies.len() {
+                    1 => replies[0].clone(),
+                    _ => replies.pop_front().unwrap(),
+                }
+            })
+        }
+
+        /// Add the specified header to every response.
+        pub(crate) fn header(mut self, name: &'static str, value: &'static str) -> Self {
+            self.headers.push((name, value));
+            self
+        }
+
+        /// The requests received so far, in order.
+        pub(crate) fn requests(&self) -> MutexGuard<'_, Vec<MockRequest>> {
+            self.requests.lock()
+        }
+
+        /// The paths of the requests received so far, in order.
+        pub(crate) fn paths(&self) -> Vec<String> {
+            self.requests.lock().iter().map(|r| r.path.clone()).collect()
+        }
+
+        /// The bodies of the requests received so far whose path ends with `suffix`.
+        pub(crate) fn bodies(&self, suffix: &str) -> Vec<Value> {
+            let requests = self.requests.lock();
+            requests.iter().filter(|r| r.path.ends_with(suffix)).map(|r| r.body.clone()).collect()
+        }
+
+        /// The number of requests received so far whose path ends with `suffix`.
+        pub(crate) fn count(&self, suffix: &str) -> usize {
+            self.requests.lock().iter().filter(|r| r.path.ends_with(suffix)).count()
+        }
+
+        /// Create a session handle using this client.
+        pub(crate) fn handle(self: &Arc<Self>) -> Arc<SessionHandle> {
+            self.handle_with_config(WebDriverConfig::default())
+        }
+
+        /// Create a session handle using this client and the specified config.
+        pub(crate) fn handle_with_config(
+            self: &Arc<Self>,
+            config: WebDriverConfig,
+        ) -> Arc<SessionHandle> {
+            let handle = SessionHandle::new_with_config(
+                self.clone(),
+                "http://localhost:4444",
+                SessionId::from("test"),
+                config,
+            );
+            Arc::new(handle.unwrap())
+        }
+
+        /// Create a session handle using this client, for a session with the specified
+        /// `browserName` capability.
+        pub(crate) fn browser_handle(self: &Arc<Self>, browser_name: &str) -> Arc<SessionHandle> {
+            let mut capabilities = crate::Capabilities::new();
+            capabilities.insert("browserName".to_string(), Value::from(browser_name));
+            let handle =
+                SessionHandle::new(self.clone(), "http://localhost:4444", SessionId::from("test"));
+            Arc::new(handle.unwrap().with_capabilities(capabilities))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockClient {
+        async fn send(&self, request: Request<Body<'_>>) -> WebDriverResult<Response<Bytes>> {
+            let request = MockRequest {
+                method: request.method().clone(),
+                uri: request.uri().clone(),
+                path: request.uri().path().to_string(),
+                headers: request.headers().clone(),
+                extensions: request.extensions().clone(),
+                body: match request.body() {
+                    Body::Json(body) => (*body).clone(),
+                    Body::Empty => Value::Null,
+                },
+            };
+            self.requests.lock().push(request.clone());
+            let handler = self.routes.iter().find(|(suffix, _)| request.path.ends_with(suffix));
+            let Some((_, handler)) = handler else {
+                return Err(WebDriverError::RequestFailed(format!(
+                    "unexpected request: {} {}",
+                    request.method, request.path
+                )));
+            };
+            let mut reply = handler(&request);
+            while let Reply::Delayed(delay, inner) = reply {
+                tokio::time::sleep(delay).await;
+                reply = *inner;
+            }
+            let (status, body) = match reply {
+                Reply::Value(value) => (200, serde_json::json!({ "value": value })),
+                Reply::Error(status, error, message) => {
+                    (status, serde_json::json!({ "value": { "error": error, "message": message } }))
+                }
+                Reply::HttpError(message) => {
+                    return Err(WebDriverError::HttpError(message.to_string()))
+                }
+                Reply::Delayed(..) => unreachable!("delays are applied above"),
+                Reply::Pending => futures::future::pending().await,
+            };
+            let mut response = Response::builder().status(status);
+            for (name, value) in &self.headers {
+                response = response.header(*name, *value);
+            }
+            Ok(response.body(Bytes::from(body.to_string())).unwrap())
+        }
+    }
+
+    /// Allows a shared client to be passed to `WebDriverBuilder::http_client()`.
+    #[async_trait::async_trait]
+    impl HttpClient for Arc<MockClient> {
+        async fn send(&self, request: Request<Body<'_>>) -> WebDriverResult<Response<Bytes>> {
+            self.as_ref().send(request).await
+        }
+    }
+
+    /// A session handle whose client fails every request with
+    /// `WebDriverError::RequestFailed`, for tests that never send any commands.
+    pub(crate) fn test_handle() -> Arc<SessionHandle> {
+        Arc::new(MockClient::new()).handle()
+    }
+}
+
 pub(crate) async fn run_webdriver_cmd(
     client: &(impl HttpClient + ?Sized),
     request_data: &RequestData,
     server_url: &Url,
     config: &WebDriverConfig,
 ) -> WebDriverResult<CmdResponse> {
+    let (status, body) = run_webdriver_cmd_raw(client, request_data, server_url, config).await?;
+    parse_response_body(status, body)
+}
+
+/// Run the command and return the status and raw response body, without parsing it.
+///
+/// Error responses are still parsed into the appropriate `WebDriverError`.
+pub(crate) async fn run_webdriver_cmd_raw(
+    client: &(impl HttpClient + ?Sized),
+    request_data: &RequestData,
+    server_url: &Url,
+    config: &WebDriverConfig,
+) -> WebDriverResult<(u16, Bytes)> {
+    let response = send_webdriver_request(client, request_data, server_url, config).await?;
+    check_response_status(response)
+}
+
+/// Make sure the path of the server url ends with a slash.
+///
+/// Command paths are joined onto the server url, so without this the last segment of a
+/// path prefix such as "/wd/hub" would be replaced rather than extended.
+pub(crate) fn normalize_server_url(mut server_url: Url) -> Url {
+    if !server_url.path().ends_with('/') {
+        let path = format!("{}/", server_url.path());
+        server_url.set_path(&path);
+    }
+    server_url
+}
+
+/// Send the request and return the HTTP response, regardless of its status.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn send_webdriver_request(
+    client: &(impl HttpClient + ?Sized),
+    request_data: &RequestData,
+    server_url: &Url,
+    config: &WebDriverConfig,
+) -> WebDriverResult<Response<Bytes>> {
     tracing::debug!("webdriver request: {request_data}");
+    // Join the command path relative to the server url, to keep any path prefix.
     let uri = server_url
-        .join(&request_data.uri)
+        .join(request_data.uri.trim_start_matches('/'))
         .map_err(|e| WebDriverError::ParseError(format!("invalid url: {e}")))?;
     let mut builder = http::Request::builder()
         .method(request_data.method.clone())
@@ -145,22 +550,83 @@ pub(crate) async fn run_webdriver_cmd(
         None => Body::Empty,
     };
 
-    let request = builder
+    let mut request = builder
         .body(body)
         .map_err(|e| WebDriverError::RequestFailed(format!("invalid request body: {e}")))?;
-    let response = client.send(request).await?;
+    let max_size = MaxResponseSize(config.max_response_size);
+    request.extensions_mut().insert(max_size);
+    let response = match config.request_timeout {
+        Some(timeout) => {
+            request.extensions_mut().insert(RequestTimeout(timeout));
+            let deadline = timeout.saturating_add(REQUEST_TIMEOUT_GRACE);
+            tokio::time::timeout(deadline, client.send(request)).await.map_err(|_| {
+                WebDriverError::Timeout(format!(
+                    "no response from the webdriver server within {timeout:?}: {} {}",
+                    request_data.method, request_data.uri
+                ))
+            })??
+        }
+        None => client.send(request).await?,
+    };
+    max_size.check(response.body().len())?;
+    tracing::debug!(
+        "webdriver response: {} {}",
+        response.status().as_u16(),
+        String::from_utf8_lossy(response.body())
+    );
+    Ok(response)
+}
+
+/// Return the status and body of a successful response, or the appropriate error.
+pub(crate) fn check_response_status(response: Response<Bytes>) -> WebDriverResult<(u16, Bytes)> {
     let status = response.status().as_u16();
-    let lossy_response = String::from_utf8_lossy(response.body());
-    tracing::debug!("webdriver response: {status} {lossy_response}");
     match status {
-        200..=399 => match serde_json::from_slice(response.body()) {
-            Ok(v) => Ok(CmdResponse {
-                body: v,
-                status,
-            }),
-            Err(_) => Err(WebDriverError::parse(status, lossy_response.into_owned())),
-        },
-        _ => Err(WebDriverError::parse(status, lossy_response.into_owned())),
+        200..=399 => Ok((status, response.into_body())),
+        _ => Err(WebDriverError::parse(
+            status,
+            String::from_utf8_lossy(response.body()).into_owned(),
+        )),
+    }
+}
+
+/// Parse the JSON body of a successful response.
+pub(crate) fn parse_response_body(status: u16, body: Bytes) -> WebDriverResult<CmdResponse> {
+    match serde_json::from_slice(&body) {
+        Ok(v) => Ok(CmdResponse {
+            body: v,
+            status,
+        }),
+        Err(_) => Err(WebDriverError::parse(status, String::from_utf8_lossy(&body).into_owned())),
+    }
+}
+
+/// The HTTP status, headers and body of a response from the WebDriver server.
+///
+/// See `WebDriverConfig::record_last_response` and `WebDriver::last_response()`.
+#[derive(Debug, Clone)]
+pub struct ResponseData {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The HTTP response headers.
+    pub headers: HeaderMap,
+    /// The raw response body.
+    pub body: Bytes,
+}
+
+impl ResponseData {
+    /// Get the response body as a string, replacing any invalid UTF-8.
+    pub fn body_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+impl From<&Response<Bytes>> for ResponseData {
+    fn from(response: &Response<Bytes>) -> Self {
+        Self {
+            status: response.status().as_u16(),
+            headers: response.headers().clone(),
+            body: response.body().clone(),
+        }
     }
 }
 
@@ -205,3 +671,128 @@ impl CmdResponse {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::command::{Command, FormatRequestData};
+    use crate::session::http::test_client::MockClient;
+    use crate::SessionId;
+
+    /// Returns a value of the specified size, ignoring `MaxResponseSize`.
+    fn large_client(size: usize) -> MockClient {
+        MockClient::new().route("", move |request| {
+            assert!(request.extensions.get::<MaxResponseSize>().is_some());
+            serde_json::json!("x".repeat(size))
+        })
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_request() {
+        let result = test_client::test_handle().cmd(Command::GetTitle).await;
+        match result {
+            Err(WebDriverError::RequestFailed(msg)) => {
+                assert_eq!(msg, "unexpected request: GET /session/test/title")
+            }
+            x => panic!("expected request failure, got {x:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_url_path_prefix() {
+        for server_url in ["http://localhost:4444/wd/hub", "http://localhost:4444/wd/hub/"] {
+            let client =
+                MockClient::new().route("", |request| Value::from(request.uri.to_string()));
+            let handle = SessionHandle::new(Arc::new(client), server_url, "test".into()).unwrap();
+            let uri: String = handle.cmd(Command::GetTitle).await.unwrap().value().unwrap();
+            assert_eq!(uri, "http://localhost:4444/wd/hub/session/test/title");
+        }
+    }
+
+    fn config(max_response_size: usize) -> WebDriverConfig {
+        WebDriverConfig::builder().max_response_size(max_response_size).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let request_data = Command::GetTitle.format_request(&SessionId::from("test"));
+        let url = Url::parse("http://localhost:4444").unwrap();
+
+        let client = large_client(100);
+        let result = run_webdriver_cmd(&client, &request_data, &url, &config(1000)).await;
+        assert_eq!(result.unwrap().body["value"].as_str().unwrap().len(), 100);
+
+        let client = large_client(2000);
+        let result = run_webdriver_cmd(&client, &request_data, &url, &config(1000)).await;
+        assert!(matches!(result, Err(WebDriverError::ResponseTooLarge(1000))));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_reqwest_max_response_size() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Serve a chunked response with no content length, so the limit can only be
+        // detected while reading the body.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    let head = "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n";
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    let chunk = "x".repeat(1024);
+                    for _ in 0..64 {
+                        let data = format!("{:x}\r\n{chunk}\r\n", chunk.len());
+                        if stream.write_all(data.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                    let _ = stream.write_all(b"0\r\n\r\n").await;
+                });
+            }
+        });
+
+        let client = create_reqwest_client(std::time::Duration::from_secs(10), None, None);
+        let request_data = Command::GetTitle.format_request(&SessionId::from("test"));
+        let result = run_webdriver_cmd_raw(&client, &request_data, &url, &config(10_000)).await;
+        assert!(matches!(result, Err(WebDriverError::ResponseTooLarge(10_000))));
+
+        let (_, body) =
+            run_webdriver_cmd_raw(&client, &request_data, &url, &config(100_000)).await.unwrap();
+        assert_eq!(body.len(), 64 * 1024);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_reqwest_request_timeout() {
+        use tokio::io::AsyncReadExt;
+
+        // Accept connections but never respond.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while matches!(stream.read(&mut buf).await, Ok(n) if n > 0) {}
+                });
+            }
+        });
+
+        let client = create_reqwest_client(std::time::Duration::from_secs(60), None, None);
+        let request_data = Command::GetTitle.format_request(&SessionId::from("test"));
+        let config = WebDriverConfig::builder()
+            .request_timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let start = std::time::Instant::now();
+        let result = run_webdriver_cmd_raw(&client, &request_data, &url, &config).await;
+        assert!(matches!(result, Err(WebDriverError::HttpError(_))), "{result:?}");
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
@@ -6,3 +6,5 @@ pub mod handle;
 pub mod http;
 /// Helper for values returned from scripts.
 pub mod scriptret;
+/// Lazily deserialized arrays returned from scripts.
+pub mod scriptstream;
@@ -1,5 +1,6 @@
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverError, WebDriverResult};
 use crate::session::handle::SessionHandle;
+use crate::support::base64_decode;
 use crate::WebElement;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
@@ -52,11 +53,136 @@ impl ScriptRet {
         Ok(v)
     }
 
+    /// Deserialize the value at the specified dotted path within the JSON value.
+    ///
+    /// Each segment of the path is either an object key or an array index, for example
+    /// `"data.items.0.name"`. An empty path refers to the whole value.
+    ///
+    /// Returns a `WebDriverError::Json` error if a key is missing, an index is out of
+    /// range, or the value at the path cannot be deserialized as `T`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let ret = driver.execute(r#"return {data: {items: [{name: "first"}]}};"#, Vec::new()).await?;
+    /// let name: String = ret.get_path("data.items.0.name")?;
+    /// assert_eq!(name, "first");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn get_path<T>(&self, path: &str) -> WebDriverResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut value = &self.value;
+        if !path.is_empty() {
+            for (i, segment) in path.split('.').enumerate() {
+                let next = match value {
+                    Value::Object(map) => map.get(segment),
+                    Value::Array(items) => segment.parse::<usize>().ok().and_then(|n| items.get(n)),
+                    _ => None,
+                };
+                value = next.ok_or_else(|| {
+                    let parent = path.split('.').take(i).collect::<Vec<_>>().join(".");
+                    let what = match value {
+                        Value::Array(items) => {
+                            format!(
+                                "index '{segment}' is not valid for an array of length {}",
+                                items.len()
+                            )
+                        }
+                        Value::Object(_) => format!("key '{segment}' does not exist"),
+                        _ => format!("cannot look up '{segment}' in {}", describe(value)),
+                    };
+                    let location = match parent.is_empty() {
+                        true => "the script return value".to_string(),
+                        false => format!("'{parent}'"),
+                    };
+                    WebDriverError::Json(format!("invalid path '{path}': {what} at {location}"))
+                })?;
+            }
+        }
+        serde_json::from_value(value.clone()).map_err(|e| {
+            WebDriverError::Json(format!("failed to deserialize the value at path '{path}': {e}"))
+        })
+    }
+
+    /// Return true if the script returned `null` or `undefined`.
+    pub fn is_null(&self) -> bool {
+        self.value.is_null()
+    }
+
+    /// Convert the JSON value into the a deserializeable type, or return `None` if the
+    /// script returned `null` or `undefined`.
+    pub fn value_opt<T>(&self) -> WebDriverResult<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.value {
+            Value::Null => Ok(None),
+            _ => self.convert().map(Some),
+        }
+    }
+
+    /// Decode the returned string as base64 and return the bytes.
+    ///
+    /// This is useful for scripts that return binary data, such as the contents of a blob
+    /// or a canvas. A data URL such as `data:image/png;base64,...`, as returned by
+    /// `FileReader.readAsDataURL()` or `canvas.toDataURL()`, is also accepted, in which
+    /// case only the data after the comma is decoded.
+    ///
+    /// Returns a `WebDriverError::Json` error if the script did not return a string, or a
+    /// `WebDriverError::DecodeError` error if the string is not valid base64.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let ret = driver.execute(r#"return btoa("hello");"#, Vec::new()).await?;
+    /// assert_eq!(ret.as_base64_bytes()?, b"hello");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn as_base64_bytes(&self) -> WebDriverResult<Vec<u8>> {
+        let data = self.value.as_str().ok_or_else(|| {
+            WebDriverError::Json(format!(
+                "the script returned {}, not a base64 string",
+                describe(&self.value)
+            ))
+        })?;
+        let data = match data.strip_prefix("data:").and_then(|d| d.split_once(',')) {
+            Some((media_type, data)) if media_type.ends_with(";base64") => data,
+            _ => data,
+        };
+        base64_decode(data)
+    }
+
     /// Get a single WebElement return value.
     ///
-    /// Your script must return only a single element for this to work.
+    /// Your script must return only a single element for this to work. The element is
+    /// bound to the same session as the script, so it can be used like any other element.
+    ///
+    /// Returns a `WebDriverError::Json` error describing the value if the script did not
+    /// return an element.
     pub fn element(self) -> WebDriverResult<WebElement> {
-        WebElement::from_json(self.value, self.handle)
+        to_element(self.value, self.handle).map_err(|kind| {
+            WebDriverError::Json(format!("the script returned {kind}, not an element"))
+        })
     }
 
     /// Get a single WebElement return value.
@@ -68,10 +194,31 @@ impl ScriptRet {
     /// Get a vec of WebElements from the return value.
     ///
     /// Your script must return an array of elements for this to work.
+    ///
+    /// Returns a `WebDriverError::Json` error describing the value if the script did not
+    /// return an array, or if any item in the array is not an element.
     pub fn elements(self) -> WebDriverResult<Vec<WebElement>> {
-        let values: Vec<Value> = serde_json::from_value(self.value)?;
+        let values = match self.value {
+            Value::Array(values) => values,
+            value => {
+                return Err(WebDriverError::Json(format!(
+                    "the script returned {}, not an array of elements",
+                    describe(&value)
+                )))
+            }
+        };
         let handle = self.handle;
-        values.into_iter().map(|x| WebElement::from_json(x, handle.clone())).collect()
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| {
+                to_element(x, handle.clone()).map_err(|kind| {
+                    WebDriverError::Json(format!(
+                        "item {i} of the array returned by the script is {kind}, not an element"
+                    ))
+                })
+            })
+            .collect()
     }
 
     /// Get a vec of WebElements from the return value.
@@ -80,3 +227,129 @@ impl ScriptRet {
         self.elements()
     }
 }
+
+/// Convert the value to an element, or return a description of the value.
+fn to_element(value: Value, handle: Arc<SessionHandle>) -> Result<WebElement, &'static str> {
+    let kind = describe(&value);
+    WebElement::from_json(value, handle).map_err(|_| kind)
+}
+
+/// Describe the type of the JSON value, for error messages.
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::test_handle;
+    use serde_json::json;
+
+    fn script_ret(value: Value) -> ScriptRet {
+        ScriptRet::new(test_handle(), value)
+    }
+
+    #[test]
+    fn test_null_return() {
+        let ret = script_ret(Value::Null);
+        assert!(ret.is_null());
+        assert_eq!(ret.value_opt::<String>().unwrap(), None);
+    }
+
+    #[test]
+    fn test_non_null_return() {
+        let ret = script_ret(json!("hello"));
+        assert!(!ret.is_null());
+        assert_eq!(ret.value_opt::<String>().unwrap(), Some("hello".to_string()));
+
+        let ret = script_ret(json!([1, 2, 3]));
+        assert_eq!(ret.value_opt::<Vec<u32>>().unwrap(), Some(vec![1, 2, 3]));
+        assert!(ret.value_opt::<String>().is_err());
+    }
+
+    #[test]
+    fn test_element_return() {
+        let elem = script_ret(json!({"element-6066-11e4-a52e-4f735466cecf": "abc"})).element();
+        assert_eq!(elem.unwrap().element_id().to_string(), "abc");
+
+        match script_ret(json!({"id": "abc"})).element() {
+            Err(WebDriverError::Json(msg)) => assert!(msg.contains("an object"), "{msg}"),
+            x => panic!("expected json error, got {x:?}"),
+        }
+        match script_ret(Value::Null).element() {
+            Err(WebDriverError::Json(msg)) => assert!(msg.contains("null"), "{msg}"),
+            x => panic!("expected json error, got {x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_elements_return() {
+        let ret = script_ret(json!([
+            {"element-6066-11e4-a52e-4f735466cecf": "a"},
+            {"element-6066-11e4-a52e-4f735466cecf": "b"}
+        ]));
+        let ids: Vec<_> =
+            ret.elements().unwrap().iter().map(|e| e.element_id().to_string()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        match script_ret(json!("abc")).elements() {
+            Err(WebDriverError::Json(msg)) => assert!(msg.contains("a string"), "{msg}"),
+            x => panic!("expected json error, got {x:?}"),
+        }
+        match script_ret(json!([{"element-6066-11e4-a52e-4f735466cecf": "a"}, 5])).elements() {
+            Err(WebDriverError::Json(msg)) => assert!(msg.contains("item 1"), "{msg}"),
+            x => panic!("expected json error, got {x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_as_base64_bytes() {
+        assert_eq!(script_ret(json!("aGVsbG8=")).as_base64_bytes().unwrap(), b"hello");
+        assert_eq!(script_ret(json!("")).as_base64_bytes().unwrap(), b"");
+        let data_url = json!("data:application/octet-stream;base64,AAEC/w==");
+        assert_eq!(script_ret(data_url).as_base64_bytes().unwrap(), [0, 1, 2, 255]);
+
+        for invalid in ["not base64!", "aGVsbG8", "data:text/plain,hello"] {
+            let result = script_ret(json!(invalid)).as_base64_bytes();
+            assert!(matches!(result, Err(WebDriverError::DecodeError(_))), "{invalid}");
+        }
+        match script_ret(json!(42)).as_base64_bytes() {
+            Err(WebDriverError::Json(msg)) => assert!(msg.contains("a number"), "{msg}"),
+            x => panic!("expected json error, got {x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_get_path() {
+        let ret = script_ret(json!({
+            "data": {
+                "items": [{"name": "first", "tags": ["a", "b"]}, {"name": "second"}],
+                "count": 2
+            }
+        }));
+        assert_eq!(ret.get_path::<String>("data.items.0.name").unwrap(), "first");
+        assert_eq!(ret.get_path::<String>("data.items.1.name").unwrap(), "second");
+        assert_eq!(ret.get_path::<String>("data.items.0.tags.1").unwrap(), "b");
+        assert_eq!(ret.get_path::<u32>("data.count").unwrap(), 2);
+        assert_eq!(ret.get_path::<Vec<String>>("data.items.0.tags").unwrap(), vec!["a", "b"]);
+        assert_eq!(ret.get_path::<Value>("").unwrap(), *ret.json());
+
+        let error = |path: &str| match ret.get_path::<Value>(path) {
+            Err(WebDriverError::Json(msg)) => msg,
+            x => panic!("expected json error for '{path}', got {x:?}"),
+        };
+        assert!(error("data.missing").contains("key 'missing' does not exist at 'data'"));
+        assert!(error("data.items.5").contains("array of length 2 at 'data.items'"));
+        assert!(error("data.items.x").contains("index 'x'"));
+        assert!(error("data.count.x").contains("in a number at 'data.count'"));
+        assert!(error("other").contains("at the script return value"));
+        assert!(matches!(ret.get_path::<u32>("data.items.0.name"), Err(WebDriverError::Json(_))));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::{WebDriverError, WebDriverResult};
+use bytes::Bytes;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Iterator over the items of an array returned from a script, deserializing each
+/// item lazily from the raw response body.
+///
+/// This is returned by [`WebDriver::execute_stream`].
+///
+/// ## Memory usage
+///
+/// The raw response body is kept until the iterator is dropped, but no intermediate
+/// `serde_json::Value` is ever built. Each item is deserialized directly from the
+/// response bytes as the iterator advances, so peak memory is roughly the size of the
+/// response plus one item, rather than the response plus a `Vec<Value>` plus a `Vec<T>`.
+///
+/// Once an item fails to deserialize, the error is returned and the iterator ends.
+///
+/// [`WebDriver::execute_stream`]: crate::session::handle::SessionHandle::execute_stream
+pub struct ScriptStream<T> {
+    body: Bytes,
+    pos: usize,
+    first: bool,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for ScriptStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptStream")
+            .field("len", &self.body.len())
+            .field("pos", &self.pos)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<T> ScriptStream<T> {
+    /// Create a new `ScriptStream` from the raw body of an `ExecuteScript` response.
+    ///
+    /// The body must be a JSON object whose `value` field is an array.
+    pub(crate) fn new(body: Bytes) -> WebDriverResult<Self> {
+        let pos = find_value_array(&body)?;
+        Ok(Self {
+            body,
+            pos,
+            first: true,
+            done: false,
+            _marker: PhantomData,
+        })
+    }
+
+    fn next_item(&mut self) -> WebDriverResult<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.pos = skip_whitespace(&self.body, self.pos);
+        match (self.first, self.body.get(self.pos)) {
+            (_, Some(b']')) => return Ok(None),
+            (true, Some(_)) => {}
+            (false, Some(b',')) => self.pos += 1,
+            _ => return Err(unexpected_body("expected ',' or ']'")),
+        }
+        self.first = false;
+        parse_one(&self.body, &mut self.pos).map(Some)
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for ScriptStream<T> {
+    type Item = WebDriverResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.next_item().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+fn unexpected_body(msg: &str) -> WebDriverError {
+    WebDriverError::Json(format!("Unexpected response body: {msg}"))
+}
+
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(|b| b.is_ascii_whitespace()) {
+        pos += 1;
+    }
+    pos
+}
+
+fn expect_byte(bytes: &[u8], pos: &mut usize, expected: u8) -> WebDriverResult<()> {
+    *pos = skip_whitespace(bytes, *pos);
+    if bytes.get(*pos) != Some(&expected) {
+        return Err(unexpected_body(&format!("expected '{}'", expected as char)));
+    }
+    *pos += 1;
+    Ok(())
+}
+
+/// Deserialize a single JSON value starting at `pos`, and advance `pos` past it.
+fn parse_one<'de, U: Deserialize<'de>>(bytes: &'de [u8], pos: &mut usize) -> WebDriverResult<U> {
+    let mut stream = serde_json::Deserializer::from_slice(&bytes[*pos..]).into_iter::<U>();
+    match stream.next() {
+        Some(Ok(value)) => {
+            *pos += stream.byte_offset();
+            Ok(value)
+        }
+        Some(Err(e)) => Err(e.into()),
+        None => Err(unexpected_body("unexpected end of data")),
+    }
+}
+
+/// Return the position just after the opening `[` of the top-level `value` array.
+fn find_value_array(bytes: &[u8]) -> WebDriverResult<usize> {
+    let mut pos = 0;
+    expect_byte(bytes, &mut pos, b'{')?;
+    loop {
+        pos = skip_whitespace(bytes, pos);
+        let key: String = parse_one(bytes, &mut pos)?;
+        expect_byte(bytes, &mut pos, b':')?;
+        if key == "value" {
+            pos = skip_whitespace(bytes, pos);
+            return match bytes.get(pos) {
+                Some(b'[') => Ok(pos + 1),
+                _ => Err(unexpected_body("the script did not return an array")),
+            };
+        }
+
+        parse_one::<IgnoredAny>(bytes, &mut pos)?;
+        pos = skip_whitespace(bytes, pos);
+        match bytes.get(pos) {
+            Some(b',') => pos += 1,
+            _ => return Err(unexpected_body("missing 'value' field")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use serde::Deserialize;
+
+    fn stream<T>(body: &'static str) -> WebDriverResult<ScriptStream<T>> {
+        ScriptStream::new(Bytes::from_static(body.as_bytes()))
+    }
+
+    #[test]
+    fn test_stream_values() {
+        let items: Vec<u32> =
+            stream(r#"{"value": [1, 2 ,3]}"#).unwrap().collect::<WebDriverResult<_>>().unwrap();
+        assert_eq!(items, vec![1, 2, 3]);
+
+        let items: Vec<String> =
+            stream(" { \"sessionId\": {\"a\": [1]}, \"value\" : [\"x\",\"y\"] } ")
+                .unwrap()
+                .collect::<WebDriverResult<_>>()
+                .unwrap();
+        assert_eq!(items, vec!["x", "y"]);
+
+        assert_eq!(stream::<u32>(r#"{"value": []}"#).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_stream_structs() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Link {
+            href: String,
+        }
+
+        let mut items = stream::<Link>(r#"{"value": [{"href": "a"}, {"href": "b"}]}"#).unwrap();
+        assert_eq!(
+            items.next().unwrap().unwrap(),
+            Link {
+                href: "a".to_string()
+            }
+        );
+        assert_eq!(
+            items.next().unwrap().unwrap(),
+            Link {
+                href: "b".to_string()
+            }
+        );
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        assert_matches!(stream::<u32>(r#"{"value": null}"#), Err(WebDriverError::Json(_)));
+        assert_matches!(stream::<u32>(r#"{"other": [1]}"#), Err(WebDriverError::Json(_)));
+
+        // A bad item ends the stream after returning the error.
+        let mut items = stream::<u32>(r#"{"value": [1, "two", 3]}"#).unwrap();
+        assert_eq!(items.next().unwrap().unwrap(), 1);
+        assert_matches!(items.next(), Some(Err(WebDriverError::Json(_))));
+        assert!(items.next().is_none());
+    }
+}
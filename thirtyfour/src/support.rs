@@ -1,6 +1,8 @@
 use crate::error::WebDriverResult;
 use base64::{prelude::BASE64_STANDARD, Engine};
 use futures::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 /// Helper to run the specified future and block the current thread waiting for the result.
@@ -31,3 +33,76 @@ pub fn base64_decode(data: &str) -> WebDriverResult<Vec<u8>> {
     let value = BASE64_STANDARD.decode(data)?;
     Ok(value)
 }
+
+/// Write the data to the specified file, creating any missing parent directories.
+///
+/// The data is written to a temporary file in the same directory first, and then renamed
+/// into place, so the file at `path` is never left partially written. Each call uses its
+/// own temporary file, so concurrent writes to the same path do not interfere, and the
+/// last one to finish wins. If writing or renaming fails, the temporary file is removed.
+pub(crate) async fn write_file_atomic(path: &Path, data: &[u8]) -> WebDriverResult<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+    if let Some(dir) = dir {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+
+    static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+    let temp_id = NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed);
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.{temp_id}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+    let result = match tokio::fs::write(&temp_path, data).await {
+        Ok(()) => tokio::fs::rename(&temp_path, path).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        // Don't leave a partially written temporary file behind.
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_file_atomic() {
+        let dir = std::env::temp_dir().join(format!("thirtyfour-write-{}", std::process::id()));
+        let path = dir.join("nested").join("out.pdf");
+        write_file_atomic(&path, b"%PDF-1.4").await.unwrap();
+        write_file_atomic(&path, b"%PDF-1.7").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"%PDF-1.7");
+
+        // Only the final file is left behind.
+        let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_file_atomic_concurrent() {
+        let dir =
+            std::env::temp_dir().join(format!("thirtyfour-concurrent-{}", std::process::id()));
+        let path = dir.join("out.png");
+        let writes = (0..8u8).map(|i| {
+            let path = path.clone();
+            tokio::spawn(async move { write_file_atomic(&path, &[i; 4096]).await })
+        });
+        for result in futures::future::join_all(writes).await {
+            result.unwrap().unwrap();
+        }
+
+        // The file contains exactly one of the writes, and no temporary files are left.
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(data.len(), 4096);
+        assert!(data.iter().all(|b| *b == data[0]));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,11 +4,51 @@ use crate::session::handle::SessionHandle;
 use crate::WindowHandle;
 use crate::{
     error::{WebDriverError, WebDriverResult},
-    Alert, WebElement,
+    Alert, By, WebElement,
 };
+use std::collections::VecDeque;
+use std::future::Future;
 use std::sync::Arc;
 
-/// Struct for switching between frames/windows/alerts.
+/// The maximum depth of nested frames searched by `WebDriver::query_anywhere()`.
+const MAX_FRAME_DEPTH: usize = 8;
+
+/// The frame to enter, for use with `WebDriver::in_frame()`.
+#[derive(Debug, Clone)]
+pub enum FrameTarget {
+    /// The frame at the specified index. See `WebDriver::enter_frame()`.
+    Index(u16),
+    /// The frame contained within the element. See `WebElement::enter_frame()`.
+    Element(WebElement),
+    /// The frame with the specified name or id. See `WebDriver::switch_to_frame_by_name()`.
+    Name(String),
+}
+
+/// Builder for switching between windows, frames and alerts.
+///
+/// This is returned by `WebDriver::switch_to()`. Each method consumes the `SwitchTo`
+/// and returns a future that performs the switch, and is equivalent to the
+/// corresponding method on `WebDriver` (for example `switch_to().parent_frame()` is the
+/// same as `enter_parent_frame()`). Use whichever style reads better.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let iframe = driver.find(By::Id("iframeid1")).await?;
+/// driver.switch_to().frame_element(&iframe).await?;
+/// // ... interact with the frame contents ...
+/// driver.switch_to().default_content().await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct SwitchTo {
     handle: Arc<SessionHandle>,
@@ -23,77 +63,201 @@ impl SwitchTo {
         }
     }
 
-    /// Get the active element for this session.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::active_element()"
-    )]
-    pub async fn active_element(self) -> WebDriverResult<WebElement> {
-        self.handle.active_element().await
-    }
-
-    /// Switch to the specified alert.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been deprecated. See the `Alert` module for new method names"
-    )]
-    pub fn alert(self) -> Alert {
-        Alert::new(self.handle)
-    }
-
-    /// Switch to the default frame.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::enter_default_frame()"
-    )]
-    pub async fn default_content(self) -> WebDriverResult<()> {
-        self.handle.enter_default_frame().await
+    /// Switch to the specified window. See `WebDriver::switch_to_window()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let handles = driver.windows().await?;
+    /// driver.switch_to().window(handles[0].clone()).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn window(self, handle: WindowHandle) -> WebDriverResult<()> {
+        self.handle.switch_to_window(handle).await
     }
 
-    /// Switch to the frame specified at the index.
-    #[deprecated(since = "0.30.0", note = "This method has been moved to WebDriver::enter_frame()")]
-    pub async fn frame_number(self, frame_number: u16) -> WebDriverResult<()> {
-        self.handle.enter_frame(frame_number).await
+    /// Switch to the frame at the specified index. See `WebDriver::enter_frame()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.switch_to().frame_index(0).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn frame_index(self, index: u16) -> WebDriverResult<()> {
+        self.handle.enter_frame(index).await
     }
 
-    /// Switch to the frame contained within the element.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebElement::enter_frame()"
-    )]
+    /// Switch to the frame contained within the element. See `WebElement::enter_frame()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let iframe = driver.find(By::Id("iframeid1")).await?;
+    /// driver.switch_to().frame_element(&iframe).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub async fn frame_element(self, frame_element: &WebElement) -> WebDriverResult<()> {
         frame_element.clone().enter_frame().await
     }
 
-    /// Switch to the parent of the frame the client is currently contained within.
-    #[deprecated(
-        since = "0.30.0",
-        note = "This method has been moved to WebDriver::enter_parent_frame()"
-    )]
+    /// Switch to the parent of the current frame. See `WebDriver::enter_parent_frame()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.switch_to().parent_frame().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub async fn parent_frame(self) -> WebDriverResult<()> {
-        self.handle.enter_parent_frame().await?;
-        Ok(())
+        self.handle.enter_parent_frame().await
+    }
+
+    /// Switch to the top-level browsing context. See `WebDriver::enter_default_frame()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.switch_to().default_content().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn default_content(self) -> WebDriverResult<()> {
+        self.handle.enter_default_frame().await
     }
 
-    /// Create a new window.
-    #[deprecated(since = "0.30.0", note = "This method has been moved to WebDriver::new_window()")]
+    /// Open a new window and return its handle. See `WebDriver::new_window()`.
+    ///
+    /// Note that this does not switch to the new window.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let handle = driver.switch_to().new_window().await?;
+    /// driver.switch_to().window(handle).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub async fn new_window(self) -> WebDriverResult<WindowHandle> {
         self.handle.new_window().await
     }
 
-    /// Create a new tab.
-    #[deprecated(since = "0.30.0", note = "This method has been moved to WebDriver::new_tab()")]
+    /// Open a new tab and return its handle. See `WebDriver::new_tab()`.
+    ///
+    /// Note that this does not switch to the new tab.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let handle = driver.switch_to().new_tab().await?;
+    /// driver.switch_to().window(handle).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub async fn new_tab(self) -> WebDriverResult<WindowHandle> {
         self.handle.new_tab().await
     }
 
-    /// Switch to the specified window.
+    /// Return an `Alert` for interacting with the active alert.
+    ///
+    /// The `Alert` methods are equivalent to `WebDriver::get_alert_text()`,
+    /// `WebDriver::accept_alert()` and so on.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.find(By::Id("show-alert")).await?.click().await?;
+    /// let alert = driver.switch_to().alert();
+    /// println!("Alert says: {}", alert.text().await?);
+    /// alert.accept().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn alert(self) -> Alert {
+        Alert::new(self.handle)
+    }
+
+    /// Get the active element for this session.
     #[deprecated(
         since = "0.30.0",
-        note = "This method has been moved to WebDriver::switch_to_window()"
+        note = "This method has been moved to WebDriver::active_element()"
     )]
-    pub async fn window(self, handle: WindowHandle) -> WebDriverResult<()> {
-        self.handle.switch_to_window(handle).await
+    pub async fn active_element(self) -> WebDriverResult<WebElement> {
+        self.handle.active_element().await
+    }
+
+    /// Switch to the frame specified at the index.
+    #[deprecated(since = "0.33.0", note = "This method has been renamed to frame_index()")]
+    pub async fn frame_number(self, frame_number: u16) -> WebDriverResult<()> {
+        self.frame_index(frame_number).await
     }
 
     /// Switch to the specified named window.
@@ -102,21 +266,7 @@ impl SwitchTo {
         note = "This method has been moved to WebDriver::switch_to_named_window()"
     )]
     pub async fn window_name(self, name: &str) -> WebDriverResult<()> {
-        let original_handle = self.handle.window().await?;
-        let handles = self.handle.windows().await?;
-        for handle in handles {
-            self.handle.switch_to_window(handle).await?;
-            let ret = self.handle.execute(r#"return window.name;"#, Vec::new()).await?;
-            let current_name: String = ret.convert()?;
-            if current_name == name {
-                return Ok(());
-            }
-        }
-
-        self.handle.switch_to_window(original_handle).await?;
-        Err(WebDriverError::NoSuchWindow(WebDriverErrorInfo::new(format!(
-            "unable to find named window: {name}"
-        ))))
+        self.handle.switch_to_named_window(name).await
     }
 }
 
@@ -206,6 +356,281 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Switch to an iframe (or frame) by its `name` attribute, or failing that, its `id`.
+    ///
+    /// This is more stable than switching by index when the order of frames on the page
+    /// changes. Frames with a matching `name` take priority over frames with a matching
+    /// `id`. If more than one frame matches, the first one in document order is chosen.
+    ///
+    /// Returns `WebDriverError::NoSuchFrame` if no matching frame is found in the
+    /// current browsing context.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Enter the iframe with name="content" (or id="content").
+    /// driver.switch_to_frame_by_name("content").await?;
+    /// // We can now search for elements within the iframe.
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// elem.click().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_to_frame_by_name(
+        self: &Arc<SessionHandle>,
+        name: &str,
+    ) -> WebDriverResult<()> {
+        let script = r#"
+            var frames = Array.from(document.querySelectorAll("iframe, frame"));
+            return frames.find(f => f.name === arguments[0])
+                || frames.find(f => f.id === arguments[0])
+                || null;
+        "#;
+        let ret = self.execute(script, vec![serde_json::Value::String(name.to_string())]).await?;
+        if ret.is_null() {
+            return Err(WebDriverError::NoSuchFrame(WebDriverErrorInfo::new(format!(
+                "no frame found with name or id '{name}'"
+            ))));
+        }
+        ret.element()?.enter_frame().await
+    }
+
+    /// Return the number of frames in the current browsing context, as given by
+    /// `window.frames.length`.
+    ///
+    /// These are the frames that `enter_frame()` indexes, including frames created by
+    /// `<object>` elements and frames inside shadow roots. Only the direct child frames
+    /// are counted. To visit nested frames, enter each frame and call `frame_count()`
+    /// again, recursively.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// for index in 0..driver.frame_count().await? {
+    ///     driver.enter_frame(index as u16).await?;
+    ///     // ... interact with the frame contents ...
+    ///     driver.enter_parent_frame().await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn frame_count(self: &Arc<SessionHandle>) -> WebDriverResult<usize> {
+        let ret = self.execute(r#"return window.frames.length;"#, Vec::new()).await?;
+        ret.convert()
+    }
+
+    /// Return the elements (usually `iframe` or `frame`) that contain the frames in the
+    /// current browsing context.
+    ///
+    /// The elements are in the same order as `window.frames`, so the element at index N
+    /// is the frame entered by `enter_frame(N)`. Frames inside open shadow roots are
+    /// included. A frame whose element cannot be found, such as one inside a closed
+    /// shadow root, is left out, and then the list is shorter than `frame_count()`.
+    ///
+    /// Only the direct child frames are returned. To visit nested frames, enter each
+    /// frame and call `frames()` again, recursively.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// for frame in driver.frames().await? {
+    ///     frame.enter_frame().await?;
+    ///     // ... interact with the frame contents ...
+    ///     driver.enter_parent_frame().await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn frames(self: &Arc<SessionHandle>) -> WebDriverResult<Vec<WebElement>> {
+        let script = r#"
+            var containers = [];
+            (function collect(root) {
+                root.querySelectorAll("*").forEach(e => {
+                    if (e.contentWindow) containers.push(e);
+                    if (e.shadowRoot) collect(e.shadowRoot);
+                });
+            })(document);
+            return Array.from(window.frames, w => containers.find(e => e.contentWindow === w))
+                .filter(e => e);
+        "#;
+        self.execute(script, Vec::new()).await?.elements()
+    }
+
+    /// Execute the specified function inside a frame, then switch back to the default
+    /// content.
+    ///
+    /// The switch back to the default content happens even if the function returns an
+    /// error, so that later commands are not accidentally run inside the frame. The
+    /// return value will be that of the supplied function, unless an error occurs while
+    /// entering or leaving the frame. If both the function and leaving the frame fail,
+    /// the error from the function is returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::FrameTarget;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let text = driver
+    ///     .in_frame(FrameTarget::Name("content".to_string()), || async {
+    ///         driver.find(By::Id("button1")).await?.text().await
+    ///     })
+    ///     .await?;
+    /// // We are now back in the default content, even if we started in a nested frame.
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn in_frame<F, Fut, T>(
+        self: &Arc<SessionHandle>,
+        frame: FrameTarget,
+        f: F,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        match frame {
+            FrameTarget::Index(index) => self.enter_frame(index).await?,
+            FrameTarget::Element(element) => element.enter_frame().await?,
+            FrameTarget::Name(name) => self.switch_to_frame_by_name(&name).await?,
+        }
+
+        let result = f().await;
+        let restored = self.enter_default_frame().await;
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Switch to the frame at the specified path, starting from the top-level document.
+    ///
+    /// Each entry in the path is a frame index, as used by `enter_frame()`, within the
+    /// frame given by the previous entry. An empty path switches to the default content.
+    /// This is the path returned by `query_anywhere()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Enter the first frame inside the second frame of the page.
+    /// driver.enter_frame_path(&[1, 0]).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn enter_frame_path(&self, path: &[u16]) -> WebDriverResult<()> {
+        self.enter_default_frame().await?;
+        for index in path {
+            self.enter_frame(*index).await?;
+        }
+        Ok(())
+    }
+
+    /// Find the first element matching the selector in the top-level document or in any
+    /// of its frames, returning it along with the path of the frame that contains it.
+    ///
+    /// The top-level document is searched first, and then the frames are searched
+    /// breadth-first, so the match in the least deeply nested frame is returned. Frames
+    /// nested more than 8 deep are not searched.
+    ///
+    /// **NOTE:** This changes the current frame. The search always starts from the
+    /// top-level document, regardless of the frame that was current before. On success
+    /// the driver is left switched into the frame containing the element, so that the
+    /// element can be used straight away. The returned path can be passed to
+    /// `enter_frame_path()` to switch back to that frame later. If no element is found,
+    /// the driver is switched back to the top-level document and `NoSuchElement` is
+    /// returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let (elem, path) = driver.query_anywhere(By::Id("button1")).await?;
+    /// // The driver is now inside the frame containing the element.
+    /// elem.click().await?;
+    /// driver.enter_default_frame().await?;
+    /// // ... later ...
+    /// driver.enter_frame_path(&path).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn query_anywhere(
+        self: &Arc<SessionHandle>,
+        by: impl Into<By>,
+    ) -> WebDriverResult<(WebElement, Vec<u16>)> {
+        let by = by.into();
+        let mut queue = VecDeque::from([Vec::new()]);
+        while let Some(path) = queue.pop_front() {
+            match self.enter_frame_path(&path).await {
+                Ok(()) => {}
+                // The frame may have been removed since it was counted.
+                Err(WebDriverError::NoSuchFrame(_)) => continue,
+                Err(e) => return Err(e),
+            }
+
+            if let Some(element) = self.find_all(by.clone()).await?.into_iter().next() {
+                return Ok((element, path));
+            }
+            if path.len() < MAX_FRAME_DEPTH {
+                let count = self.frame_count().await?.min(u16::MAX as usize) as u16;
+                for index in 0..count {
+                    let mut child = path.clone();
+                    child.push(index);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        self.enter_default_frame().await?;
+        Err(crate::error::no_such_element(format!(
+            "no such element: not found using selector {by} in the document or any of its frames"
+        )))
+    }
+
     /// Switch to the parent frame.
     ///
     /// # Example:
@@ -363,3 +788,121 @@ impl SessionHandle {
         self.cmd(Command::NewTab).await?.value()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::{MockClient, Reply};
+    use parking_lot::Mutex;
+    use serde_json::{json, Value};
+
+    /// Simulates a page with nested frames, tracking the current frame path.
+    ///
+    /// `frames` maps each frame path to the number of child frames it contains, and
+    /// the element is only found in the frame at `target`.
+    struct NestedFrames {
+        frames: Vec<(Vec<u16>, usize)>,
+        target: Option<Vec<u16>>,
+        current: Mutex<Vec<u16>>,
+        searched: Mutex<Vec<Vec<u16>>>,
+    }
+
+    impl NestedFrames {
+        fn new(frames: Vec<(Vec<u16>, usize)>, target: Option<Vec<u16>>) -> Arc<Self> {
+            Arc::new(Self {
+                frames,
+                target,
+                current: Mutex::new(Vec::new()),
+                searched: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn child_count(&self, path: &[u16]) -> usize {
+            self.frames.iter().find(|(f, _)| f == path).map_or(0, |f| f.1)
+        }
+
+        fn switch_to_frame(&self, id: &Value) -> Reply {
+            let mut current = self.current.lock();
+            match id.as_u64() {
+                Some(index) if (index as usize) < self.child_count(&current) => {
+                    current.push(index as u16);
+                    Reply::null()
+                }
+                Some(_) => Reply::error(404, "no such frame", ""),
+                None => {
+                    current.clear();
+                    Reply::null()
+                }
+            }
+        }
+
+        fn find_elements(&self) -> Value {
+            let current = self.current.lock();
+            self.searched.lock().push(current.clone());
+            match self.target.as_ref() == Some(&*current) {
+                true => json!([{ "element-6066-11e4-a52e-4f735466cecf": "found" }]),
+                false => json!([]),
+            }
+        }
+
+        fn handle(self: &Arc<Self>) -> Arc<SessionHandle> {
+            let (frame, script, search) = (self.clone(), self.clone(), self.clone());
+            let client = MockClient::new()
+                .route("/frame", move |request| frame.switch_to_frame(&request.body["id"]))
+                .route("/execute/sync", move |_| json!(script.child_count(&script.current.lock())))
+                .route("/elements", move |_| search.find_elements())
+                .reply("", Reply::null());
+            Arc::new(client).handle()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_anywhere() {
+        let frames = vec![(vec![], 2), (vec![0], 0), (vec![1], 1), (vec![1, 0], 0)];
+        let page = NestedFrames::new(frames, Some(vec![1, 0]));
+        let handle = page.handle();
+
+        let (element, path) = handle.query_anywhere(By::Id("button1")).await.unwrap();
+        assert_eq!(element.element_id().to_string(), "found");
+        assert_eq!(path, vec![1, 0]);
+        assert_eq!(*page.current.lock(), vec![1, 0]);
+        assert_eq!(page.searched.lock().as_slice(), &[vec![], vec![0], vec![1], vec![1, 0]]);
+    }
+
+    #[tokio::test]
+    async fn test_query_anywhere_not_found() {
+        // A single frame nested deeper than the search goes.
+        let frames = (0..12).map(|depth| (vec![0; depth], 1)).collect();
+        let page = NestedFrames::new(frames, None);
+        let handle = page.handle();
+
+        let result = handle.query_anywhere(By::Id("button1")).await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchElement(_))));
+        assert!(page.current.lock().is_empty());
+        let searched = page.searched.lock();
+        assert_eq!(searched.len(), MAX_FRAME_DEPTH + 1);
+        assert_eq!(searched.last().unwrap().len(), MAX_FRAME_DEPTH);
+    }
+
+    #[tokio::test]
+    async fn test_in_frame_restores_on_error() {
+        let client = Arc::new(MockClient::new().reply("", Reply::null()));
+        let handle = client.handle();
+
+        let result: WebDriverResult<()> = handle
+            .in_frame(FrameTarget::Index(1), || async {
+                Err(WebDriverError::FatalError("inside frame".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(WebDriverError::FatalError(m)) if m == "inside frame"));
+        let requests: Vec<_> =
+            client.requests().iter().map(|r| (r.path.clone(), r.body.clone())).collect();
+        assert_eq!(
+            requests,
+            [
+                ("/session/test/frame".to_string(), json!({ "id": 1 })),
+                ("/session/test/frame".to_string(), json!({ "id": null })),
+            ]
+        );
+    }
+}
@@ -1,16 +1,22 @@
+use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::common::command::Command;
 use crate::common::config::WebDriverConfig;
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::query::DEFAULT_POLL_INTERVAL;
 use crate::prelude::WebDriverError;
-use crate::session::create::start_session;
+use crate::session::create::start_session_with_capabilities;
 use crate::session::handle::SessionHandle;
 #[cfg(feature = "reqwest")]
 use crate::session::http::create_reqwest_client;
-use crate::session::http::HttpClient;
-use crate::Capabilities;
+use crate::session::http::{
+    normalize_server_url, HttpClient, InterceptingClient, RequestInterceptor,
+};
+use crate::support::sleep;
+use crate::{Capabilities, SessionId};
 
 /// The `WebDriver` struct encapsulates an async Selenium WebDriver browser
 /// session.
@@ -26,7 +32,7 @@ use crate::Capabilities;
 /// // NOTE: this assumes you have a WebDriver compatible server running
 /// //       at http://localhost:4444
 /// //       e.g. `geckodriver -p 4444`
-/// // NOTE: If using selenium 3.x, use "http://localhost:4444/wd/hub/session" for the url.
+/// // NOTE: If using selenium 3.x, use "http://localhost:4444/wd/hub" for the url.
 /// let driver = WebDriver::new("http://localhost:4444", caps).await?;
 /// driver.goto("https://www.rust-lang.org/").await?;
 /// // Always remember to close the session.
@@ -55,7 +61,7 @@ impl WebDriver {
     /// // NOTE: this assumes you have a WebDriver compatible server running
     /// //       at http://localhost:4444
     /// //       e.g. `geckodriver -p 4444`
-    /// // NOTE: If using selenium 3.x, use "http://localhost:4444/wd/hub/session" for the url.
+    /// // NOTE: If using selenium 3.x, use "http://localhost:4444/wd/hub" for the url.
     /// let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
@@ -64,10 +70,26 @@ impl WebDriver {
     /// ```
     ///
     /// ## Using Selenium Server
-    /// - For selenium 3.x, you need to also add "/wd/hub/session" to the end of the url
-    ///   (e.g. "http://localhost:4444/wd/hub/session")
+    /// - For selenium 3.x, you need to also add "/wd/hub" to the end of the url
+    ///   (e.g. "http://localhost:4444/wd/hub")
     /// - For selenium 4.x and later, no path should be needed on the url.
     ///
+    /// A path on the url is kept as a prefix for every command, so "http://host/wd/hub"
+    /// and "http://host/wd/hub/" are equivalent.
+    ///
+    /// ## Invalid urls
+    /// The url must use the `http`, `https` or (see below) `unix` scheme. Anything else,
+    /// including a url without a scheme such as "localhost:4444", returns
+    /// `WebDriverError::InvalidUrl`.
+    ///
+    /// ## Using a Unix domain socket
+    /// With the `unix-socket` feature enabled, a url such as "unix:///tmp/chromedriver.sock"
+    /// connects to a server listening on that socket instead of a TCP port. The whole path
+    /// is treated as the socket path, so a url path prefix such as "/wd/hub" cannot be used.
+    /// This is only supported on Unix platforms (Linux and macOS), and only when using the
+    /// default `reqwest` client. A custom `HttpClient` receives requests for
+    /// "http://localhost/" and must dial the socket itself.
+    ///
     /// ## Troubleshooting
     ///
     /// - If the webdriver appears to freeze or give no response, please check that the
@@ -92,11 +114,8 @@ impl WebDriver {
         S: Into<String>,
         C: Into<Capabilities>,
     {
-        // TODO: create builder and make timeout configurable.
-        #[cfg(feature = "reqwest")]
-        let client = create_reqwest_client(std::time::Duration::from_secs(120));
-        #[cfg(not(feature = "reqwest"))]
-        let client = crate::session::http::null_client::create_null_client();
+        let server_url = server_url.into();
+        let client = create_default_client(&server_url, None);
         Self::new_with_config_and_client(server_url, capabilities, config, client).await
     }
 
@@ -113,21 +132,145 @@ impl WebDriver {
         S: Into<String>,
         C: Into<Capabilities>,
     {
-        let capabilities = capabilities.into();
-        let server_url = server_url
-            .into()
-            .parse()
-            .map_err(|e| WebDriverError::ParseError(format!("invalid url: {e}")))?;
+        Self::connect(server_url.into(), capabilities.into(), config, Arc::new(client), 0).await
+    }
+
+    /// Create a new `WebDriverBuilder` for configuring a new session.
+    ///
+    /// This consolidates all of the options for creating a session. `WebDriver::new()`
+    /// is equivalent to `WebDriver::builder(server_url).capabilities(caps).build()`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// let driver = WebDriver::builder("http://localhost:4444")
+    ///     .capabilities(DesiredCapabilities::chrome())
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .request_timeout(Duration::from_secs(300))
+    ///     .connect_retries(3)
+    ///     .build()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn builder(server_url: impl Into<String>) -> WebDriverBuilder {
+        WebDriverBuilder::new(server_url)
+    }
+
+    /// Start a new session, retrying up to `connect_retries` times if the server
+    /// could not be reached.
+    async fn connect(
+        server_url: String,
+        capabilities: Capabilities,
+        config: WebDriverConfig,
+        client: Arc<dyn HttpClient>,
+        connect_retries: u32,
+    ) -> WebDriverResult<Self> {
+        let server_url = parse_server_url(server_url)?;
 
-        let client = Arc::new(client);
-        let session_id = start_session(client.as_ref(), &server_url, &config, capabilities).await?;
+        let mut retry = 0;
+        let (session_id, session_capabilities) = loop {
+            let caps = capabilities.clone();
+            match start_session_with_capabilities(client.as_ref(), &server_url, &config, caps).await
+            {
+                Err(WebDriverError::HttpError(e)) if retry < connect_retries => {
+                    tracing::debug!("retrying new session after http error: {e}");
+                    sleep(DEFAULT_POLL_INTERVAL).await;
+                    retry += 1;
+                }
+                r => break r?,
+            }
+        };
 
-        let handle = SessionHandle::new_with_config(client, server_url, session_id, config)?;
+        let handle = SessionHandle::new_with_config(client, server_url, session_id, config)?
+            .with_capabilities(session_capabilities);
         Ok(Self {
             handle: Arc::new(handle),
         })
     }
 
+    /// Reconnect to an existing session that is still running on the WebDriver server.
+    ///
+    /// This is useful when running against a Selenium Grid, where the test process may
+    /// have restarted but the browser session is still active.
+    ///
+    /// Unlike creating a `SessionHandle` directly, this will verify that the session is
+    /// still alive by requesting the page title, and will return an error if not.
+    ///
+    /// **NOTE:** Capabilities cannot be renegotiated when reconnecting. The session will
+    ///           continue to use the capabilities it was originally created with.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let session_id = driver.session_id().clone();
+    ///
+    /// // Later, possibly in a different process...
+    /// let driver = WebDriver::reconnect("http://localhost:4444", session_id).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn reconnect<S>(
+        server_url: S,
+        session_id: impl Into<SessionId>,
+    ) -> WebDriverResult<Self>
+    where
+        S: Into<String>,
+    {
+        let server_url = server_url.into();
+        let client = create_default_client(&server_url, None);
+        Self::reconnect_with_config_and_client(
+            server_url,
+            session_id,
+            WebDriverConfig::default(),
+            client,
+        )
+        .await
+    }
+
+    /// Reconnect to an existing session using the specified `WebDriverConfig` and client.
+    ///
+    /// See [`WebDriver::reconnect`] for more details.
+    pub async fn reconnect_with_config_and_client<S>(
+        server_url: S,
+        session_id: impl Into<SessionId>,
+        config: WebDriverConfig,
+        client: impl HttpClient,
+    ) -> WebDriverResult<Self>
+    where
+        S: Into<String>,
+    {
+        let server_url = parse_server_url(server_url.into())?;
+
+        let handle = SessionHandle::new_with_config(
+            Arc::new(client),
+            server_url,
+            session_id.into(),
+            config,
+        )?;
+        let handle = Arc::new(handle);
+        // Verify that the session is still alive.
+        handle.cmd(Command::GetTitle).await?;
+        Ok(Self {
+            handle,
+        })
+    }
+
     /// Clone this `WebDriver` keeping the session handle, but supplying a new `WebDriverConfig`.
     ///
     /// This still uses the same underlying client, and still controls the same browser
@@ -152,6 +295,194 @@ impl WebDriver {
     }
 }
 
+/// Builder for creating a new `WebDriver` session.
+///
+/// See `WebDriver::builder()`.
+pub struct WebDriverBuilder {
+    server_url: String,
+    capabilities: Capabilities,
+    config: WebDriverConfig,
+    client: Option<Arc<dyn HttpClient>>,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    connect_retries: u32,
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+}
+
+impl Debug for WebDriverBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebDriverBuilder")
+            .field("server_url", &self.server_url)
+            .field("capabilities", &self.capabilities)
+            .field("config", &self.config)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .field("connect_retries", &self.connect_retries)
+            .field("interceptors", &self.interceptors.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebDriverBuilder {
+    /// Create a new `WebDriverBuilder` for the specified WebDriver server.
+    ///
+    /// The defaults match `WebDriver::new()`: empty capabilities, the default
+    /// `WebDriverConfig`, and a `reqwest` client with a 120 second request timeout.
+    pub fn new(server_url: impl Into<String>) -> Self {
+        Self {
+            server_url: server_url.into(),
+            capabilities: Capabilities::new(),
+            config: WebDriverConfig::default(),
+            client: None,
+            connect_timeout: None,
+            request_timeout: None,
+            connect_retries: 0,
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Set the capabilities to request for the new session.
+    pub fn capabilities(mut self, capabilities: impl Into<Capabilities>) -> Self {
+        self.capabilities = capabilities.into();
+        self
+    }
+
+    /// Set the `WebDriverConfig` for the new session.
+    pub fn config(mut self, config: WebDriverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Use the specified `reqwest` client for all requests.
+    ///
+    /// Any `connect_timeout()` is ignored, since it only applies to the default client.
+    #[cfg(feature = "reqwest")]
+    pub fn client(self, client: reqwest::Client) -> Self {
+        self.http_client(client)
+    }
+
+    /// Use the specified `HttpClient` for all requests.
+    ///
+    /// Any `connect_timeout()` is ignored, since it only applies to the default client.
+    pub fn http_client(mut self, client: impl HttpClient) -> Self {
+        self.client = Some(Arc::new(client));
+        self
+    }
+
+    /// Set the timeout for establishing a connection to the WebDriver server.
+    ///
+    /// This only limits connecting, so a short value fails fast if the server cannot be
+    /// reached, without limiting how long a command may take once connected. See
+    /// `request_timeout()` for that. By default, only the request timeout applies.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the overall timeout for each command sent to the WebDriver server, until the
+    /// whole response has been received. The default is `WebDriverConfig::request_timeout`,
+    /// which is 120 seconds unless changed in `config()`.
+    ///
+    /// Use a long request timeout with a short `connect_timeout()` to allow slow commands
+    /// such as `execute()` while the browser is busy, but still fail fast if the server
+    /// is down.
+    ///
+    /// This sets `WebDriverConfig::request_timeout`, overriding any value in `config()`,
+    /// and also applies to a custom client.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the number of times to retry creating the session if the WebDriver server
+    /// could not be reached, for example while it is still starting up.
+    ///
+    /// Retries happen every 500ms and only for `WebDriverError::HttpError`. The default is 0.
+    pub fn connect_retries(mut self, retries: u32) -> Self {
+        self.connect_retries = retries;
+        self
+    }
+
+    /// Add an interceptor that is called for every request and response.
+    ///
+    /// Interceptors are called in the order they were added.
+    pub fn interceptor(mut self, interceptor: impl RequestInterceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Start the new session.
+    pub async fn build(self) -> WebDriverResult<WebDriver> {
+        let mut config = self.config;
+        if let Some(timeout) = self.request_timeout {
+            config.request_timeout = Some(timeout);
+        }
+        let client = match self.client {
+            Some(client) => client,
+            None => Arc::new(create_default_client(&self.server_url, self.connect_timeout)),
+        };
+        let client: Arc<dyn HttpClient> = if self.interceptors.is_empty() {
+            client
+        } else {
+            Arc::new(InterceptingClient::new(client, self.interceptors))
+        };
+        WebDriver::connect(self.server_url, self.capabilities, config, client, self.connect_retries)
+            .await
+    }
+}
+
+/// The URL scheme for a WebDriver server listening on a Unix domain socket.
+const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// Return the socket path if the server url uses the `unix://` scheme.
+fn unix_socket_path(server_url: &str) -> Option<&str> {
+    server_url.strip_prefix(UNIX_SOCKET_SCHEME)
+}
+
+/// Parse the server url, mapping a `unix://` url to the HTTP url sent over the socket.
+fn parse_server_url(server_url: String) -> WebDriverResult<url::Url> {
+    let server_url = match unix_socket_path(&server_url) {
+        Some(_) if cfg!(all(feature = "unix-socket", unix)) => "http://localhost/".to_string(),
+        Some(_) => {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "unix:// server urls require the `unix-socket` feature on a Unix platform"
+                    .to_string(),
+            )));
+        }
+        None => server_url,
+    };
+    let url: url::Url = server_url.parse().map_err(|e| {
+        WebDriverError::InvalidUrl(format!(
+            "'{server_url}' is not a valid url ({e}). Use the full url including the scheme, \
+             e.g. \"http://localhost:4444\""
+        ))
+    })?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebDriverError::InvalidUrl(format!(
+            "'{server_url}' has the unsupported scheme '{}'. Use an http://, https:// or \
+             unix:// url, e.g. \"http://localhost:4444\"",
+            url.scheme()
+        )));
+    }
+    Ok(normalize_server_url(url))
+}
+
+/// Create the default HTTP client, if the `reqwest` feature is enabled.
+fn create_default_client(server_url: &str, connect_timeout: Option<Duration>) -> impl HttpClient {
+    #[cfg(feature = "reqwest")]
+    let client = create_reqwest_client(
+        crate::common::config::DEFAULT_REQUEST_TIMEOUT,
+        connect_timeout,
+        unix_socket_path(server_url),
+    );
+    #[cfg(not(feature = "reqwest"))]
+    let client = {
+        let _ = (server_url, connect_timeout);
+        crate::session::http::null_client::create_null_client()
+    };
+    client
+}
+
 /// The Deref implementation allows the WebDriver to "fall back" to SessionHandle and
 /// exposes all the methods there without requiring us to use an async_trait.
 /// See documentation at the top of this module for more details on the design.
@@ -162,3 +493,147 @@ impl Deref for WebDriver {
         &self.handle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::http::test_client::{MockClient, Reply};
+    use crate::session::http::Body;
+    use http::{HeaderValue, Request};
+    use serde_json::json;
+
+    /// A server that refuses the first `unreachable_for` connections.
+    fn fake_server(unreachable_for: usize) -> Arc<MockClient> {
+        let refused = std::iter::repeat_n(Reply::HttpError("connection refused"), unreachable_for);
+        let session = json!({"sessionId": "abc", "capabilities": {}});
+        let client = MockClient::new()
+            .sequence("/session", refused.chain([session.into()]))
+            .reply("", Reply::null());
+        Arc::new(client)
+    }
+
+    struct ApiKey;
+
+    impl RequestInterceptor for ApiKey {
+        fn on_request(&self, request: &mut Request<Body<'_>>) {
+            request.headers_mut().insert("x-api-key", HeaderValue::from_static("secret"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_interceptor() {
+        let server = fake_server(0);
+        let driver = WebDriver::builder("http://localhost:4444")
+            .http_client(server.clone())
+            .interceptor(ApiKey)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(driver.session_id().to_string(), "abc");
+
+        // New session plus setting the default timeouts.
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.headers.get("x-api-key").is_some_and(|h| h == "secret")));
+    }
+
+    #[tokio::test]
+    async fn test_builder_connect_retries() {
+        let server = fake_server(2);
+        let driver = WebDriver::builder("http://localhost:4444")
+            .http_client(server.clone())
+            .connect_retries(2)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(driver.session_id().to_string(), "abc");
+        assert!(server.requests().iter().all(|r| r.headers.get("x-api-key").is_none()));
+
+        let server = fake_server(2);
+        let result = WebDriver::builder("http://localhost:4444")
+            .http_client(server.clone())
+            .connect_retries(1)
+            .build()
+            .await;
+        assert!(matches!(result, Err(WebDriverError::HttpError(_))));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_null_capabilities() {
+        let session = json!({"sessionId": "abc", "capabilities": null});
+        let client = MockClient::new().reply("/session", session).reply("", Reply::null());
+        let driver = WebDriver::builder("http://localhost:4444")
+            .http_client(Arc::new(client))
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(driver.session_id().to_string(), "abc");
+        assert!(driver.capabilities().is_empty());
+    }
+
+    #[test]
+    fn test_parse_server_url() {
+        let url = parse_server_url("http://localhost:4444".to_string()).unwrap();
+        assert_eq!(url.as_str(), "http://localhost:4444/");
+
+        let url = parse_server_url("http://localhost:4444/wd/hub".to_string()).unwrap();
+        assert_eq!(url.as_str(), "http://localhost:4444/wd/hub/");
+        assert_eq!(url.join("session").unwrap().as_str(), "http://localhost:4444/wd/hub/session");
+        let url = parse_server_url("https://grid.example.com/wd/hub/".to_string()).unwrap();
+        assert_eq!(url.as_str(), "https://grid.example.com/wd/hub/");
+
+        // A missing scheme either fails to parse or is mistaken for a custom scheme.
+        for invalid in ["localhost:4444", "127.0.0.1:4444", "ftp://localhost:4444", ""] {
+            let result = parse_server_url(invalid.to_string());
+            assert!(matches!(&result, Err(WebDriverError::InvalidUrl(_))), "{invalid}: {result:?}");
+        }
+
+        let result = parse_server_url("unix:///tmp/chromedriver.sock".to_string());
+        if cfg!(all(feature = "unix-socket", unix)) {
+            assert_eq!(result.unwrap().as_str(), "http://localhost/");
+        } else {
+            assert!(matches!(result, Err(WebDriverError::UnsupportedOperation(_))));
+        }
+    }
+
+    #[cfg(all(feature = "unix-socket", unix))]
+    #[tokio::test]
+    async fn test_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = std::env::temp_dir().join(format!("thirtyfour-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let mut paths = Vec::new();
+            // New session, then setting the default timeouts.
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                paths.push(request.split(' ').nth(1).unwrap().to_string());
+
+                let body = r#"{"value": {"sessionId": "abc", "capabilities": {}}}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                     content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            paths
+        });
+
+        let url = format!("unix://{}", path.display());
+        let driver = WebDriver::new(url, Capabilities::new()).await.unwrap();
+        assert_eq!(driver.session_id().to_string(), "abc");
+        assert_eq!(server.await.unwrap(), vec!["/session", "/session/abc/timeouts"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
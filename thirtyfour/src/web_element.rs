@@ -1,19 +1,31 @@
+use serde::de::{Deserialize, DeserializeSeed, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 
 use crate::common::command::Command;
-use crate::error::WebDriverError;
-use crate::js::SIMULATE_DRAG_AND_DROP;
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+use crate::extensions::query::{
+    poll_value, ElementPoller, ElementPollerWithTimeout, DEFAULT_POLL_INTERVAL,
+};
+use crate::js::{
+    CANVAS_TO_DATA_URL, DISPATCH_EVENT, ELEMENT_CHILDREN, ELEMENT_RELATIVE, HTML5_DRAG_AND_DROP,
+    IS_IN_VIEWPORT, OBSCURING_ELEMENT, SET_VALUE, SIMULATE_DRAG_AND_DROP,
+};
 use crate::session::handle::SessionHandle;
-use crate::support::base64_decode;
-use crate::IntoArcStr;
+use crate::session::scriptret::ScriptRet;
+use crate::support::{base64_decode, sleep};
 use crate::{common::types::ElementRect, error::WebDriverResult, By, ElementRef};
-use crate::{ElementId, TypingData};
+use crate::{CachedWebElement, IntoArcStr};
+use crate::{ElementId, Key, TypingData};
+
+/// The delay between attempts when retrying an empty element screenshot.
+const SCREENSHOT_RETRY_DELAY: Duration = Duration::from_millis(100);
 
 /// The WebElement struct encapsulates a single element on a page.
 ///
@@ -70,9 +82,17 @@ impl fmt::Debug for WebElement {
     }
 }
 
+/// Two `WebElement`s are equal if they refer to the same element in the same session.
+///
+/// The WebDriver server gives each element a single reference that is kept for as long
+/// as the element exists, so the same element compares equal however it was obtained,
+/// for example when it is returned by two different scripts or by a script and a `find()`.
+/// No element state is cached. The reference is only valid within the document that
+/// contains the element: after navigating away, an element that looks the same on the
+/// new page is a different element.
 impl PartialEq for WebElement {
     fn eq(&self, other: &Self) -> bool {
-        self.element_id() == other.element_id()
+        self.element_id == other.element_id && self.handle.session_id() == other.handle.session_id()
     }
 }
 
@@ -94,8 +114,8 @@ impl WebElement {
     /// Construct a `WebElement` from a JSON response and a session handle.
     ///
     /// The `value` argument should be a JSON object containing the property
-    /// `element-6066-11e4-a52e-4f735466cecf` whose value is the element id
-    /// assigned by the WebDriver.
+    /// `element-6066-11e4-a52e-4f735466cecf` (or the legacy `ELEMENT` property) whose
+    /// value is the element id assigned by the WebDriver.
     ///
     /// You can get the session handle from any existing `WebDriver` or
     /// `WebElement` that is using this session, e.g. `driver.handle`.
@@ -112,15 +132,69 @@ impl WebElement {
         })
     }
 
+    /// Create a new WebElement from the element id assigned by the WebDriver.
+    ///
+    /// This is the counterpart to serializing a `WebElement`, and can be used to restore
+    /// an element reference that was stored earlier. See also [`WebElementSeed`] for
+    /// deserializing element references directly.
+    ///
+    /// **NOTE**: An element reference is only valid within the document it was found in.
+    /// After navigating away or reloading the page, using the element will return a
+    /// `StaleElementReference` error.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let id = elem.element_id().to_string();
+    ///
+    /// // Later, in the same document...
+    /// let elem = WebElement::from_reference(driver.handle.clone(), id);
+    /// elem.click().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn from_reference(handle: Arc<SessionHandle>, id: impl Into<ElementId>) -> Self {
+        Self::new(id.into(), handle)
+    }
+
+    /// Wrap this `WebElement` in a [`CachedWebElement`], which caches properties that
+    /// rarely change, such as the tag name.
+    ///
+    /// See [`CachedWebElement`] for the list of cached properties.
+    pub fn cached(self) -> CachedWebElement {
+        CachedWebElement::new(self)
+    }
+
     /// Serialize this `WebElement` to JSON.
     ///
     /// This is useful for supplying an element as an argument to a script.
     ///
     /// See the documentation for [`SessionHandle::execute`] for more details.
     pub fn to_json(&self) -> WebDriverResult<Value> {
-        Ok(serde_json::to_value(ElementRef::Element {
-            id: self.element_id.to_string(),
-        })?)
+        Ok(serde_json::to_value(self.element_ref())?)
+    }
+
+    /// Get the element reference for this element, using the key set by
+    /// `WebDriverConfig::legacy_element_key`.
+    fn element_ref(&self) -> ElementRef {
+        let id = self.element_id.to_string();
+        match self.handle.config().legacy_element_key {
+            true => ElementRef::LegacyElement {
+                id,
+            },
+            false => ElementRef::Element {
+                id,
+            },
+        }
     }
 
     /// Get the internal element id for this element.
@@ -154,13 +228,12 @@ impl WebElement {
         r.value()
     }
 
-    /// Alias for [`WebElement::rect()`].
-    #[deprecated(since = "0.32.0", note = "Use rect() instead")]
-    pub async fn rectangle(&self) -> WebDriverResult<ElementRect> {
-        self.rect().await
-    }
-
-    /// Get the tag name for this WebElement.
+    /// Get the bounding rectangle for this WebElement in device pixels.
+    ///
+    /// [`WebElement::rect()`] returns the rectangle in CSS pixels, whereas screenshots are
+    /// captured in device pixels. On HiDPI displays these differ by `window.devicePixelRatio`.
+    /// This method returns the CSS-pixel rectangle multiplied by the device pixel ratio, so
+    /// that it lines up with the pixels in a screenshot.
     ///
     /// # Example:
     /// ```no_run
@@ -172,17 +245,33 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// assert_eq!(elem.tag_name().await?, "button");
+    /// let r = elem.rect_device_pixels().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn tag_name(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::GetElementTagName(self.element_id.clone())).await?.value()
+    pub async fn rect_device_pixels(&self) -> WebDriverResult<ElementRect> {
+        let rect = self.rect().await?;
+        let ratio: f64 =
+            self.handle.execute("return window.devicePixelRatio;", Vec::new()).await?.convert()?;
+        Ok(rect.scale(ratio))
     }
 
-    /// Get the class name for this WebElement.
+    /// Get the rects of this element and `other`, using a single request.
+    async fn rect_pair(&self, other: &Self) -> WebDriverResult<(ElementRect, ElementRect)> {
+        let mut rects = self.handle.rects_of(&[self.clone(), other.clone()]).await?.into_iter();
+        match (rects.next(), rects.next()) {
+            (Some(a), Some(b)) => Ok((a, b)),
+            _ => Err(WebDriverError::Json("expected 2 element rects".to_string())),
+        }
+    }
+
+    /// Get the bounding rectangle for this WebElement, relative to the top-left corner
+    /// of `other`.
+    ///
+    /// The width and height are unchanged. The x and y coordinates are negative if this
+    /// element starts above or to the left of `other`.
     ///
     /// # Example:
     /// ```no_run
@@ -193,18 +282,26 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let class_name: Option<String> = elem.class_name().await?;
+    /// let container = driver.find(By::Id("container")).await?;
+    /// let icon = driver.find(By::Id("icon")).await?;
+    /// let r = icon.relative_rect(&container).await?;
+    /// assert!(r.x >= 0.0 && r.y >= 0.0);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn class_name(&self) -> WebDriverResult<Option<String>> {
-        self.attr("class").await
+    pub async fn relative_rect(&self, other: &Self) -> WebDriverResult<ElementRect> {
+        let (rect, origin) = self.rect_pair(other).await?;
+        Ok(ElementRect {
+            x: rect.x - origin.x,
+            y: rect.y - origin.y,
+            ..rect
+        })
     }
 
-    /// Get the id for this WebElement.
+    /// Return true if this element is entirely to the left of `other`, meaning that its
+    /// right edge is at or before the left edge of `other`.
     ///
     /// # Example:
     /// ```no_run
@@ -215,18 +312,21 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let id: Option<String> = elem.id().await?;
+    /// let icon = driver.find(By::Id("icon")).await?;
+    /// let label = driver.find(By::Id("label")).await?;
+    /// assert!(icon.is_left_of(&label).await?);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn id(&self) -> WebDriverResult<Option<String>> {
-        self.attr("id").await
+    pub async fn is_left_of(&self, other: &Self) -> WebDriverResult<bool> {
+        let (rect, other) = self.rect_pair(other).await?;
+        Ok(rect.x + rect.width <= other.x)
     }
 
-    /// Get the text contents for this WebElement.
+    /// Return true if this element is entirely above `other`, meaning that its bottom
+    /// edge is at or before the top edge of `other`.
     ///
     /// # Example:
     /// ```no_run
@@ -237,24 +337,26 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let text = elem.text().await?;
-    /// assert_eq!(text, "Click Me");
+    /// let header = driver.find(By::Id("header")).await?;
+    /// let footer = driver.find(By::Id("footer")).await?;
+    /// assert!(header.is_above(&footer).await?);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn text(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::GetElementText(self.element_id.clone())).await?.value()
+    pub async fn is_above(&self, other: &Self) -> WebDriverResult<bool> {
+        let (rect, other) = self.rect_pair(other).await?;
+        Ok(rect.y + rect.height <= other.y)
     }
 
-    /// Convenience method for getting the (optional) value property of this element.
-    pub async fn value(&self) -> WebDriverResult<Option<String>> {
-        self.prop("value").await
+    /// Alias for [`WebElement::rect()`].
+    #[deprecated(since = "0.32.0", note = "Use rect() instead")]
+    pub async fn rectangle(&self) -> WebDriverResult<ElementRect> {
+        self.rect().await
     }
 
-    /// Click the WebElement.
+    /// Get the tag name for this WebElement.
     ///
     /// # Example:
     /// ```no_run
@@ -266,18 +368,17 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// elem.click().await?;
+    /// assert_eq!(elem.tag_name().await?, "button");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn click(&self) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementClick(self.element_id.clone())).await?;
-        Ok(())
+    pub async fn tag_name(&self) -> WebDriverResult<String> {
+        self.handle.cmd(Command::GetElementTagName(self.element_id.clone())).await?.value()
     }
 
-    /// Clear the WebElement contents.
+    /// Get the class name for this WebElement.
     ///
     /// # Example:
     /// ```no_run
@@ -288,19 +389,21 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Css("input[type='text']")).await?;
-    /// elem.clear().await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let class_name: Option<String> = elem.class_name().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn clear(&self) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementClear(self.element_id.clone())).await?;
-        Ok(())
+    pub async fn class_name(&self) -> WebDriverResult<Option<String>> {
+        self.attr("class").await
     }
 
-    /// Get the specified property.
+    /// Return true if this WebElement has the specified class.
+    ///
+    /// This checks `classList.contains()`, so only whole class tokens match.
+    /// For example, `has_class("active")` does not match an element with class `"inactive"`.
     ///
     /// # Example:
     /// ```no_run
@@ -311,37 +414,29 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Css("input[type='checkbox']")).await?;
-    /// let property_value: Option<String> = elem.prop("checked").await?;
-    /// assert_eq!(property_value.unwrap(), "true");
-    ///
-    /// // If a property is not found, None is returned.
-    /// assert_eq!(elem.prop("invalid-property").await?, None);
+    /// let elem = driver.find(By::Id("my-tab")).await?;
+    /// if elem.has_class("active").await? {
+    ///     println!("The tab is active");
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn prop(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
-        let resp = self
-            .handle
-            .cmd(Command::GetElementProperty(self.element_id.clone(), name.into()))
-            .await?;
-        match resp.value()? {
-            Value::String(v) => Ok(Some(v)),
-            Value::Bool(b) => Ok(Some(b.to_string())),
-            Value::Null => Ok(None),
-            v => Err(WebDriverError::Json(format!("Unexpected value for property: {:?}", v))),
-        }
-    }
-
-    /// Get the specified property.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to prop()")]
-    pub async fn get_property(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
-        self.prop(name).await
+    pub async fn has_class(&self, class: &str) -> WebDriverResult<bool> {
+        self.handle
+            .execute(
+                "return arguments[0].classList.contains(arguments[1]);",
+                vec![self.to_json()?, Value::String(class.to_string())],
+            )
+            .await?
+            .convert()
     }
 
-    /// Get the specified attribute.
+    /// Return true if the tag name of this WebElement matches the specified name.
+    ///
+    /// The comparison is case-insensitive, so `tag_is("button")` matches regardless of
+    /// whether the driver reports the tag name as `button` or `BUTTON`.
     ///
     /// # Example:
     /// ```no_run
@@ -352,31 +447,18 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Name("input2")).await?;
-    /// let attribute: Option<String> = elem.attr("name").await?;
-    /// assert_eq!(attribute.unwrap(), "input2");
-    ///
-    /// // If the attribute does not exist, None is returned.
-    /// assert_eq!(elem.attr("invalid-attribute").await?, None);
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// assert!(elem.tag_is("button").await?);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn attr(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
-        self.handle
-            .cmd(Command::GetElementAttribute(self.element_id.clone(), name.into()))
-            .await?
-            .value()
-    }
-
-    /// Get the specified attribute.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to attr()")]
-    pub async fn get_attribute(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
-        self.attr(name).await
+    pub async fn tag_is(&self, name: &str) -> WebDriverResult<bool> {
+        Ok(self.tag_name().await?.eq_ignore_ascii_case(name))
     }
 
-    /// Get the specified CSS property.
+    /// Get the id for this WebElement.
     ///
     /// # Example:
     /// ```no_run
@@ -387,38 +469,20 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("my-element-id")).await?;
-    /// let css_color = elem.css_value("color").await?;
-    /// assert_eq!(css_color, "rgba(0, 0, 0, 1)");
-    ///
-    /// // If an invalid CSS property is specified, a blank string is returned.
-    /// assert_eq!(elem.css_value("invalid-css-property").await?, "");
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let id: Option<String> = elem.id().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn css_value(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
-        self.handle
-            .cmd(Command::GetElementCssValue(self.element_id.clone(), name.into()))
-            .await?
-            .value()
-    }
-
-    /// Get the specified CSS property.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to css_value()")]
-    pub async fn get_css_property(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
-        self.css_value(name).await
-    }
-
-    /// Return true if the WebElement is currently selected, otherwise false.
-    pub async fn is_selected(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementSelected(self.element_id.clone())).await?.value()
+    pub async fn id(&self) -> WebDriverResult<Option<String>> {
+        self.attr("id").await
     }
 
-    /// Return true if the WebElement is currently displayed, otherwise false.
+    /// Get the text contents for this WebElement.
     ///
-    /// # Example
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
@@ -428,75 +492,97 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// assert!(elem.is_displayed().await?);
+    /// let text = elem.text().await?;
+    /// assert_eq!(text, "Click Me");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn is_displayed(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementDisplayed(self.element_id.clone())).await?.value()
+    pub async fn text(&self) -> WebDriverResult<String> {
+        self.handle.cmd(Command::GetElementText(self.element_id.clone())).await?.value()
     }
 
-    /// Return true if the WebElement is currently enabled, otherwise false.
+    /// Wait until the visible text of this element is equal to the specified string.
     ///
-    /// # Example
+    /// Returns a `WebDriverError::Timeout` error, including the last text seen,
+    /// if the text does not match within the timeout. Any error while reading the text,
+    /// such as a `StaleElementReference`, is returned immediately.
+    ///
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// assert!(elem.is_enabled().await?);
+    /// let status = driver.find(By::Id("status")).await?;
+    /// status.wait_until_text_is("Done", Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn is_enabled(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementEnabled(self.element_id.clone())).await?.value()
+    pub async fn wait_until_text_is(&self, text: &str, timeout: Duration) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for element text to be '{text}'"),
+            || self.text(),
+            |t| t == text,
+        )
+        .await?;
+        Ok(())
     }
 
-    /// Return true if the WebElement is currently clickable (visible and enabled),
-    /// otherwise false.
+    /// Wait until the visible text of this element contains the specified string.
     ///
-    /// # Example
+    /// Returns a `WebDriverError::Timeout` error, including the last text seen,
+    /// if the text does not match within the timeout. Any error while reading the text,
+    /// such as a `StaleElementReference`, is returned immediately.
+    ///
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// assert!(elem.is_clickable().await?);
+    /// let log = driver.find(By::Id("log")).await?;
+    /// log.wait_until_text_contains("finished", Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn is_clickable(&self) -> WebDriverResult<bool> {
-        Ok(self.is_displayed().await? && self.is_enabled().await?)
+    pub async fn wait_until_text_contains(
+        &self,
+        substr: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for element text to contain '{substr}'"),
+            || self.text(),
+            |t| t.contains(substr),
+        )
+        .await?;
+        Ok(())
     }
 
-    /// Return true if the WebElement is currently (still) present
-    /// and not stale.
-    ///
-    /// NOTE: This method simply queries the tag name to
-    ///       determine whether the element is still present.
-    ///
-    /// IMPORTANT:
-    /// If an element is re-rendered, it may be considered stale even
-    /// though to the user it looks like it is still there.
-    ///
-    /// The recommended way to check for the presence of an element is
-    /// to simply search for the element again.
+    /// Convenience method for getting the (optional) value property of this element.
+    pub async fn value(&self) -> WebDriverResult<Option<String>> {
+        self.prop("value").await
+    }
+
+    /// Click the WebElement.
     ///
-    /// # Example
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
@@ -506,64 +592,82 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// assert!(elem.is_present().await?);
+    /// elem.click().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn is_present(&self) -> WebDriverResult<bool> {
-        let present = match self.tag_name().await {
-            Ok(..) => true,
-            Err(WebDriverError::StaleElementReference(..)) => false,
-            Err(e) => return Err(e),
-        };
-        Ok(present)
+    pub async fn click(&self) -> WebDriverResult<()> {
+        self.handle.cmd(Command::ElementClick(self.element_id.clone())).await?;
+        Ok(())
     }
 
-    /// Search for a child element of this WebElement using the specified selector.
+    /// Wait until this WebElement is displayed, enabled and not covered by another
+    /// element, and then click it.
     ///
-    /// **NOTE**: For more powerful element queries including polling and filters, see the
-    ///  [`WebElement::query`] method instead.
+    /// If the click is still intercepted, for example because an overlay appeared in the
+    /// meantime, the whole check is repeated until the timeout. Polls every 500ms.
     ///
-    /// [`WebElement::query`]: crate::extensions::query::ElementQueryable::query
+    /// Returns a `WebDriverError::Timeout` error if the element could not be clicked
+    /// within the timeout, saying whether it was last not displayed, not enabled, covered
+    /// by another element, or had its click intercepted. Any other error, such as a
+    /// `StaleElementReference`, is returned immediately.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("my-element-id")).await?;
-    /// let child_elem = elem.find(By::Tag("button")).await?;
+    /// let elem = driver.find(By::Id("submit")).await?;
+    /// elem.wait_and_click(Duration::from_secs(10)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn find(&self, by: By) -> WebDriverResult<WebElement> {
-        let r = self
-            .handle
-            .cmd(Command::FindElementFromElement(self.element_id.clone(), by.into()))
-            .await?;
-        r.element(self.handle.clone())
+    pub async fn wait_and_click(&self, timeout: Duration) -> WebDriverResult<()> {
+        let mut poller = ElementPollerWithTimeout::new(timeout, DEFAULT_POLL_INTERVAL);
+        loop {
+            let blocker = match self.click_blocker().await? {
+                Some(blocker) => blocker,
+                None => match self.click().await {
+                    Ok(()) => return Ok(()),
+                    Err(
+                        WebDriverError::ElementClickIntercepted(info)
+                        | WebDriverError::ElementNotInteractable(info),
+                    ) => ClickBlocker::Intercepted(info.value.message),
+                    Err(e) => return Err(e),
+                },
+            };
+            if !poller.tick().await {
+                return Err(WebDriverError::Timeout(format!(
+                    "timed out waiting to click element {}: {blocker}",
+                    self.element_id
+                )));
+            }
+        }
     }
 
-    /// Search for a child element of this WebElement using the specified selector.
-    #[deprecated(since = "0.30.0", note = "This method has been renamed to find()")]
-    pub async fn find_element(&self, by: By) -> WebDriverResult<WebElement> {
-        self.find(by).await
+    /// Return the reason this element cannot be clicked yet, if any.
+    async fn click_blocker(&self) -> WebDriverResult<Option<ClickBlocker>> {
+        if !self.is_displayed().await? {
+            return Ok(Some(ClickBlocker::NotDisplayed));
+        }
+        if !self.is_enabled().await? {
+            return Ok(Some(ClickBlocker::NotEnabled));
+        }
+        let obscured_by: Option<String> =
+            self.handle.execute(OBSCURING_ELEMENT, vec![self.to_json()?]).await?.convert()?;
+        Ok(obscured_by.map(ClickBlocker::Obscured))
     }
 
-    /// Search for all child elements of this WebElement that match the specified selector.
-    ///
-    /// **NOTE**: For more powerful element queries including polling and filters, see the
-    /// [`WebElement::query`] method instead.
-    ///
-    /// [`WebElement::query`]: crate::extensions::query::ElementQueryable::query
+    /// Clear the WebElement contents.
     ///
     /// # Example:
     /// ```no_run
@@ -574,9 +678,542 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Id("my-element-id")).await?;
-    /// let child_elems = elem.find_all(By::Tag("button")).await?;
-    /// for child_elem in child_elems {
+    /// let elem = driver.find(By::Css("input[type='text']")).await?;
+    /// elem.clear().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn clear(&self) -> WebDriverResult<()> {
+        self.handle.cmd(Command::ElementClear(self.element_id.clone())).await?;
+        Ok(())
+    }
+
+    /// Clear the WebElement contents, returning an error if the element is not editable.
+    ///
+    /// Some drivers allow `clear()` to succeed on a `readonly` or `disabled` element
+    /// without changing anything. This checks the element's `disabled` and `readOnly`
+    /// properties first, and returns `WebDriverError::InvalidElementState` if either is
+    /// set, rather than clearing the element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Css("input[type='text']")).await?;
+    /// elem.clear_strict().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn clear_strict(&self) -> WebDriverResult<()> {
+        for property in ["disabled", "readOnly"] {
+            if self.prop(property).await?.as_deref() == Some("true") {
+                return Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(format!(
+                    "cannot clear element because it is {}",
+                    property.to_lowercase()
+                ))));
+            }
+        }
+        self.clear().await
+    }
+
+    /// Get the specified property.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Css("input[type='checkbox']")).await?;
+    /// let property_value: Option<String> = elem.prop("checked").await?;
+    /// assert_eq!(property_value.unwrap(), "true");
+    ///
+    /// // If a property is not found, None is returned.
+    /// assert_eq!(elem.prop("invalid-property").await?, None);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn prop(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
+        let resp = self
+            .handle
+            .cmd(Command::GetElementProperty(self.element_id.clone(), name.into()))
+            .await?;
+        match resp.value()? {
+            Value::String(v) => Ok(Some(v)),
+            Value::Bool(b) => Ok(Some(b.to_string())),
+            Value::Null => Ok(None),
+            v => Err(WebDriverError::Json(format!("Unexpected value for property: {:?}", v))),
+        }
+    }
+
+    /// Get the specified property.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to prop()")]
+    pub async fn get_property(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
+        self.prop(name).await
+    }
+
+    /// Get the specified attribute.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input2")).await?;
+    /// let attribute: Option<String> = elem.attr("name").await?;
+    /// assert_eq!(attribute.unwrap(), "input2");
+    ///
+    /// // If the attribute does not exist, None is returned.
+    /// assert_eq!(elem.attr("invalid-attribute").await?, None);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn attr(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
+        self.handle
+            .cmd(Command::GetElementAttribute(self.element_id.clone(), name.into()))
+            .await?
+            .value()
+    }
+
+    /// Get the specified attribute, returning an error if the element does not have it.
+    ///
+    /// This is a shortcut for unwrapping the result of `WebElement::attr()` when the
+    /// attribute is required. If it is missing, a `WebDriverError::MissingAttribute`
+    /// error is returned, including the element and attribute name.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let link = driver.find(By::Css("a.next")).await?;
+    /// let href: String = link.attr_required("href").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn attr_required(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
+        let name = name.into();
+        self.attr(name.clone()).await?.ok_or_else(|| WebDriverError::MissingAttribute {
+            element: self.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    /// Get the specified attribute.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to attr()")]
+    pub async fn get_attribute(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
+        self.attr(name).await
+    }
+
+    /// Wait until the specified attribute of this element is equal to the specified value.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the last value seen,
+    /// if the attribute does not match within the timeout. Any error while reading the
+    /// attribute, such as a `StaleElementReference`, is returned immediately.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let results = driver.find(By::Id("results")).await?;
+    /// results.wait_until_attribute_is("aria-busy", "false", Duration::from_secs(10)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn wait_until_attribute_is(
+        &self,
+        name: &str,
+        value: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for attribute '{name}' to be '{value}'"),
+            || self.attr(name.to_string()),
+            |v| v.as_deref() == Some(value),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Wait until this element does not have the specified attribute.
+    ///
+    /// Returns a `WebDriverError::Timeout` error, including the last value seen,
+    /// if the attribute is still present after the timeout. Any error while reading the
+    /// attribute, such as a `StaleElementReference`, is returned immediately.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let results = driver.find(By::Id("results")).await?;
+    /// results.wait_until_attribute_absent("aria-busy", Duration::from_secs(10)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn wait_until_attribute_absent(
+        &self,
+        name: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<()> {
+        poll_value(
+            timeout,
+            &format!("timed out waiting for attribute '{name}' to be removed"),
+            || self.attr(name.to_string()),
+            Option::is_none,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Get the specified CSS property.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let css_color = elem.css_value("color").await?;
+    /// assert_eq!(css_color, "rgba(0, 0, 0, 1)");
+    ///
+    /// // If an invalid CSS property is specified, a blank string is returned.
+    /// assert_eq!(elem.css_value("invalid-css-property").await?, "");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn css_value(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
+        self.handle
+            .cmd(Command::GetElementCssValue(self.element_id.clone(), name.into()))
+            .await?
+            .value()
+    }
+
+    /// Get the specified CSS property.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to css_value()")]
+    pub async fn get_css_property(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
+        self.css_value(name).await
+    }
+
+    /// Return true if the WebElement is currently selected, otherwise false.
+    pub async fn is_selected(&self) -> WebDriverResult<bool> {
+        self.handle.cmd(Command::IsElementSelected(self.element_id.clone())).await?.value()
+    }
+
+    /// Return true if the WebElement is currently displayed, otherwise false.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// assert!(elem.is_displayed().await?);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn is_displayed(&self) -> WebDriverResult<bool> {
+        self.handle.cmd(Command::IsElementDisplayed(self.element_id.clone())).await?.value()
+    }
+
+    /// Return true if the WebElement is currently enabled, otherwise false.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// assert!(elem.is_enabled().await?);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn is_enabled(&self) -> WebDriverResult<bool> {
+        self.handle.cmd(Command::IsElementEnabled(self.element_id.clone())).await?.value()
+    }
+
+    /// Return true if the WebElement is currently clickable (visible and enabled),
+    /// otherwise false.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// assert!(elem.is_clickable().await?);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn is_clickable(&self) -> WebDriverResult<bool> {
+        Ok(self.is_displayed().await? && self.is_enabled().await?)
+    }
+
+    /// Return true if any part of this WebElement is within the visible viewport,
+    /// otherwise false.
+    ///
+    /// An element that is only partially scrolled into view counts as being in the
+    /// viewport. This only compares the element's bounding rectangle against the window
+    /// size, so it does not account for the element being hidden via CSS or covered by
+    /// other elements. See `is_displayed()` for that.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("load-more")).await?;
+    /// if !elem.is_in_viewport().await? {
+    ///     elem.scroll_into_view().await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn is_in_viewport(&self) -> WebDriverResult<bool> {
+        self.handle.execute(IS_IN_VIEWPORT, vec![self.to_json()?]).await?.convert()
+    }
+
+    /// Return true if the WebElement is currently (still) present
+    /// and not stale.
+    ///
+    /// NOTE: This method simply queries the tag name to
+    ///       determine whether the element is still present.
+    ///
+    /// IMPORTANT:
+    /// If an element is re-rendered, it may be considered stale even
+    /// though to the user it looks like it is still there.
+    ///
+    /// The recommended way to check for the presence of an element is
+    /// to simply search for the element again.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// assert!(elem.is_present().await?);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn is_present(&self) -> WebDriverResult<bool> {
+        let present = match self.tag_name().await {
+            Ok(..) => true,
+            Err(WebDriverError::StaleElementReference(..)) => false,
+            Err(e) => return Err(e),
+        };
+        Ok(present)
+    }
+
+    /// Wait until this element becomes stale, for example because the page has been
+    /// re-rendered or navigated away from.
+    ///
+    /// This polls `WebElement::is_present()` every 500ms and succeeds once the WebDriver
+    /// reports a `StaleElementReference` error. Returns a `WebDriverError::Timeout` error
+    /// if the element is still attached when the timeout is reached. Any other error is
+    /// returned immediately.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let results = driver.find(By::Id("results")).await?;
+    /// driver.find(By::Id("refresh")).await?.click().await?;
+    /// results.wait_until_stale(Duration::from_secs(10)).await?;
+    /// let results = driver.find(By::Id("results")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn wait_until_stale(&self, timeout: Duration) -> WebDriverResult<()> {
+        let description = format!("element {} did not become stale", self.element_id);
+        poll_value(timeout, &description, || self.is_present(), |present| !present).await?;
+        Ok(())
+    }
+
+    /// Search for a child element of this WebElement using the specified selector.
+    ///
+    /// **NOTE**: For more powerful element queries including polling and filters, see the
+    ///  [`WebElement::query`] method instead.
+    ///
+    /// [`WebElement::query`]: crate::extensions::query::ElementQueryable::query
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let child_elem = elem.find(By::Tag("button")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn find(&self, by: impl Into<By>) -> WebDriverResult<WebElement> {
+        let r = self
+            .handle
+            .cmd(Command::FindElementFromElement(self.element_id.clone(), by.into().into()))
+            .await?;
+        r.element(self.handle.clone())
+    }
+
+    /// Search for a child element of this WebElement, retrying until it is found or the
+    /// timeout is reached.
+    ///
+    /// Polls every 500ms. If no matching element is found before the timeout, the last
+    /// `WebDriverError::NoSuchElement` is returned. Any other error, such as a
+    /// `StaleElementReference` for this element, is returned immediately.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let child_elem = elem.find_with_timeout(By::Tag("button"), Duration::from_secs(2)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn find_with_timeout(
+        &self,
+        by: By,
+        timeout: Duration,
+    ) -> WebDriverResult<WebElement> {
+        let mut poller = ElementPollerWithTimeout::new(timeout, DEFAULT_POLL_INTERVAL);
+        loop {
+            match self.find(by.clone()).await {
+                Err(WebDriverError::NoSuchElement(e)) => {
+                    if !poller.tick().await {
+                        return Err(WebDriverError::NoSuchElement(e));
+                    }
+                }
+                r => return r,
+            }
+        }
+    }
+
+    /// Search for a child element of this WebElement using the specified selector.
+    #[deprecated(since = "0.30.0", note = "This method has been renamed to find()")]
+    pub async fn find_element(&self, by: By) -> WebDriverResult<WebElement> {
+        self.find(by).await
+    }
+
+    /// Search for all child elements of this WebElement that match the specified selector.
+    ///
+    /// **NOTE**: For more powerful element queries including polling and filters, see the
+    /// [`WebElement::query`] method instead.
+    ///
+    /// [`WebElement::query`]: crate::extensions::query::ElementQueryable::query
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let child_elems = elem.find_all(By::Tag("button")).await?;
+    /// for child_elem in child_elems {
     ///     assert_eq!(child_elem.tag_name().await?, "button");
     /// }
     /// #         driver.quit().await?;
@@ -584,10 +1221,10 @@ impl WebElement {
     /// #     })
     /// # }
     /// ```
-    pub async fn find_all(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
+    pub async fn find_all(&self, by: impl Into<By>) -> WebDriverResult<Vec<WebElement>> {
         let r = self
             .handle
-            .cmd(Command::FindElementsFromElement(self.element_id.clone(), by.into()))
+            .cmd(Command::FindElementsFromElement(self.element_id.clone(), by.into().into()))
             .await?;
         r.elements(self.handle.clone())
     }
@@ -600,6 +1237,369 @@ impl WebElement {
 
     /// Send the specified input.
     ///
+    /// The keys are typed at the current cursor position, which is usually the end of
+    /// any existing text, so this appends to the element's value rather than replacing
+    /// it. Use `type_replacing()` to replace the existing text.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.send_keys("thirtyfour").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// You can also send special key combinations like this:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.send_keys("selenium").await?;
+    /// elem.send_keys(Key::Control + "a").await?;
+    /// elem.send_keys("thirtyfour" + Key::Enter).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn send_keys(&self, key: impl Into<TypingData>) -> WebDriverResult<()> {
+        self.handle.cmd(Command::ElementSendKeys(self.element_id.clone(), key.into())).await?;
+        Ok(())
+    }
+
+    /// Type the specified text into this WebElement one character at a time, waiting
+    /// for `delay` between characters.
+    ///
+    /// This is for inputs that drop characters when typed too quickly, such as
+    /// rate-limited or debounced autocomplete fields. Each character is sent with a
+    /// separate `send_keys()` call, so the event handlers for each keystroke have run
+    /// before the next one is typed.
+    ///
+    /// **NOTE:** This is much slower than `send_keys()`, since it sends one command per
+    /// character in addition to the delays. Also, because modifier keys are released
+    /// after each command, key combinations such as `Key::Control + "a"` will not work.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("search")).await?;
+    /// elem.send_keys_slowly("thirtyfour", Duration::from_millis(100)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn send_keys_slowly(
+        &self,
+        text: impl Into<TypingData>,
+        delay: Duration,
+    ) -> WebDriverResult<()> {
+        for (i, c) in text.into().as_vec().into_iter().enumerate() {
+            if i > 0 {
+                sleep(delay).await;
+            }
+            self.send_keys(c.to_string()).await?;
+        }
+        Ok(())
+    }
+
+    /// Clear this WebElement and then type the specified text, replacing any existing
+    /// text rather than appending to it like `send_keys()`.
+    ///
+    /// This first uses `clear()`. Some frameworks restore the previous value because
+    /// `clear()` does not send any key events, so if the element still has a value, it
+    /// is selected with `Ctrl+A` and deleted with `Backspace` before typing. If that does
+    /// not work either, for example on macOS, the same is tried with `Cmd+A`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.type_replacing("thirtyfour").await?;
+    /// assert_eq!(elem.value().await?, Some("thirtyfour".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn type_replacing(&self, text: impl Into<TypingData>) -> WebDriverResult<()> {
+        self.clear().await?;
+        for select_all in [Key::Control, Key::Command] {
+            if self.value().await?.unwrap_or_default().is_empty() {
+                break;
+            }
+            self.send_keys(select_all + "a").await?;
+            self.send_keys(Key::Backspace).await?;
+        }
+        self.send_keys(text).await
+    }
+
+    /// Take a screenshot of this WebElement and return it as PNG, base64 encoded.
+    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
+        self.handle.cmd(Command::TakeElementScreenshot(self.element_id.clone())).await?.value()
+    }
+
+    /// Take a screenshot of this WebElement and return it as PNG bytes.
+    ///
+    /// If the browser returns an empty image (this can happen when the element is still
+    /// being rendered), the screenshot will be retried up to `screenshot_retries` times
+    /// as specified in the [`WebDriverConfig`]. With the `image` feature, an image that
+    /// cannot be decoded as PNG is retried too. If every attempt fails, the last image is
+    /// returned as is.
+    ///
+    /// [`WebDriverConfig`]: crate::common::config::WebDriverConfig
+    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        let mut retries = self.handle.config().screenshot_retries;
+        loop {
+            let png = base64_decode(&self.screenshot_as_png_base64().await?)?;
+            #[cfg(feature = "image")]
+            let complete = !png.is_empty() && crate::png::decode(&png).is_ok();
+            #[cfg(not(feature = "image"))]
+            let complete = !png.is_empty();
+            if complete || retries == 0 {
+                return Ok(png);
+            }
+            retries -= 1;
+            sleep(SCREENSHOT_RETRY_DELAY).await;
+        }
+    }
+
+    /// Take a screenshot of this WebElement and write it to the specified filename.
+    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.screenshot_as_png().await?;
+        let mut file = File::create(path).await?;
+        file.write_all(&png).await?;
+        Ok(())
+    }
+
+    /// Take a screenshot of this WebElement and compare it against a baseline image,
+    /// pixel by pixel.
+    ///
+    /// Two pixels match if none of their RGBA channels differ by more than `tolerance`,
+    /// as a fraction of the full range, so `0.0` requires an exact match and `0.1` allows
+    /// each channel to differ by up to 10%. If the screenshot and the baseline differ in
+    /// size, the result is a non-match rather than an error. See [`DiffResult`] for details.
+    ///
+    /// This method requires the `image` feature.
+    ///
+    /// [`DiffResult`]: crate::DiffResult
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("logo")).await?;
+    /// let baseline = thirtyfour::image::open("baseline/logo.png")?;
+    /// let result = elem.screenshot_matches(&baseline, 0.05).await?;
+    /// if !result.matches {
+    ///     result.diff_image.save("logo-diff.png")?;
+    /// }
+    /// assert!(result.matches, "{} pixels differ", result.diff_pixels);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn screenshot_matches(
+        &self,
+        baseline: &image::DynamicImage,
+        tolerance: f32,
+    ) -> WebDriverResult<crate::DiffResult> {
+        let image = crate::png::decode(&self.screenshot_as_png().await?)?;
+        Ok(crate::png::diff(&image, baseline, tolerance))
+    }
+
+    /// Get the contents of this `<canvas>` element as PNG bytes.
+    ///
+    /// This uses the canvas `toDataURL()` method, so it captures exactly what has been
+    /// drawn on the canvas at its own resolution, regardless of any elements covering it
+    /// or its position on the screen. Returns `WebDriverError::InvalidArgument` if this
+    /// element is not a canvas. A canvas containing cross-origin images is "tainted" and
+    /// cannot be read, which results in a `WebDriverError::JavaScript` error.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let canvas = driver.find(By::Css("canvas#chart")).await?;
+    /// let png = canvas.canvas_as_png().await?;
+    /// std::fs::write("chart.png", png)?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn canvas_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        let data_url: Option<String> =
+            self.handle.execute(CANVAS_TO_DATA_URL, vec![self.to_json()?]).await?.value_opt()?;
+        let data_url = data_url.ok_or_else(|| {
+            WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "element is not a <canvas> element".to_string(),
+            ))
+        })?;
+        match data_url.strip_prefix("data:image/png;base64,") {
+            Some(data) => base64_decode(data),
+            // An empty canvas returns "data:,".
+            None => Err(WebDriverError::ParseError(format!(
+                "canvas did not return PNG data: {}",
+                data_url.chars().take(40).collect::<String>()
+            ))),
+        }
+    }
+
+    /// Get the contents of this `<canvas>` element as an image.
+    ///
+    /// This is the same as `canvas_as_png()`, but decodes the PNG data.
+    /// This method requires the `image` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let canvas = driver.find(By::Css("canvas#chart")).await?;
+    /// let image = canvas.canvas_as_image().await?;
+    /// assert_eq!(image.width(), 300);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn canvas_as_image(&self) -> WebDriverResult<image::DynamicImage> {
+        crate::png::decode(&self.canvas_as_png().await?)
+    }
+
+    /// Execute the specified Javascript synchronously with this element as `arguments[0]`.
+    ///
+    /// Any additional arguments are appended after the element, starting at `arguments[1]`.
+    /// See [`SessionHandle::execute`] for more details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let height: i64 = elem.execute("return arguments[0].scrollHeight;", vec![]).await?.convert()?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn execute(
+        &self,
+        script: impl IntoArcStr,
+        extra_args: Vec<Value>,
+    ) -> WebDriverResult<ScriptRet> {
+        let mut args = Vec::with_capacity(extra_args.len() + 1);
+        args.push(self.to_json()?);
+        args.extend(extra_args);
+        self.handle.execute(script, args).await
+    }
+
+    /// Focus this WebElement using JavaScript.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.focus().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn focus(&self) -> WebDriverResult<()> {
+        self.handle.execute(r#"arguments[0].focus();"#, vec![self.to_json()?]).await?;
+        Ok(())
+    }
+
+    /// Remove focus from this element using JavaScript.
+    ///
+    /// This fires the `blur` event, for example to trigger validation that runs when the
+    /// user leaves a field. It does nothing if the element does not have focus.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.focus().await?;
+    /// elem.blur().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn blur(&self) -> WebDriverResult<()> {
+        self.handle.execute(r#"arguments[0].blur();"#, vec![self.to_json()?]).await?;
+        Ok(())
+    }
+
+    /// Dispatch a synthetic event of the specified type, such as `input`, `change` or
+    /// `blur`, on this element using JavaScript.
+    ///
+    /// The event bubbles and is cancelable. This is useful when a page relies on
+    /// events that `send_keys()` does not always fire.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -610,55 +1610,47 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Name("input1")).await?;
-    /// elem.send_keys("thirtyfour").await?;
+    /// elem.dispatch_event("blur").await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
+    pub async fn dispatch_event(&self, event_type: &str) -> WebDriverResult<()> {
+        let args = vec![self.to_json()?, Value::String(event_type.to_string())];
+        self.handle.execute(DISPATCH_EVENT, args).await?;
+        Ok(())
+    }
+
+    /// Set the value of this element using JavaScript, and then dispatch the `input`
+    /// and `change` events.
     ///
-    /// You can also send special key combinations like this:
+    /// This is the reliable way to populate controlled inputs, such as those in React,
+    /// which ignore a value that is set without the corresponding events.
+    ///
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// #
     /// # fn main() -> WebDriverResult<()> {
-    /// block_on(async {
+    /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Name("input1")).await?;
-    /// elem.send_keys("selenium").await?;
-    /// elem.send_keys(Key::Control + "a").await?;
-    /// elem.send_keys("thirtyfour" + Key::Enter).await?;
+    /// elem.set_value("selenium").await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn send_keys(&self, key: impl Into<TypingData>) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementSendKeys(self.element_id.clone(), key.into())).await?;
-        Ok(())
-    }
-
-    /// Take a screenshot of this WebElement and return it as PNG, base64 encoded.
-    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::TakeElementScreenshot(self.element_id.clone())).await?.value()
-    }
-
-    /// Take a screenshot of this WebElement and return it as PNG bytes.
-    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
-        base64_decode(&self.screenshot_as_png_base64().await?)
-    }
-
-    /// Take a screenshot of this WebElement and write it to the specified filename.
-    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
-        let png = self.screenshot_as_png().await?;
-        let mut file = File::create(path).await?;
-        file.write_all(&png).await?;
+    pub async fn set_value(&self, value: &str) -> WebDriverResult<()> {
+        let args = vec![self.to_json()?, Value::String(value.to_string())];
+        self.handle.execute(SET_VALUE, args).await?;
         Ok(())
     }
 
-    /// Focus this WebElement using JavaScript.
+    /// Scroll this element into view using JavaScript.
     ///
     /// # Example:
     /// ```no_run
@@ -669,19 +1661,30 @@ impl WebElement {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// elem.focus().await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// elem.scroll_into_view().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn focus(&self) -> WebDriverResult<()> {
-        self.handle.execute(r#"arguments[0].focus();"#, vec![self.to_json()?]).await?;
+    pub async fn scroll_into_view(&self) -> WebDriverResult<()> {
+        self.handle
+            .execute(
+                r#"arguments[0].scrollIntoView({block: "center", inline: "center"});"#,
+                vec![self.to_json()?],
+            )
+            .await?;
         Ok(())
     }
 
-    /// Scroll this element into view using JavaScript.
+    /// Scroll this element into view and return the viewport-relative coordinates
+    /// of its top-left corner, as `(x, y)`.
+    ///
+    /// These coordinates are suitable for use with pointer actions that use the
+    /// viewport as their origin. Note that this differs from [`WebElement::rect()`],
+    /// which may be relative to the document rather than the viewport in some
+    /// driver implementations.
     ///
     /// # Example:
     /// ```no_run
@@ -693,20 +1696,25 @@ impl WebElement {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// elem.scroll_into_view().await?;
+    /// let (x, y) = elem.location_in_view().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn scroll_into_view(&self) -> WebDriverResult<()> {
-        self.handle
+    pub async fn location_in_view(&self) -> WebDriverResult<(i64, i64)> {
+        let ret = self
+            .handle
             .execute(
-                r#"arguments[0].scrollIntoView({block: "center", inline: "center"});"#,
+                r#"
+                arguments[0].scrollIntoView({block: "center", inline: "center"});
+                let rect = arguments[0].getBoundingClientRect();
+                return [Math.round(rect.left), Math.round(rect.top)];
+                "#,
                 vec![self.to_json()?],
             )
             .await?;
-        Ok(())
+        ret.convert()
     }
 
     /// Get the innerHtml property of this element.
@@ -815,6 +1823,34 @@ impl WebElement {
         Ok(())
     }
 
+    /// Drag the element to a target element using native HTML5 drag events.
+    ///
+    /// This dispatches `dragstart`, `dragenter`, `dragover`, `drop` and `dragend`
+    /// events that share a single `DataTransfer` object, so handlers that call
+    /// `dataTransfer.setData()` and `dataTransfer.getData()` work as expected.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("draggable")).await?;
+    /// let target = driver.find(By::Id("target")).await?;
+    /// elem.html5_drag_to(&target).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn html5_drag_to(&self, target: &Self) -> WebDriverResult<()> {
+        self.handle.execute(HTML5_DRAG_AND_DROP, vec![self.to_json()?, target.to_json()?]).await?;
+        Ok(())
+    }
+
     /// Get the parent of the WebElement.
     ///
     /// # Example
@@ -836,6 +1872,163 @@ impl WebElement {
     pub async fn parent(&self) -> WebDriverResult<Self> {
         self.find(By::XPath("./..")).await
     }
+
+    /// Read the named element property (such as `nextElementSibling`) that refers to
+    /// another element, returning `None` if it is not set.
+    async fn relative(&self, property: &str) -> WebDriverResult<Option<Self>> {
+        let ret = self
+            .handle
+            .execute(ELEMENT_RELATIVE, vec![self.to_json()?, Value::String(property.to_string())])
+            .await?;
+        match ret.is_null() {
+            true => Ok(None),
+            false => ret.element().map(Some),
+        }
+    }
+
+    /// Get the parent element of the WebElement, or `None` if this is the root element.
+    ///
+    /// Unlike `WebElement::parent()`, this reads the `parentElement` property, so the
+    /// root `<html>` element gives `None` rather than an error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("child")).await?;
+    /// if let Some(parent) = elem.parent_element().await? {
+    ///     println!("parent: {}", parent.tag_name().await?);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn parent_element(&self) -> WebDriverResult<Option<Self>> {
+        self.relative("parentElement").await
+    }
+
+    /// Get the nearest positioned ancestor of the WebElement, as given by the
+    /// `offsetParent` property.
+    ///
+    /// Returns `None` if the element is hidden, is fixed position, or is the `<body>`
+    /// or root element.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("tooltip")).await?;
+    /// let container = elem.offset_parent().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn offset_parent(&self) -> WebDriverResult<Option<Self>> {
+        self.relative("offsetParent").await
+    }
+
+    /// Get the child elements of the WebElement, in document order.
+    ///
+    /// Text and comment nodes are not included.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let list = driver.find(By::Tag("ul")).await?;
+    /// for item in list.children().await? {
+    ///     println!("{}", item.text().await?);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn children(&self) -> WebDriverResult<Vec<Self>> {
+        self.handle.execute(ELEMENT_CHILDREN, vec![self.to_json()?]).await?.elements()
+    }
+
+    /// Get the next sibling element of the WebElement, or `None` if it is the last child.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let label = driver.find(By::Css("label[for='name']")).await?;
+    /// let next = label.next_sibling().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn next_sibling(&self) -> WebDriverResult<Option<Self>> {
+        self.relative("nextElementSibling").await
+    }
+
+    /// Get the previous sibling element of the WebElement, or `None` if it is the
+    /// first child.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let input = driver.find(By::Id("name")).await?;
+    /// let label = input.previous_sibling().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn previous_sibling(&self) -> WebDriverResult<Option<Self>> {
+        self.relative("previousElementSibling").await
+    }
+}
+
+/// The reason `WebElement::wait_and_click()` could not click the element yet.
+#[derive(Debug)]
+enum ClickBlocker {
+    NotDisplayed,
+    NotEnabled,
+    Obscured(String),
+    Intercepted(String),
+}
+
+impl fmt::Display for ClickBlocker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClickBlocker::NotDisplayed => write!(f, "element is not displayed"),
+            ClickBlocker::NotEnabled => write!(f, "element is not enabled"),
+            ClickBlocker::Obscured(by) => write!(f, "element is covered by {by}"),
+            ClickBlocker::Intercepted(message) => write!(f, "click was intercepted: {message}"),
+        }
+    }
 }
 
 impl fmt::Display for WebElement {
@@ -844,11 +2037,278 @@ impl fmt::Display for WebElement {
     }
 }
 
+/// A `WebElement` serializes as a W3C element reference object, which allows elements
+/// to be supplied as script arguments (including inside other serializable values).
+/// See `WebDriverConfig::legacy_element_key` for drivers that need the legacy key.
+///
+/// A serialized reference is only valid within the document the element was found in.
+/// Use [`WebElementSeed`] or [`WebElement::from_reference`] to restore it.
 impl Serialize for WebElement {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        self.element_id.serialize(serializer)
+        self.element_ref().serialize(serializer)
+    }
+}
+
+/// Deserialize a `WebElement` from a W3C element reference object (or one using the
+/// legacy `ELEMENT` key), attaching it to the specified session.
+///
+/// A `WebElement` cannot implement `Deserialize` directly because it needs a session
+/// handle, so this seed supplies one.
+///
+/// **NOTE**: An element reference is only valid within the document it was found in.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use serde::de::DeserializeSeed;
+/// use thirtyfour::WebElementSeed;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let elem = driver.find(By::Id("my-element-id")).await?;
+/// let stored = serde_json::to_string(&elem)?;
+///
+/// // Later, in the same document...
+/// let mut de = serde_json::Deserializer::from_str(&stored);
+/// let restored = WebElementSeed::new(driver.handle.clone()).deserialize(&mut de)?;
+/// assert_eq!(restored, elem);
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebElementSeed {
+    handle: Arc<SessionHandle>,
+}
+
+impl WebElementSeed {
+    /// Create a new `WebElementSeed` that attaches elements to the specified session.
+    pub fn new(handle: Arc<SessionHandle>) -> Self {
+        Self {
+            handle,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for WebElementSeed {
+    type Value = WebElement;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let element_ref = ElementRef::deserialize(deserializer)?;
+        Ok(WebElement::from_reference(self.handle, element_ref.id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::WebDriverConfig;
+    use crate::session::http::test_client::{MockClient, Reply};
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    /// Responds to scripts with `value`, and to tag name requests with `div`.
+    fn element_client(value: Value) -> Arc<MockClient> {
+        Arc::new(MockClient::new().reply("/execute/sync", value).reply("/name", json!("div")))
+    }
+
+    #[tokio::test]
+    async fn test_element_reference_round_trip() {
+        let client = element_client(json!({"element-6066-11e4-a52e-4f735466cecf": "abc-123"}));
+        let handle = client.handle();
+        let elem = WebElement::from_reference(handle.clone(), "abc-123");
+        let value = serde_json::to_value(&elem).unwrap();
+        assert_eq!(value, json!({"element-6066-11e4-a52e-4f735466cecf": "abc-123"}));
+
+        let ret = handle.execute("return window.saved;", Vec::new()).await.unwrap();
+        let restored = WebElementSeed::new(handle).deserialize(ret.json()).unwrap();
+        assert_eq!(restored, elem);
+        assert_eq!(restored.element_id().to_string(), "abc-123");
+        assert_eq!(restored.tag_name().await.unwrap(), "div");
+        assert_eq!(client.paths().last().unwrap(), "/session/test/element/abc-123/name");
+    }
+
+    #[tokio::test]
+    async fn test_attr_required() {
+        let client = MockClient::new()
+            .reply("/attribute/data-present", json!("yes"))
+            .reply("/attribute/data-missing", Reply::null());
+        let elem = WebElement::from_reference(Arc::new(client).handle(), "abc-123");
+        assert_eq!(elem.attr_required("data-present").await.unwrap(), "yes");
+        match elem.attr_required("data-missing").await {
+            Err(
+                e @ WebDriverError::MissingAttribute {
+                    ..
+                },
+            ) => {
+                let msg = e.to_string();
+                assert!(msg.contains("abc-123") && msg.contains("'data-missing'"), "{msg}");
+            }
+            x => panic!("expected missing attribute error, got {x:?}"),
+        }
+    }
+
+    /// Responds with the tag name `div` `remaining` times, and then with the specified error.
+    fn detaching_element(remaining: usize, error: &'static str) -> WebElement {
+        let tag_names = std::iter::repeat_n(Reply::Value(json!("div")), remaining);
+        let client =
+            MockClient::new().sequence("", tag_names.chain([Reply::error(404, error, "")]));
+        WebElement::from_reference(Arc::new(client).handle(), "abc-123")
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_stale() {
+        let timeout = Duration::from_secs(5);
+        detaching_element(1, "stale element reference").wait_until_stale(timeout).await.unwrap();
+
+        let result = detaching_element(0, "no such window").wait_until_stale(timeout).await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchWindow(_))), "{result:?}");
+
+        let client = Arc::new(MockClient::new().reply("", json!("div")));
+        let elem = WebElement::from_reference(client.handle(), "abc-123");
+        let result = elem.wait_until_stale(Duration::from_millis(100)).await;
+        assert!(matches!(result, Err(WebDriverError::Timeout(_))), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_element_reference() {
+        let config = WebDriverConfig::builder().legacy_element_key(true).build().unwrap();
+        let client = element_client(json!({"ELEMENT": "abc-123"}));
+        let handle = client.handle_with_config(config);
+        let elem = WebElement::from_reference(handle.clone(), "abc-123");
+        assert_eq!(serde_json::to_value(&elem).unwrap(), json!({"ELEMENT": "abc-123"}));
+        assert_eq!(elem.to_json().unwrap(), json!({"ELEMENT": "abc-123"}));
+
+        let ret = handle.execute("return window.saved;", Vec::new()).await.unwrap();
+        let restored = WebElementSeed::new(handle).deserialize(ret.json()).unwrap();
+        assert_eq!(restored, elem);
+        assert_eq!(restored.tag_name().await.unwrap(), "div");
+    }
+
+    #[tokio::test]
+    async fn test_element_equality_across_scripts() {
+        // Respond to scripts with the same element, using the W3C and the legacy key in turn.
+        let client = Arc::new(MockClient::new().sequence(
+            "/execute/sync",
+            [
+                json!({ "element-6066-11e4-a52e-4f735466cecf": "abc-123" }),
+                json!({ "ELEMENT": "abc-123" }),
+            ],
+        ));
+        let session = |id: &str| {
+            let handle = SessionHandle::new(client.clone(), "http://localhost:4444", id.into());
+            Arc::new(handle.unwrap())
+        };
+        let handle = session("test");
+        let first = handle.execute("return document.body;", Vec::new()).await;
+        let second = handle.execute("return document.activeElement;", Vec::new()).await;
+        let (first, second) = (first.unwrap().element().unwrap(), second.unwrap().element());
+        assert_eq!(first, second.unwrap());
+        assert_eq!(first, WebElement::from_reference(handle, "abc-123"));
+
+        // The same reference in another session is a different element.
+        assert_ne!(first, WebElement::from_reference(session("other"), "abc-123"));
+    }
+
+    #[tokio::test]
+    async fn test_element_seed_invalid() {
+        let handle = element_client(json!({"id": "abc"})).handle();
+        let ret = handle.execute("return window.saved;", Vec::new()).await.unwrap();
+        assert!(WebElementSeed::new(handle).deserialize(ret.json()).is_err());
+    }
+
+    /// Reports the element as disabled for the first `disabled` checks, and intercepts the
+    /// first `intercepted` clicks.
+    fn click_element(
+        disabled: u32,
+        intercepted: u32,
+        obscured_by: Option<&'static str>,
+    ) -> WebElement {
+        let (disabled, intercepted) = (Mutex::new(disabled), Mutex::new(intercepted));
+        let client = MockClient::new()
+            .reply("/displayed", json!(true))
+            .route("/enabled", move |_| json!(!countdown(&disabled)))
+            .reply("/execute/sync", json!(obscured_by))
+            .route("/click", move |_| match countdown(&intercepted) {
+                true => Reply::error(
+                    400,
+                    "element click intercepted",
+                    "other element would receive the click: <div id=\"overlay\">",
+                ),
+                false => Reply::null(),
+            });
+        WebElement::from_reference(Arc::new(client).handle(), "abc-123")
+    }
+
+    fn countdown(count: &Mutex<u32>) -> bool {
+        let mut count = count.lock();
+        let active = *count > 0;
+        *count = count.saturating_sub(1);
+        active
+    }
+
+    #[tokio::test]
+    async fn test_wait_and_click() {
+        let timeout = Duration::from_secs(5);
+        click_element(1, 1, None).wait_and_click(timeout).await.unwrap();
+
+        let timeout = Duration::ZERO;
+        let result = click_element(u32::MAX, 0, None).wait_and_click(timeout).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("not enabled"), "{msg}"),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+
+        let result = click_element(0, 0, Some("div#overlay")).wait_and_click(timeout).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => {
+                assert!(msg.contains("covered by div#overlay"), "{msg}")
+            }
+            x => panic!("expected timeout, got {x:?}"),
+        }
+
+        let result = click_element(0, u32::MAX, None).wait_and_click(timeout).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("intercepted"), "{msg}"),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+    }
+
+    fn screenshot_element(screenshots: Vec<&[u8]>) -> (Arc<MockClient>, WebElement) {
+        let replies = screenshots.into_iter().map(|png| json!(crate::support::base64_encode(png)));
+        let client = Arc::new(MockClient::new().sequence("/screenshot", replies));
+        (client.clone(), WebElement::from_reference(client.handle(), "abc-123"))
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_retries_empty_image() {
+        let (client, elem) = screenshot_element(vec![b"", b""]);
+        assert!(elem.screenshot_as_png().await.unwrap().is_empty());
+        assert_eq!(client.count("/screenshot"), 4);
+
+        let config = WebDriverConfig::builder().screenshot_retries(0).build().unwrap();
+        let client = Arc::new(MockClient::new().reply("/screenshot", json!("")));
+        let elem = WebElement::from_reference(client.handle_with_config(config), "abc-123");
+        assert!(elem.screenshot_as_png().await.unwrap().is_empty());
+        assert_eq!(client.count("/screenshot"), 1);
+    }
+
+    #[cfg(feature = "image")]
+    #[tokio::test]
+    async fn test_screenshot_retries_invalid_image() {
+        let png = crate::png::encode(&image::DynamicImage::new_rgb8(2, 2)).unwrap();
+        let (client, elem) = screenshot_element(vec![b"", b"not a png", &png]);
+        assert_eq!(elem.screenshot_as_png().await.unwrap(), png);
+        assert_eq!(client.count("/screenshot"), 3);
     }
 }
@@ -0,0 +1,260 @@
+use crate::error::WebDriverResult;
+use crate::session::handle::SessionHandle;
+use crate::session::scriptret::ScriptRet;
+use serde_json::Value;
+use std::sync::Arc;
+
+const STORAGE_KEYS: &str = r#"
+var keys = [];
+for (var i = 0; i < storage.length; i++) {
+    keys.push(storage.key(i));
+}
+return keys;"#;
+
+/// The kind of web storage accessed by a [`WebStorage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// The `window.localStorage` object.
+    Local,
+    /// The `window.sessionStorage` object.
+    Session,
+}
+
+impl StorageKind {
+    fn js_name(self) -> &'static str {
+        match self {
+            StorageKind::Local => "localStorage",
+            StorageKind::Session => "sessionStorage",
+        }
+    }
+}
+
+/// Struct for reading and writing `localStorage` or `sessionStorage` for
+/// the current page.
+///
+/// Each method is implemented with a small JavaScript evaluation against the
+/// current browsing context, so the values seen are those of the origin of the
+/// currently loaded page.
+///
+/// See `WebDriver::local_storage()` and `WebDriver::session_storage()`.
+#[derive(Debug, Clone)]
+pub struct WebStorage {
+    handle: Arc<SessionHandle>,
+    kind: StorageKind,
+}
+
+impl WebStorage {
+    /// Create a new WebStorage struct. This is typically created internally
+    /// via a call to `WebDriver::local_storage()` or `WebDriver::session_storage()`.
+    pub fn new(handle: Arc<SessionHandle>, kind: StorageKind) -> Self {
+        Self {
+            handle,
+            kind,
+        }
+    }
+
+    /// The kind of storage this struct accesses.
+    pub fn kind(&self) -> StorageKind {
+        self.kind
+    }
+
+    async fn run(&self, body: &str, mut args: Vec<Value>) -> WebDriverResult<ScriptRet> {
+        let script = format!("var storage = window[arguments[0]]; {body}");
+        args.insert(0, Value::String(self.kind.js_name().to_string()));
+        self.handle.execute(script, args).await
+    }
+
+    /// Get the value stored for the specified key, or `None` if the key is not present.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let token = driver.local_storage().get("token").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get(&self, key: impl Into<String>) -> WebDriverResult<Option<String>> {
+        self.run("return storage.getItem(arguments[1]);", vec![Value::String(key.into())])
+            .await?
+            .value_opt()
+    }
+
+    /// Store the specified value for the specified key.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.session_storage().set("token", "abc123").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> WebDriverResult<()> {
+        self.run(
+            "storage.setItem(arguments[1], arguments[2]);",
+            vec![Value::String(key.into()), Value::String(value.into())],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the specified key. This does nothing if the key is not present.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.local_storage().remove("token").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn remove(&self, key: impl Into<String>) -> WebDriverResult<()> {
+        self.run("storage.removeItem(arguments[1]);", vec![Value::String(key.into())]).await?;
+        Ok(())
+    }
+
+    /// Remove all keys.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.local_storage().clear().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn clear(&self) -> WebDriverResult<()> {
+        self.run("storage.clear();", Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Get all keys currently stored.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// for key in driver.local_storage().keys().await? {
+    ///     println!("{key}");
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn keys(&self) -> WebDriverResult<Vec<String>> {
+        self.run(STORAGE_KEYS, Vec::new()).await?.convert()
+    }
+
+    /// Get the number of keys currently stored.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let count = driver.session_storage().len().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn len(&self) -> WebDriverResult<usize> {
+        self.run("return storage.length;", Vec::new()).await?.convert()
+    }
+
+    /// Return true if no keys are currently stored.
+    pub async fn is_empty(&self) -> WebDriverResult<bool> {
+        Ok(self.len().await? == 0)
+    }
+}
+
+impl SessionHandle {
+    /// Access `localStorage` for the current page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let storage = driver.local_storage();
+    /// storage.set("token", "abc123").await?;
+    /// assert_eq!(storage.get("token").await?, Some("abc123".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn local_storage(self: &Arc<SessionHandle>) -> WebStorage {
+        WebStorage::new(self.clone(), StorageKind::Local)
+    }
+
+    /// Access `sessionStorage` for the current page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let storage = driver.session_storage();
+    /// storage.set("token", "abc123").await?;
+    /// assert_eq!(storage.get("token").await?, Some("abc123".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn session_storage(self: &Arc<SessionHandle>) -> WebStorage {
+        WebStorage::new(self.clone(), StorageKind::Session)
+    }
+}
@@ -20,16 +20,17 @@ pub fn make_capabilities(s: &str) -> Capabilities {
     match s {
         "firefox" => {
             let mut caps = DesiredCapabilities::firefox();
-            caps.set_headless().unwrap();
+            caps.set_headless(true).unwrap();
             caps.into()
         }
         "chrome" => {
             let mut caps = DesiredCapabilities::chrome();
-            caps.set_headless().unwrap();
+            caps.set_headless(true).unwrap();
             caps.set_no_sandbox().unwrap();
             caps.set_disable_gpu().unwrap();
             caps.set_disable_dev_shm_usage().unwrap();
             caps.add_arg("--no-sandbox").unwrap();
+            caps.enable_performance_logging().unwrap();
             caps.into()
         }
         browser => unimplemented!("unsupported browser backend {}", browser),
@@ -45,6 +46,12 @@ pub fn webdriver_url(s: &str) -> String {
     }
 }
 
+/// A response that takes a while to arrive, for testing requests that are in flight.
+async fn slow_response() -> &'static str {
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    "slow"
+}
+
 /// Starts the web server.
 pub fn start_server() -> Arc<JoinHandle<()>> {
     SERVER
@@ -57,7 +64,8 @@ pub fn start_server() -> Arc<JoinHandle<()>> {
                     let addr = SocketAddr::from(([127, 0, 0, 1], PORT));
                     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
                     let app = axum::Router::new()
-                        .nest_service("/", tower_http::services::ServeDir::new(ASSETS_DIR));
+                        .route("/slow", axum::routing::get(slow_response))
+                        .fallback_service(tower_http::services::ServeDir::new(ASSETS_DIR));
                     axum::serve(listener, app).await.unwrap();
                 });
             });
@@ -172,3 +180,43 @@ pub fn other_page_url() -> String {
 pub fn drag_to_url() -> String {
     format!("http://localhost:{PORT}/drag_to.html")
 }
+
+pub fn named_frames_url() -> String {
+    format!("http://localhost:{PORT}/named_frames.html")
+}
+
+pub fn pagination_url() -> String {
+    format!("http://localhost:{PORT}/pagination.html")
+}
+
+pub fn layout_url() -> String {
+    format!("http://localhost:{PORT}/layout.html")
+}
+
+pub fn controlled_input_url() -> String {
+    format!("http://localhost:{PORT}/controlled_input.html")
+}
+
+pub fn validation_url() -> String {
+    format!("http://localhost:{PORT}/validation.html")
+}
+
+pub fn network_url() -> String {
+    format!("http://localhost:{PORT}/network.html")
+}
+
+pub fn network_inflight_url() -> String {
+    format!("http://localhost:{PORT}/network_inflight.html")
+}
+
+pub fn prefilled_input_url() -> String {
+    format!("http://localhost:{PORT}/prefilled_input.html")
+}
+
+pub fn delayed_enable_url() -> String {
+    format!("http://localhost:{PORT}/delayed_enable.html")
+}
+
+pub fn canvas_url() -> String {
+    format!("http://localhost:{PORT}/canvas.html")
+}
@@ -27,3 +27,24 @@ fn drag_to(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn html5_drag_to(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let drag_to_url = drag_to_url();
+        c.goto(&drag_to_url).await?;
+
+        // Validate we are starting with a div and an image that are adjacent to one another.
+        c.find(By::XPath("//div[@id='target']/../img[@id='draggable']")).await?;
+
+        // Drag the image to the target div using native drag events.
+        let elem = c.find(By::Id("draggable")).await?;
+        let target = c.find(By::Id("target")).await?;
+        elem.html5_drag_to(&target).await?;
+
+        // Validate that the image was moved into the target div
+        c.find(By::XPath("//div[@id='target']/img[@id='draggable']")).await?;
+        Ok(())
+    })
+}
@@ -1,8 +1,13 @@
 //! Element tests
 use crate::common::sample_page_url;
+use assert_matches::assert_matches;
 use common::*;
 use rstest::rstest;
-use thirtyfour::{prelude::*, support::block_on};
+use serde::de::DeserializeSeed;
+use std::time::Duration;
+#[cfg(feature = "image")]
+use thirtyfour::image;
+use thirtyfour::{prelude::*, support::block_on, WebElementSeed};
 
 mod common;
 
@@ -42,6 +47,22 @@ fn element_attr(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn element_attr_required(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let elem = c.find(By::Id("checkbox-option-1")).await?;
+        assert_eq!(elem.attr_required("id").await?, "checkbox-option-1");
+        assert_matches!(
+            elem.attr_required("invalid-attribute").await,
+            Err(WebDriverError::MissingAttribute { name, .. }) if name == "invalid-attribute"
+        );
+        Ok(())
+    })
+}
+
 #[rstest]
 fn element_prop(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -130,6 +151,70 @@ fn element_rect(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn element_rect_device_pixels(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let elem = c.find(By::Id("button-alert")).await?;
+        let ratio: f64 = c.execute("return window.devicePixelRatio;", vec![]).await?.convert()?;
+        let rect = elem.rect().await?;
+        let device_rect = elem.rect_device_pixels().await?;
+        assert_eq!(device_rect.x, rect.x * ratio);
+        assert_eq!(device_rect.y, rect.y * ratio);
+        assert_eq!(device_rect.width, rect.width * ratio);
+        assert_eq!(device_rect.height, rect.height * ratio);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_rects_of(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let ids = ["footer", "button-alert", "text-input", "button-copy"];
+        let mut elements = Vec::new();
+        for id in ids {
+            elements.push(c.find(By::Id(id)).await?);
+        }
+
+        let rects = c.rects_of(&elements).await?;
+        assert_eq!(rects.len(), elements.len());
+        for (elem, rect) in elements.iter().zip(&rects) {
+            let expected = elem.rect().await?;
+            assert!((rect.x - expected.x).abs() < 1.0);
+            assert!((rect.y - expected.y).abs() < 1.0);
+            assert!((rect.width - expected.width).abs() < 1.0);
+            assert!((rect.height - expected.height).abs() < 1.0);
+        }
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_send_keys_unicode(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    let browser = test_harness.browser().to_string();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        elem.send_keys("héllo ✓").await?;
+        assert_eq!(elem.value().await?.unwrap(), "héllo ✓");
+
+        // chromedriver only supports characters in the Basic Multilingual Plane.
+        if browser != "chrome" {
+            elem.clear().await?;
+            elem.send_keys("👍").await?;
+            assert_eq!(elem.value().await?.unwrap(), "👍");
+        }
+        Ok(())
+    })
+}
+
 #[rstest]
 fn element_send_keys(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -173,6 +258,34 @@ fn element_clear(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn element_clear_strict(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        elem.send_keys("thirtyfour").await?;
+
+        c.execute("arguments[0].readOnly = true;", vec![elem.to_json()?]).await?;
+        assert_matches!(elem.clear_strict().await, Err(WebDriverError::InvalidElementState(_)));
+        assert_eq!(elem.value().await?.unwrap(), "thirtyfour");
+
+        c.execute(
+            "arguments[0].readOnly = false; arguments[0].disabled = true;",
+            vec![elem.to_json()?],
+        )
+        .await?;
+        assert_matches!(elem.clear_strict().await, Err(WebDriverError::InvalidElementState(_)));
+        assert_eq!(elem.value().await?.unwrap(), "thirtyfour");
+
+        c.execute("arguments[0].disabled = false;", vec![elem.to_json()?]).await?;
+        elem.clear_strict().await?;
+        assert_eq!(elem.value().await?.unwrap(), "");
+        Ok(())
+    })
+}
+
 #[rstest]
 fn serialize_element(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -203,6 +316,50 @@ fn serialize_element(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn element_location_in_view(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        let (x, y) = elem.location_in_view().await?;
+
+        let (width, height): (i64, i64) = c
+            .execute("return [window.innerWidth, window.innerHeight];", vec![])
+            .await?
+            .convert()?;
+        assert!(x >= 0 && x < width, "x should be within the viewport");
+        assert!(y >= 0 && y < height, "y should be within the viewport");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_execute(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let elem = c.find(By::Id("navigation")).await?;
+
+        let height: i64 =
+            elem.execute("return arguments[0].scrollHeight;", vec![]).await?.convert()?;
+        let expected: i64 = c
+            .execute("return document.getElementById('navigation').scrollHeight;", vec![])
+            .await?
+            .convert()?;
+        assert_eq!(height, expected);
+
+        // Extra args start at arguments[1], and elements can be returned.
+        let ret = elem
+            .execute("return arguments[0].querySelector(arguments[1]);", vec!["a".into()])
+            .await?;
+        assert_eq!(ret.element()?.id().await?.unwrap(), "other_page_id");
+        Ok(())
+    })
+}
+
 #[rstest]
 fn element_screenshot(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -219,6 +376,66 @@ fn element_screenshot(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[cfg(feature = "image")]
+#[rstest]
+fn element_screenshot_matches(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let elem = c.find(By::Id("select1")).await?;
+        let baseline = image::load_from_memory(&elem.screenshot_as_png().await?)?;
+        let result = elem.screenshot_matches(&baseline, 0.0).await?;
+        assert!(result.matches, "{} pixels differ", result.diff_pixels);
+        assert_eq!(result.diff_pixels, 0);
+
+        // A screenshot of the whole window is a different size, which is a non-match.
+        let window = image::load_from_memory(&c.screenshot_as_png().await?)?;
+        let result = elem.screenshot_matches(&window, 1.0).await?;
+        assert!(!result.matches);
+        assert_eq!(result.diff_image.width(), window.width().max(result.size.0));
+        assert!(result.diff_pixels > 0);
+        assert_ne!(result.size, result.baseline_size);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_canvas_as_png(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        let canvas = c.find(By::Id("canvas")).await?;
+
+        let png = canvas.canvas_as_png().await?;
+        assert!(png.starts_with(b"\x89PNG"));
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!((width, height), (40, 30));
+
+        let not_canvas = c.find(By::Id("not-canvas")).await?;
+        assert_matches!(not_canvas.canvas_as_png().await, Err(WebDriverError::InvalidArgument(_)));
+        Ok(())
+    })
+}
+
+#[cfg(feature = "image")]
+#[rstest]
+fn element_canvas_as_image(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        let canvas = c.find(By::Id("canvas")).await?;
+
+        let image = canvas.canvas_as_image().await?.to_rgba8();
+        assert_eq!(image.dimensions(), (40, 30));
+        assert_eq!(image.get_pixel(5, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(30, 20).0[3], 0);
+        Ok(())
+    })
+}
+
 #[rstest]
 fn element_focus(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -244,6 +461,16 @@ fn element_html(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
 
         let elem = c.find(By::Id("text-output")).await?;
         assert_eq!(elem.outer_html().await?, r#"<div id="text-output"></div>"#);
+
+        // Nested markup is returned as serialized by the browser.
+        let elem = c.find(By::Id("class-section")).await?;
+        let inner = elem.inner_html().await?;
+        assert!(inner.contains(r#"<span id="class-active" class="item active">Active</span>"#));
+        assert!(!inner.contains("class-section"));
+        let outer = elem.outer_html().await?;
+        assert!(outer.starts_with(r#"<div id="class-section">"#));
+        assert!(outer.ends_with("</div>"));
+        assert!(outer.contains(&inner));
         Ok(())
     })
 }
@@ -260,3 +487,449 @@ fn element_get_parent(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn element_dom_traversal(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.execute(
+            r#"
+            let tree = document.createElement("div");
+            tree.id = "tree";
+            tree.style.position = "relative";
+            tree.innerHTML = '<span id="first"></span> text <span id="middle"><b id="leaf"></b></span>'
+                + '<!-- comment --><span id="last"></span>';
+            document.body.appendChild(tree);
+            "#,
+            Vec::new(),
+        )
+        .await?;
+
+        let tree = c.find(By::Id("tree")).await?;
+        let mut ids = Vec::new();
+        for child in tree.children().await? {
+            ids.push(child.id().await?.unwrap_or_default());
+        }
+        assert_eq!(ids, vec!["first", "middle", "last"]);
+
+        let middle = c.find(By::Id("middle")).await?;
+        let first = middle.previous_sibling().await?.expect("previous sibling");
+        assert_eq!(first.id().await?.as_deref(), Some("first"));
+        let last = middle.next_sibling().await?.expect("next sibling");
+        assert_eq!(last.id().await?.as_deref(), Some("last"));
+        assert!(first.previous_sibling().await?.is_none());
+        assert!(last.next_sibling().await?.is_none());
+
+        let leaf = c.find(By::Id("leaf")).await?;
+        assert!(leaf.children().await?.is_empty());
+        let parent = leaf.parent_element().await?.expect("parent");
+        assert_eq!(parent.id().await?.as_deref(), Some("middle"));
+        let offset_parent = leaf.offset_parent().await?.expect("offset parent");
+        assert_eq!(offset_parent.id().await?.as_deref(), Some("tree"));
+
+        let root = c.find(By::Tag("html")).await?;
+        assert!(root.parent_element().await?.is_none());
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_send_keys_slowly(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        // Simulate an input that drops keystrokes arriving less than 30ms after the
+        // previous one, like a debounced autocomplete that cannot keep up.
+        c.execute(
+            r#"
+            let input = document.getElementById("text-input");
+            window.keystrokes = 0;
+            window.lastKey = 0;
+            input.addEventListener("beforeinput", (e) => {
+                let now = performance.now();
+                if (now - window.lastKey < 30) {
+                    e.preventDefault();
+                    return;
+                }
+                window.lastKey = now;
+                window.keystrokes++;
+            });
+            "#,
+            Vec::new(),
+        )
+        .await?;
+
+        // Typing at full speed loses characters.
+        let elem = c.find(By::Id("text-input")).await?;
+        elem.clear().await?;
+        elem.send_keys("hello").await?;
+        let fast = elem.value().await?.unwrap_or_default();
+        assert!(fast.len() < 5, "expected dropped characters, got {fast:?}");
+
+        // The slow path keeps every character.
+        elem.clear().await?;
+        c.execute("window.keystrokes = 0; window.lastKey = 0;", Vec::new()).await?;
+        elem.send_keys_slowly("hello", Duration::from_millis(50)).await?;
+        assert_eq!(elem.value().await?.as_deref(), Some("hello"));
+        let keystrokes: u32 =
+            c.execute("return window.keystrokes;", Vec::new()).await?.convert()?;
+        assert_eq!(keystrokes, 5);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_find_with_timeout(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let section = c.find(By::Id("section-text")).await?;
+        c.execute(
+            r#"
+            setTimeout(() => {
+                let elem = document.createElement("span");
+                elem.id = "delayed-child";
+                arguments[0].appendChild(elem);
+            }, 500);
+            "#,
+            vec![section.to_json()?],
+        )
+        .await?;
+        let child =
+            section.find_with_timeout(By::Id("delayed-child"), Duration::from_secs(5)).await?;
+        assert_eq!(child.id().await?, Some("delayed-child".to_string()));
+
+        let missing =
+            section.find_with_timeout(By::Id("not-here"), Duration::from_millis(500)).await;
+        assert_matches!(missing, Err(WebDriverError::NoSuchElement(_)));
+
+        // A stale parent fails immediately rather than waiting for the timeout.
+        c.execute("arguments[0].remove();", vec![section.to_json()?]).await?;
+        let stale = section.find_with_timeout(By::Tag("button"), Duration::from_secs(30)).await;
+        assert_matches!(stale, Err(WebDriverError::StaleElementReference(_)));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_wait_until_text(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let output = c.find(By::Id("text-output")).await?;
+        c.execute(
+            r#"setTimeout(() => { arguments[0].innerText = "Status: Done"; }, 500);"#,
+            vec![output.to_json()?],
+        )
+        .await?;
+        output.wait_until_text_contains("Done", Duration::from_secs(5)).await?;
+        output.wait_until_text_is("Status: Done", Duration::from_secs(5)).await?;
+
+        let result = output.wait_until_text_is("Pending", Duration::from_millis(500)).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("Status: Done")),
+            x => panic!("expected timeout error, got {x:?}"),
+        }
+
+        // A stale element fails immediately rather than waiting for the timeout.
+        c.execute("arguments[0].remove();", vec![output.to_json()?]).await?;
+        let stale = output.wait_until_text_is("Done", Duration::from_secs(30)).await;
+        assert_matches!(stale, Err(WebDriverError::StaleElementReference(_)));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_wait_until_attribute(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let section = c.find(By::Id("section-text")).await?;
+        c.execute(
+            r#"
+            let elem = arguments[0];
+            elem.setAttribute("aria-busy", "true");
+            setTimeout(() => elem.setAttribute("aria-busy", "false"), 300);
+            setTimeout(() => elem.removeAttribute("aria-busy"), 600);
+            "#,
+            vec![section.to_json()?],
+        )
+        .await?;
+        section.wait_until_attribute_is("aria-busy", "false", Duration::from_secs(5)).await?;
+        section.wait_until_attribute_absent("aria-busy", Duration::from_secs(5)).await?;
+
+        let result =
+            section.wait_until_attribute_is("id", "other", Duration::from_millis(500)).await;
+        assert_matches!(result, Err(WebDriverError::Timeout(msg)) if msg.contains("section-text"));
+        let result = section.wait_until_attribute_absent("id", Duration::from_millis(500)).await;
+        assert_matches!(result, Err(WebDriverError::Timeout(_)));
+
+        // A stale element fails immediately rather than waiting for the timeout.
+        c.execute("arguments[0].remove();", vec![section.to_json()?]).await?;
+        let stale = section.wait_until_attribute_absent("id", Duration::from_secs(30)).await;
+        assert_matches!(stale, Err(WebDriverError::StaleElementReference(_)));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_is_in_viewport(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let elem = c.find(By::Id("footer")).await?;
+        assert!(elem.is_in_viewport().await?);
+
+        // Move the element well below the fold.
+        c.execute(
+            r#"
+            let spacer = document.createElement("div");
+            spacer.style.height = (window.innerHeight * 3) + "px";
+            document.body.appendChild(spacer);
+            let target = document.createElement("div");
+            target.id = "below-fold";
+            target.style.height = "100px";
+            document.body.appendChild(target);
+            "#,
+            Vec::new(),
+        )
+        .await?;
+        let below = c.find(By::Id("below-fold")).await?;
+        assert!(!below.is_in_viewport().await?);
+
+        below.scroll_into_view().await?;
+        assert!(below.is_in_viewport().await?);
+
+        // Partially visible counts as in the viewport.
+        c.execute(
+            "window.scrollTo(0, arguments[0].offsetTop - window.innerHeight + 10);",
+            vec![below.to_json()?],
+        )
+        .await?;
+        assert!(below.is_in_viewport().await?);
+
+        c.execute("window.scrollTo(0, 0);", Vec::new()).await?;
+        assert!(!below.is_in_viewport().await?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_has_class_and_tag_is(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let active = c.find(By::Id("class-active")).await?;
+        assert!(active.has_class("item").await?);
+        assert!(active.has_class("active").await?);
+        assert!(!active.has_class("inactive").await?);
+        assert!(!active.has_class("act").await?);
+
+        let inactive = c.find(By::Id("class-inactive")).await?;
+        assert!(inactive.has_class("item").await?);
+        assert!(inactive.has_class("inactive").await?);
+        assert!(!inactive.has_class("active").await?);
+
+        assert!(active.tag_is("span").await?);
+        assert!(active.tag_is("SPAN").await?);
+        assert!(!active.tag_is("div").await?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn find_with_str_shortcuts(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let by_str = c.find("#class-section span.active").await?;
+        let by_css = c.find_css("#class-section span.active").await?;
+        let by_xpath = c.find_xpath("//span[@id='class-active']").await?;
+        assert_eq!(by_str.element_id(), by_css.element_id());
+        assert_eq!(by_str.element_id(), by_xpath.element_id());
+
+        let section = c.find("#class-section").await?;
+        assert_eq!(section.find_all("span").await?.len(), 2);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_reference_round_trip(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let elem = c.find(By::Id("class-active")).await?;
+        let stored = serde_json::to_string(&elem)?;
+
+        let mut de = serde_json::Deserializer::from_str(&stored);
+        let restored = WebElementSeed::new(c.handle.clone()).deserialize(&mut de)?;
+        assert_eq!(restored, elem);
+        let ret =
+            c.execute("return arguments[0].id;", vec![serde_json::to_value(&restored)?]).await?;
+        assert_eq!(ret.convert::<String>()?, "class-active");
+
+        let by_id = WebElement::from_reference(c.handle.clone(), elem.element_id().to_string());
+        assert_eq!(by_id.text().await?, "Active");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_relative_layout(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&layout_url()).await?;
+        let container = c.find(By::Id("container")).await?;
+        let icon = c.find(By::Id("icon")).await?;
+        let label = c.find(By::Id("label")).await?;
+        let footer = c.find(By::Id("footer")).await?;
+
+        let r = label.relative_rect(&container).await?;
+        assert_eq!((r.x, r.y, r.width, r.height), (40.0, 10.0, 50.0, 20.0));
+        let r = container.relative_rect(&footer).await?;
+        assert_eq!((r.x, r.y), (-10.0, -150.0));
+
+        assert!(icon.is_left_of(&label).await?);
+        assert!(!label.is_left_of(&icon).await?);
+        assert!(!icon.is_left_of(&footer).await?);
+        assert!(icon.is_above(&footer).await?);
+        assert!(!footer.is_above(&label).await?);
+        assert!(!icon.is_above(&label).await?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_wait_until_stale(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let elem = c.find(By::Id("button-copy")).await?;
+
+        let result = elem.wait_until_stale(Duration::from_millis(500)).await;
+        assert_matches!(result, Err(WebDriverError::Timeout(_)));
+
+        c.execute("setTimeout(() => arguments[0].remove(), 500);", vec![elem.to_json()?]).await?;
+        elem.wait_until_stale(Duration::from_secs(5)).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_set_value(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&controlled_input_url()).await?;
+        let input = c.find(By::Id("name")).await?;
+        let state = c.find(By::Id("state")).await?;
+        let events = c.find(By::Id("events")).await?;
+
+        // Setting the value directly is ignored by the controlled input, even with an event.
+        input.execute("arguments[0].value = 'ignored';", Vec::new()).await?;
+        input.dispatch_event("input").await?;
+        assert_eq!(state.text().await?, "");
+
+        input.set_value("thirtyfour").await?;
+        assert_eq!(state.text().await?, "thirtyfour");
+        assert_eq!(input.value().await?, Some("thirtyfour".to_string()));
+
+        input.dispatch_event("blur").await?;
+        assert_eq!(events.text().await?, "input,input,change,blur");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_type_replacing(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&prefilled_input_url()).await?;
+        let input = c.find(By::Id("name")).await?;
+
+        // send_keys() appends to the existing text.
+        input.send_keys("!").await?;
+        assert_eq!(input.value().await?, Some("old text!".to_string()));
+
+        input.type_replacing("thirtyfour").await?;
+        assert_eq!(input.value().await?, Some("thirtyfour".to_string()));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_wait_and_click(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&delayed_enable_url()).await?;
+        let button = c.find(By::Id("submit")).await?;
+        assert!(!button.is_enabled().await?);
+
+        button.wait_and_click(Duration::from_secs(10)).await?;
+        assert_eq!(c.find(By::Id("result")).await?.text().await?, "clicked");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_equality_across_scripts(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let script = "return document.getElementById('select1');";
+        let first = c.execute(script, Vec::new()).await?.element()?;
+        let second = c.execute("return document.querySelector('#select1');", Vec::new()).await?;
+        assert_eq!(first, second.element()?);
+        assert_eq!(first, c.find(By::Id("select1")).await?);
+        assert_ne!(first, c.find(By::Id("select2")).await?);
+
+        // After reloading, the page contains a different element.
+        c.refresh().await?;
+        assert_ne!(first, c.execute(script, Vec::new()).await?.element()?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_focus_and_blur(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&validation_url()).await?;
+        let input = c.find(By::Id("email")).await?;
+        let message = c.find(By::Id("email-error")).await?;
+        assert_eq!(message.text().await?, "");
+
+        input.focus().await?;
+        assert_eq!(c.active_element().await?, input);
+        assert_eq!(message.text().await?, "");
+
+        input.blur().await?;
+        assert_ne!(c.active_element().await?, input);
+        assert_eq!(message.text().await?, "Email is required");
+
+        input.send_keys("user@example.com").await?;
+        input.blur().await?;
+        assert_eq!(message.text().await?, "");
+        Ok(())
+    })
+}
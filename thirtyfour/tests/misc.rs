@@ -1,8 +1,10 @@
 //! Tests that don't make use of external websites.
 use std::time::Duration;
 
+use assert_matches::assert_matches;
 use rstest::rstest;
-use thirtyfour::{prelude::*, support::block_on, SameSite};
+use thirtyfour::common::config::WebDriverConfig;
+use thirtyfour::{prelude::*, support::block_on, PermissionState, SameSite};
 
 use crate::common::*;
 
@@ -35,6 +37,90 @@ fn resolve_execute_async_value(test_harness: TestHarness<'_>) -> WebDriverResult
     })
 }
 
+#[rstest]
+fn execute_javascript_error(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let result = c.execute(r#"throw new Error("Boom");"#, Vec::new()).await;
+        match result {
+            Err(WebDriverError::JavaScript {
+                message,
+                ..
+            }) => assert_eq!(message, "Boom"),
+            x => panic!("expected javascript error, got {x:?}"),
+        }
+        Ok(())
+    })
+}
+
+#[rstest]
+fn execute_with_args(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        #[derive(serde::Serialize)]
+        struct Args {
+            elem: WebElement,
+            text: &'static str,
+        }
+
+        let elem = c.find(By::Id("text-output")).await?;
+        let args = Args {
+            elem: elem.clone(),
+            text: "TESTING",
+        };
+        c.execute_with("arguments[0].innerText = arguments[1];", args).await?;
+        assert_eq!(elem.text().await?, "TESTING");
+
+        let sum: u64 =
+            c.execute_with("return arguments[0] + arguments[1];", (1, 2)).await?.convert()?;
+        assert_eq!(sum, 3);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn reconnect(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    let server_url = webdriver_url(test_harness.browser());
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let driver = WebDriver::reconnect(server_url.clone(), c.session_id().clone()).await?;
+        assert_eq!(driver.title().await?, c.title().await?);
+
+        let result = WebDriver::reconnect(server_url, "not-a-real-session").await;
+        assert!(result.is_err(), "reconnecting to a missing session should fail");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn wait_until_url_and_title(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.find(By::Id("other_page_id")).await?.click().await?;
+
+        c.wait_until_url_contains("other_page.html", Duration::from_secs(5)).await?;
+        c.wait_until_title_is("Other Page", Duration::from_secs(5)).await?;
+
+        let result = c.wait_until_title_is("Not The Title", Duration::from_millis(500)).await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.contains("Other Page")),
+            x => panic!("expected timeout error, got {x:?}"),
+        }
+        Ok(())
+    })
+}
+
 #[rstest]
 fn status(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -141,10 +227,13 @@ fn test_cookies(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
 
         // Verify that the cookie exists.
         assert_eq!(c.get_named_cookie(&cookie.name).await?.value, cookie.value);
+        let cookie_opt = c.get_named_cookie_opt(&cookie.name).await?;
+        assert_eq!(cookie_opt.map(|x| x.value), Some(cookie.value.clone()));
 
         // Delete the cookie and make sure it's gone
         c.delete_cookie(&cookie.name).await?;
         assert!(c.get_named_cookie(&cookie.name).await.is_err());
+        assert!(c.get_named_cookie_opt(&cookie.name).await?.is_none());
 
         c.delete_all_cookies().await?;
         let cookies = c.get_all_cookies().await?;
@@ -153,3 +242,302 @@ fn test_cookies(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn set_permission(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        match c.set_permission("notifications", PermissionState::Granted).await {
+            Ok(()) => {}
+            // Not every browser supports setting permissions.
+            Err(WebDriverError::UnsupportedOperation(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        let state: String = c
+            .execute_async(
+                r#"
+                let done = arguments[0];
+                navigator.permissions.query({ name: "notifications" }).then(p => done(p.state));
+                "#,
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+        assert_eq!(state, "granted");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn clear_storage_on_origin_change(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        // Same test page, but a different origin.
+        let alt_url = sample_page_url().replace("localhost", "127.0.0.1");
+        c.goto(&alt_url).await?;
+        c.local_storage().set("key", "value").await?;
+
+        let config = WebDriverConfig::builder().clear_storage_on_origin_change(true).build()?;
+        let driver = c.clone_with_config(config);
+
+        driver.goto(&sample_page_url()).await?;
+        driver.local_storage().set("key", "value").await?;
+
+        // Navigating within the same origin keeps storage.
+        driver.goto(&other_page_url()).await?;
+        assert_eq!(driver.local_storage().get("key").await?, Some("value".to_string()));
+
+        // Changing origin clears storage for the new origin.
+        driver.goto(&alt_url).await?;
+        assert_eq!(driver.local_storage().get("key").await?, None);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn execute_stream(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let items = c
+            .execute_stream::<u64>("return Array.from({ length: 10000 }, (_, i) => i);", Vec::new())
+            .await?;
+        let mut count = 0;
+        for (i, item) in items.enumerate() {
+            assert_eq!(item?, i as u64);
+            count += 1;
+        }
+        assert_eq!(count, 10000);
+
+        let not_array = c.execute_stream::<u64>("return 1;", Vec::new()).await;
+        assert!(matches!(not_array, Err(WebDriverError::Json(_))));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn set_user_agent(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        if test_harness.browser() == "firefox" {
+            assert_matches!(
+                c.set_user_agent("thirtyfour-test/1.0").await,
+                Err(WebDriverError::UnsupportedOperation(_))
+            );
+            return Ok(());
+        }
+
+        c.set_user_agent("thirtyfour-test/1.0").await?;
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let user_agent: String =
+            c.execute("return navigator.userAgent;", Vec::new()).await?.convert()?;
+        assert_eq!(user_agent, "thirtyfour-test/1.0");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn cookies_matching(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.delete_all_cookies().await?;
+        for (name, value) in [("first", "1"), ("second", "2"), ("third", "3")] {
+            let mut cookie = Cookie::new(name, value);
+            cookie.set_path("/");
+            c.add_cookie(cookie).await?;
+        }
+
+        assert!(c.cookie_exists("second").await?);
+        assert!(!c.cookie_exists("fourth").await?);
+
+        let mut names: Vec<_> = c
+            .cookies_matching(|cookie| cookie.value != "2")
+            .await?
+            .into_iter()
+            .map(|cookie| cookie.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["first", "third"]);
+        assert!(c.cookies_matching(|cookie| cookie.name == "fourth").await?.is_empty());
+
+        c.delete_all_cookies().await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn add_cookie_for_current(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.add_cookie_for_current(Cookie::new("currenttest", "thirtyfour")).await?;
+
+        let cookie = c.get_named_cookie("currenttest").await?;
+        assert_eq!(cookie.value, "thirtyfour");
+        assert_eq!(cookie.domain.as_deref().map(|d| d.trim_start_matches('.')), Some("localhost"));
+        assert_eq!(cookie.path.as_deref(), Some("/"));
+        c.delete_cookie("currenttest").await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn js_cookies(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.add_cookie_for_current(Cookie::new("fromdriver", "thirtyfour")).await?;
+        c.execute("document.cookie = 'fromjs=a=b; path=/';", Vec::new()).await?;
+
+        let cookies = c.js_cookies().await?;
+        assert_eq!(cookies.get("fromdriver").map(String::as_str), Some("thirtyfour"));
+        assert_eq!(cookies.get("fromjs").map(String::as_str), Some("a=b"));
+        assert_eq!(c.get_named_cookie("fromjs").await?.value, "a=b");
+
+        c.delete_all_cookies().await?;
+        assert!(c.js_cookies().await?.is_empty());
+        Ok(())
+    })
+}
+
+#[rstest]
+fn reset(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let window = c.window().await?;
+        c.add_cookie(Cookie::new("resettest", "thirtyfour")).await?;
+        c.local_storage().set("token", "abc123").await?;
+        c.new_tab().await?;
+        c.new_tab().await?;
+        assert_eq!(c.windows().await?.len(), 3);
+
+        c.reset().await?;
+        assert_eq!(c.windows().await?, vec![window]);
+        assert_eq!(c.current_url().await?.as_str(), "about:blank");
+
+        c.goto(&url).await?;
+        assert!(c.get_named_cookie_opt("resettest").await?.is_none());
+        assert_eq!(c.local_storage().get("token").await?, None);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn navigation_timing(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let timing = c.navigation_timing().await?;
+        assert_eq!(timing.url, url);
+        assert_eq!(timing.navigation_type, "navigate");
+        assert!(timing.response_end >= timing.request_start);
+        assert!(timing.dom_content_loaded_event_end >= timing.response_end);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn clipboard(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        if test_harness.browser() != "chrome" {
+            // Clipboard permissions cannot be granted without CDP, so the result depends
+            // on the browser configuration.
+            return Ok(());
+        }
+
+        c.write_clipboard("thirtyfour clipboard").await?;
+        assert_eq!(c.read_clipboard().await?, "thirtyfour clipboard");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn set_timezone(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        if test_harness.browser() != "chrome" {
+            let result = c.set_timezone("Asia/Tokyo").await;
+            assert!(matches!(result, Err(WebDriverError::UnsupportedOperation(_))));
+            return Ok(());
+        }
+
+        let script = "return Intl.DateTimeFormat().resolvedOptions().timeZone;";
+        for timezone in ["Asia/Tokyo", "America/New_York"] {
+            c.set_timezone(timezone).await?;
+            let resolved: String = c.execute(script, Vec::new()).await?.convert()?;
+            assert_eq!(resolved, timezone);
+        }
+
+        let result = c.set_timezone("Mars/Olympus_Mons").await;
+        assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+        c.set_timezone("").await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn execute_file(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_js/sum.js");
+        let sum: i64 = c.execute_file(&path, vec![2.into(), 3.into()]).await?.convert()?;
+        assert_eq!(sum, 5);
+
+        let result = c.execute_file(path.with_file_name("missing.js"), Vec::new()).await;
+        assert!(matches!(result, Err(WebDriverError::IoError(_))));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn wait_for_network_idle(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = network_url();
+        c.goto(&url).await?;
+        c.wait_for_network_idle(Duration::ZERO, Duration::from_secs(10)).await?;
+        c.find(By::Id("load")).await?.click().await?;
+        c.wait_for_network_idle(Duration::from_millis(500), Duration::from_secs(10)).await?;
+        let result = c.find(By::Id("result")).await?.text().await?;
+        assert_eq!(result, "loaded");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn wait_for_network_idle_in_flight(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    // Only the performance log sees requests that started before the first call.
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&network_inflight_url()).await?;
+        c.wait_for_network_idle(Duration::from_millis(200), Duration::from_secs(10)).await?;
+        let result = c.find(By::Id("result")).await?.text().await?;
+        assert_eq!(result, "loaded");
+        Ok(())
+    })
+}
@@ -1,7 +1,9 @@
 use assert_matches::assert_matches;
+use futures::TryStreamExt;
 use rstest::rstest;
 use std::time::Duration;
 use thirtyfour::components::{ElementResolverMulti, ElementResolverSingle};
+use thirtyfour::stringmatch::StringMatch;
 use thirtyfour::support::block_on;
 use thirtyfour::{components::SelectElement, prelude::*};
 
@@ -172,6 +174,102 @@ fn query_exists(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn driver_wait(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let wait = c.wait(Duration::from_secs(1)).interval(Duration::from_millis(200));
+        let elem = wait.until_present(By::Css("nav a")).await?;
+        assert_eq!(elem.id().await?.unwrap(), "other_page_id");
+        let elems = wait.until_all_present(By::Css("nav a")).await?;
+        assert_eq!(elems.len(), 2);
+        let elem = wait.until_displayed(By::Id("footer")).await?;
+        assert_eq!(elem.id().await?.unwrap(), "footer");
+
+        let result = wait.until_displayed(By::Id("checkbox-hidden")).await;
+        assert_matches!(result, Err(WebDriverError::NoSuchElement(_)));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn query_with_attribute(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        c.execute(
+            r#"
+            setTimeout(() => {
+                let elem = document.getElementById("text-input2");
+                elem.setAttribute("data-state", "Ready");
+                elem.value = "done";
+            }, 500);
+            "#,
+            Vec::new(),
+        )
+        .await?;
+
+        let elem = c
+            .query(By::Id("text-input2"))
+            .with_attribute("data-state", "Ready")
+            .with_property("value", "done")
+            .first()
+            .await?;
+        assert_eq!(elem.id().await?.unwrap(), "text-input2");
+
+        // Values are compared case-sensitively unless requested otherwise.
+        let result = c
+            .query(By::Id("text-input2"))
+            .wait(Duration::from_millis(500), Duration::from_millis(100))
+            .with_attribute("data-state", "ready")
+            .first()
+            .await;
+        assert_matches!(result, Err(WebDriverError::NoSuchElement(_)));
+        c.query(By::Id("text-input2"))
+            .with_attribute("data-state", StringMatch::new("ready").case_insensitive())
+            .first()
+            .await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn paginate(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = pagination_url();
+        c.goto(&url).await?;
+
+        let pages: Vec<Vec<String>> = c
+            .paginate(By::Css("#rows tr"), By::Id("next-page"), 10, |rows| async move {
+                let mut texts = Vec::new();
+                for row in rows {
+                    texts.push(row.text().await?);
+                }
+                Ok(texts)
+            })
+            .try_collect()
+            .await?;
+        assert_eq!(pages, vec![vec!["A1", "A2"], vec!["B1", "B2"], vec!["C1"]]);
+
+        // Limit the number of pages.
+        c.goto(&url).await?;
+        let pages: Vec<usize> =
+            c.paginate(By::Css("#rows tr"), By::Id("next-page"), 2, |rows| async move {
+                Ok(rows.len())
+            })
+            .try_collect()
+            .await?;
+        assert_eq!(pages, vec![2, 2]);
+        Ok(())
+    })
+}
+
 #[rstest]
 fn resolve(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -269,6 +367,26 @@ fn select_by_index(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn query_first_select(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let select_element = c.query(By::Css("#select1")).first_select().await?;
+        select_element.select_by_index(1).await?;
+        let value = c.find(By::Css("#select1")).await?.prop("value").await?;
+        assert_eq!(Some("Select1-Option2".into()), value);
+
+        let result = c.query(By::Id("footer")).first_select().await;
+        assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+        let result = c.query(By::Id("missing")).nowait().first_select().await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchElement(_))));
+        Ok(())
+    })
+}
+
 #[rstest]
 fn select_by_label(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -319,3 +437,40 @@ fn find_element_from_element(test_harness: TestHarness<'_>) -> WebDriverResult<(
         Ok(())
     })
 }
+
+#[rstest]
+fn query_until_count(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = pagination_url();
+        c.goto(&url).await?;
+        c.execute(
+            r#"
+            let rows = document.getElementById("rows");
+            [200, 400].forEach((delay, i) => setTimeout(() => {
+                let row = document.createElement("tr");
+                row.textContent = "late" + i;
+                rows.appendChild(row);
+            }, delay));
+            "#,
+            Vec::new(),
+        )
+        .await?;
+
+        let rows = c.query(By::Css("#rows tr")).until_count_at_least(4).await?;
+        assert_eq!(rows.len(), 4);
+        assert_eq!(c.query(By::Css("#rows tr")).until_count(4).await?.len(), 4);
+
+        let rows = c.find(By::Id("rows")).await?;
+        let result = rows
+            .query(By::Tag("tr"))
+            .wait(Duration::from_millis(500), Duration::from_millis(100))
+            .until_count(5)
+            .await;
+        match result {
+            Err(WebDriverError::Timeout(msg)) => assert!(msg.ends_with("found 4"), "{msg}"),
+            x => panic!("expected timeout, got {x:?}"),
+        }
+        Ok(())
+    })
+}
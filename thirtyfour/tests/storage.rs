@@ -0,0 +1,58 @@
+//! Tests for localStorage and sessionStorage.
+
+use rstest::rstest;
+use thirtyfour::{prelude::*, support::block_on, WebStorage};
+
+use crate::common::*;
+
+mod common;
+
+async fn round_trip(storage: WebStorage) -> WebDriverResult<()> {
+    storage.clear().await?;
+    assert_eq!(storage.len().await?, 0);
+    assert!(storage.is_empty().await?);
+    assert_eq!(storage.get("missing").await?, None);
+
+    storage.set("token", "abc123").await?;
+    storage.set("user", "ferris").await?;
+    assert_eq!(storage.get("token").await?, Some("abc123".to_string()));
+    assert_eq!(storage.len().await?, 2);
+
+    let mut keys = storage.keys().await?;
+    keys.sort();
+    assert_eq!(keys, vec!["token".to_string(), "user".to_string()]);
+
+    storage.remove("token").await?;
+    assert_eq!(storage.get("token").await?, None);
+    assert_eq!(storage.len().await?, 1);
+
+    storage.clear().await?;
+    assert!(storage.keys().await?.is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn local_storage(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        round_trip(c.local_storage()).await
+    })
+}
+
+#[rstest]
+fn session_storage(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        round_trip(c.session_storage()).await?;
+
+        // sessionStorage and localStorage are independent.
+        c.session_storage().set("only-session", "1").await?;
+        assert_eq!(c.local_storage().get("only-session").await?, None);
+        c.session_storage().clear().await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,2 @@
+// Used by the execute_file test.
+return arguments[0] + arguments[1];
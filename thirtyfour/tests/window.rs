@@ -1,6 +1,7 @@
 use common::*;
 use rstest::rstest;
-use thirtyfour::{prelude::*, support::block_on};
+use std::time::Duration;
+use thirtyfour::{prelude::*, support::block_on, FrameTarget, PrintParameters};
 
 mod common;
 
@@ -36,6 +37,35 @@ fn iframe_switch(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn switch_to_builder(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        c.find(By::Css("#iframe_page_id")).await?.click().await?;
+
+        let iframe_element = c.find(By::Id("iframe")).await?;
+        c.switch_to().frame_element(&iframe_element).await?;
+        c.find(By::Id("iframe_button")).await?;
+        c.switch_to().parent_frame().await?;
+        c.find(By::Id("root_button")).await?;
+
+        c.switch_to().frame_index(0).await?;
+        c.find(By::Id("iframe_button")).await?;
+        c.switch_to().default_content().await?;
+        c.find(By::Id("root_button")).await?;
+
+        let window = c.window().await?;
+        let tab = c.switch_to().new_tab().await?;
+        c.switch_to().window(tab.clone()).await?;
+        assert_eq!(c.window().await?, tab);
+        c.close_window().await?;
+        c.switch_to().window(window).await?;
+        Ok(())
+    })
+}
+
 #[rstest]
 fn new_window(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -109,6 +139,31 @@ fn new_tab_switch(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn tabs(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let window_1 = c.window().await?;
+        c.new_tab().await?;
+
+        let tabs = c.tabs().await?;
+        assert_eq!(tabs.len(), 2);
+        let tab_1 = tabs.iter().find(|t| t.handle == window_1).expect("current tab is listed");
+        if test_harness.browser() == "chrome" {
+            assert_eq!(tab_1.url.as_deref(), Some(url.as_str()));
+            assert_eq!(tab_1.target_type.as_deref(), Some("page"));
+        } else {
+            assert!(tab_1.url.is_none());
+        }
+
+        // The current tab is unchanged.
+        assert_eq!(c.window().await?, window_1);
+        Ok(())
+    })
+}
+
 #[rstest]
 fn close_window(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     block_on(async {
@@ -213,8 +268,9 @@ fn in_new_tab(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
 fn window_rect(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
     block_on(async {
         let c = test_harness.driver();
-        c.set_window_rect(20, 20, 1900, 1000).await?;
+        let set_rect = c.set_window_rect(20, 20, 1900, 1000).await?;
         let r = c.get_window_rect().await?;
+        assert_eq!(set_rect, r);
 
         // On Mac OS, the window position doesn't seem to be returned correctly.
         if !cfg!(target_os = "macos") {
@@ -245,3 +301,178 @@ fn screenshot(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[cfg(feature = "image")]
+#[rstest]
+fn screenshot_region(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let png = c.screenshot_region(10, 10, 100, 50).await?;
+        assert!(png.starts_with(b"\x89PNG"));
+        let ratio: f64 =
+            c.execute("return window.devicePixelRatio;", Vec::new()).await?.convert()?;
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        assert_eq!(width, (100.0 * ratio).round() as u32);
+
+        let result = c.screenshot_region(0, 0, 100_000, 10).await;
+        assert!(matches!(result, Err(WebDriverError::InvalidArgument(_))));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn print_page_to_file(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+
+        let dir = std::env::temp_dir().join(format!("thirtyfour-print-{}", std::process::id()));
+        let path = dir.join("pdf").join("sample_page.pdf");
+        c.print_page_to_file(&path, PrintParameters::default()).await?;
+        let pdf = std::fs::read(&path)?;
+        assert!(pdf.starts_with(b"%PDF"));
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn switch_to_frame_by_name(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&named_frames_url()).await?;
+
+        // The name takes priority over the id, and the first matching frame is chosen.
+        c.switch_to_frame_by_name("inner").await?;
+        c.find(By::Id("iframe_button")).await?;
+        c.enter_parent_frame().await?;
+        c.find(By::Id("root_button")).await?;
+
+        // Falls back to the id if no frame has a matching name.
+        c.switch_to_frame_by_name("by-id").await?;
+        c.find(By::Id("iframe_button")).await?;
+        c.enter_default_frame().await?;
+
+        let missing = c.switch_to_frame_by_name("missing").await;
+        assert!(matches!(missing, Err(WebDriverError::NoSuchFrame(_))));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn frame_count_and_frames(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&named_frames_url()).await?;
+        assert_eq!(c.frame_count().await?, 4);
+
+        let frames = c.frames().await?;
+        assert_eq!(frames.len(), 4);
+
+        // The frames are in the same order as the indices used by enter_frame().
+        for (index, frame) in frames.iter().enumerate() {
+            let src = frame.attr("src").await?.unwrap_or_default();
+            c.enter_frame(index as u16).await?;
+            let path = c.execute("return location.pathname;", Vec::new()).await?;
+            assert!(path.json().as_str().unwrap_or_default().ends_with(&src));
+            c.enter_parent_frame().await?;
+        }
+
+        let mut buttons = 0;
+        for frame in frames {
+            frame.enter_frame().await?;
+            assert_eq!(c.frame_count().await?, 0);
+            buttons += c.find_all(By::Id("iframe_button")).await?.len();
+            c.enter_parent_frame().await?;
+        }
+        assert_eq!(buttons, 2);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn in_frame(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&named_frames_url()).await?;
+
+        let targets = [
+            FrameTarget::Index(1),
+            FrameTarget::Element(c.find(By::Id("by-id")).await?),
+            FrameTarget::Name("inner".to_string()),
+        ];
+        for target in targets {
+            let text = c
+                .in_frame(target, || async { c.find(By::Id("iframe_button")).await?.text().await })
+                .await?;
+            assert_eq!(text, "Just A Button");
+            c.find(By::Id("root_button")).await?;
+        }
+
+        // The default content is restored even if the closure fails.
+        let result = c
+            .in_frame(FrameTarget::Index(1), || async {
+                c.find(By::Id("missing")).await?;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchElement(_))));
+        c.find(By::Id("root_button")).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn query_anywhere(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&named_frames_url()).await?;
+
+        // The driver is left inside the frame containing the element.
+        let (button, path) = c.query_anywhere(By::Id("iframe_button")).await?;
+        assert_eq!(path, vec![1]);
+        assert_eq!(button.text().await?, "Just A Button");
+        assert!(c.find(By::Id("root_button")).await.is_err());
+
+        // The search starts from the top-level document.
+        let (_, path) = c.query_anywhere(By::Id("root_button")).await?;
+        assert!(path.is_empty());
+
+        c.enter_frame_path(&[3]).await?;
+        c.find(By::Id("iframe_button")).await?;
+
+        let result = c.query_anywhere(By::Id("missing")).await;
+        assert!(matches!(result, Err(WebDriverError::NoSuchElement(_))));
+        c.find(By::Id("root_button")).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn wait_for_new_window(test_harness: TestHarness<'_>) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let url = sample_page_url();
+        c.goto(&url).await?;
+        let main = c.window().await?;
+        let before = c.windows().await?;
+
+        let result = c.wait_for_new_window(&before, Duration::from_millis(500)).await;
+        assert!(matches!(result, Err(WebDriverError::Timeout(_))));
+
+        let script = "setTimeout(() => window.open(arguments[0]), 500);";
+        c.execute(script, vec![other_page_url().into()]).await?;
+        let popup = c.wait_for_new_window(&before, Duration::from_secs(10)).await?;
+        assert!(!before.contains(&popup));
+        assert_eq!(c.window().await?, main);
+
+        c.switch_to_window(popup).await?;
+        c.close_window().await?;
+        c.switch_to_window(main).await?;
+        Ok(())
+    })
+}